@@ -0,0 +1,2 @@
+-- Track the git worktree a task is being worked on in, if any
+ALTER TABLE tasks ADD COLUMN worktree_path TEXT;
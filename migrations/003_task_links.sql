@@ -0,0 +1,11 @@
+-- Labeled external links attached to tasks (design docs, specs, mockups)
+CREATE TABLE task_links (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    task_id INTEGER NOT NULL,
+    label TEXT NOT NULL,
+    url TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_links_task_id ON task_links(task_id);
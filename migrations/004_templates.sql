@@ -0,0 +1,8 @@
+-- Reusable task shells that can be applied to create new backlog tasks
+CREATE TABLE templates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    title TEXT NOT NULL,
+    description TEXT,
+    created_at TEXT NOT NULL
+);
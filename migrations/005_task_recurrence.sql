@@ -0,0 +1,2 @@
+-- Recurrence rule (daily, weekly, monthly, or <n>d / <n>w) for repeating tasks
+ALTER TABLE tasks ADD COLUMN recurrence TEXT;
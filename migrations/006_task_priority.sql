@@ -0,0 +1,2 @@
+-- Optional task priority: low, medium, high, critical
+ALTER TABLE tasks ADD COLUMN priority TEXT;
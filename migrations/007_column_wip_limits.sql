@@ -0,0 +1,5 @@
+-- Optional work-in-progress limit per column
+CREATE TABLE column_limits (
+    column_id INTEGER PRIMARY KEY REFERENCES columns(id) ON DELETE CASCADE,
+    wip_limit INTEGER NOT NULL
+);
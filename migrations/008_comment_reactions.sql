@@ -0,0 +1,12 @@
+-- Emoji reactions on comments, at most one of each emoji per author
+CREATE TABLE reactions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    comment_id INTEGER NOT NULL,
+    author TEXT NOT NULL,
+    emoji TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (comment_id) REFERENCES comments (id) ON DELETE CASCADE,
+    UNIQUE (comment_id, author, emoji)
+);
+
+CREATE INDEX idx_reactions_comment_id ON reactions(comment_id);
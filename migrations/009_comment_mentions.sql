@@ -0,0 +1,11 @@
+-- Users @mentioned in comments
+CREATE TABLE mentions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    comment_id INTEGER NOT NULL,
+    username TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (comment_id) REFERENCES comments (id) ON DELETE CASCADE,
+    UNIQUE (comment_id, username)
+);
+
+CREATE INDEX idx_mentions_username ON mentions(username);
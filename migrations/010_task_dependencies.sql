@@ -0,0 +1,11 @@
+-- Task dependencies: task_id cannot be worked on until blocked_by is done
+CREATE TABLE task_dependencies (
+    task_id INTEGER NOT NULL,
+    blocked_by INTEGER NOT NULL,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (task_id, blocked_by),
+    FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE,
+    FOREIGN KEY (blocked_by) REFERENCES tasks (id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_dependencies_blocked_by ON task_dependencies(blocked_by);
@@ -0,0 +1,7 @@
+-- Structured fields for task events, so the log can be filtered and exported
+-- without parsing metadata. column_from/column_to are set when a task moves.
+ALTER TABLE activity_log ADD COLUMN task_id INTEGER;
+ALTER TABLE activity_log ADD COLUMN column_from TEXT;
+ALTER TABLE activity_log ADD COLUMN column_to TEXT;
+
+CREATE INDEX idx_activity_log_task_id ON activity_log(task_id);
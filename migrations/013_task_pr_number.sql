@@ -0,0 +1,2 @@
+-- PR number of the task's pull request, recorded by `pb submit`
+ALTER TABLE tasks ADD COLUMN pr_number INTEGER;
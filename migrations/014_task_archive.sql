@@ -0,0 +1,4 @@
+-- Archived tasks are kept but hidden from the board; deleted tasks are
+-- soft-deleted and can be restored
+ALTER TABLE tasks ADD COLUMN archived_at TEXT;
+ALTER TABLE tasks ADD COLUMN deleted_at TEXT;
@@ -0,0 +1,11 @@
+-- What a column means to start/submit/done, so columns can be renamed freely
+ALTER TABLE columns ADD COLUMN role TEXT;
+
+UPDATE columns SET role = 'backlog' WHERE name = 'Backlog';
+UPDATE columns SET role = 'todo' WHERE name = 'To Do';
+UPDATE columns SET role = 'doing' WHERE name = 'Doing';
+UPDATE columns SET role = 'review' WHERE name = 'Review';
+UPDATE columns SET role = 'done' WHERE name = 'Done';
+
+-- At most one column per role
+CREATE UNIQUE INDEX idx_columns_role ON columns(role);
@@ -0,0 +1,10 @@
+-- Time logged against tasks with `pb log-time`
+CREATE TABLE worklog (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    task_id INTEGER NOT NULL,
+    minutes INTEGER NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_worklog_task_id ON worklog(task_id);
@@ -0,0 +1,7 @@
+-- Running timers started by `pb start` when `track_time` is on; `pb done`
+-- turns the elapsed time into a worklog entry
+CREATE TABLE timers (
+    task_id INTEGER PRIMARY KEY,
+    started_at TEXT NOT NULL,
+    FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE
+);
@@ -0,0 +1,2 @@
+-- Comments double as review threads that can be marked resolved
+ALTER TABLE comments ADD COLUMN resolved BOOLEAN NOT NULL DEFAULT 0;
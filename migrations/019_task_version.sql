@@ -0,0 +1,2 @@
+-- Bumped on every task update, so a write based on a stale read is refused
+ALTER TABLE tasks ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
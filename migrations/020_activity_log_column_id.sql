@@ -0,0 +1,6 @@
+-- The column a task moved into, by id, so a later rename doesn't lose track of
+-- it. Earlier moves are matched to the columns that still carry their names.
+ALTER TABLE activity_log ADD COLUMN column_to_id INTEGER;
+
+UPDATE activity_log SET column_to_id = (SELECT id FROM columns WHERE columns.name = activity_log.column_to)
+WHERE column_to IS NOT NULL;
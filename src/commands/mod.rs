@@ -1,25 +1,247 @@
 use anyhow::{Result, bail, Context};
-use std::path::PathBuf;
+use chrono::{DateTime, Duration, Months, Utc};
+use std::path::{Path, PathBuf};
+use std::fmt::Write as _;
+use std::io::Write as _;
 use std::fs;
+use std::sync::OnceLock;
 
-use crate::db::Database;
+use crate::config::{ColumnSpec, Config, UserDefaults};
+use crate::db::{check_task_prefix, task_ref, ActivityFilter, ActivityLog, AssigneeFilter, AssigneeStats, Column, Comment, CommentFilter, ColumnRole, ColumnWithLimit, Database, Idea, ImportedTask, LoggedTime, Priority, Task, TaskDependency, TaskDetail, TaskId, TaskLabel, TaskQuery};
 use crate::git::GitRepo;
-use crate::github::{GitHubClient, extract_github_info};
+use crate::github::{CheckState, CheckStatus, GitHubClient, MergeMethod, PullRequestStatus, extract_github_info, get_token_info, parse_pr_url, resolve_token};
 use crate::ExportFormat;
+use crate::glyphs;
+use serde::Serialize;
 
-pub async fn init_command() -> Result<()> {
-    println!("🚀 Initializing ProjectBoard...");
+/// `println!` for success chatter that `--quiet` silences. Output a command
+/// exists to produce (lists, exports, branch names) and warnings use `println!`.
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if verbosity() != Verbosity::Quiet {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// How much commands print on success; errors go to stderr either way
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verbosity {
+    Quiet,
+    Normal,
+}
+
+// Set once from the global --quiet flag before the command runs
+static VERBOSITY: OnceLock<Verbosity> = OnceLock::new();
+
+pub fn set_verbosity(verbosity: Verbosity) {
+    let _ = VERBOSITY.set(verbosity);
+}
+
+fn verbosity() -> Verbosity {
+    VERBOSITY.get().copied().unwrap_or(Verbosity::Normal)
+}
+
+/// When to style `list`, `show`, and `log` output with ANSI colors
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Color when stdout is a terminal, honoring NO_COLOR and CLICOLOR
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+// Resolved once from the global --color flag before the command runs
+static COLOR: OnceLock<bool> = OnceLock::new();
+
+pub fn set_color(choice: ColorChoice) {
+    let _ = COLOR.set(resolve_color(
+        choice,
+        |name| std::env::var(name).ok(),
+        std::io::IsTerminal::is_terminal(&std::io::stdout()),
+    ));
+}
+
+fn color_enabled() -> bool {
+    *COLOR.get_or_init(|| resolve_color(
+        ColorChoice::Auto,
+        |name| std::env::var(name).ok(),
+        std::io::IsTerminal::is_terminal(&std::io::stdout()),
+    ))
+}
+
+/// An explicit --color wins; otherwise NO_COLOR (any value) and CLICOLOR=0 turn
+/// color off, CLICOLOR_FORCE turns it on, and the rest depends on the terminal
+fn resolve_color(choice: ColorChoice, env: impl Fn(&str) -> Option<String>, is_tty: bool) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            let set = |name| env(name).is_some_and(|v| !v.is_empty());
+            if set("NO_COLOR") || env("CLICOLOR").as_deref() == Some("0") {
+                false
+            } else if set("CLICOLOR_FORCE") && env("CLICOLOR_FORCE").as_deref() != Some("0") {
+                true
+            } else {
+                is_tty
+            }
+        }
+    }
+}
+
+/// Wrap `text` in the given SGR code (e.g. "1;36") when color is enabled
+fn paint(text: &str, sgr: &str) -> String {
+    if color_enabled() {
+        format!("\x1b[{}m{}\x1b[0m", sgr, text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// A task given on the command line, either by ID or as `@<query>`
+#[derive(Debug, Clone)]
+pub enum TaskSelector {
+    Id(TaskId),
+    Query(String),
+}
+
+impl std::str::FromStr for TaskSelector {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if let Some(query) = s.strip_prefix('@') {
+            if query.trim().is_empty() {
+                return Err("expected text after '@'".to_string());
+            }
+            return Ok(TaskSelector::Query(query.to_string()));
+        }
+        s.parse()
+            .map(TaskSelector::Id)
+            .map_err(|_| format!("'{}' is not a task ID or @<query>", s))
+    }
+}
+
+/// Where `pb move` sends a task
+#[derive(Debug, Clone)]
+pub enum MoveTarget {
+    Column(String),
+    /// The column holding this role, so scripts survive column renames
+    Role(ColumnRole),
+    Next,
+    Prev,
+    /// Back to the column the task's last move came from
+    Undo,
+    /// Back to where the task's last `move` came from, whatever happened since
+    Back,
+}
+
+/// Hidden tasks `pb export --include` adds to the active ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportInclude {
+    Archived,
+    Deleted,
+}
+
+/// Task fields `pb export --anonymize --redact` blanks out as well
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportRedact {
+    Titles,
+    Descriptions,
+}
+
+/// How often a recurring task comes back after it is done
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Recurrence {
+    Daily,
+    Weekly,
+    Monthly,
+    Days(u32),
+}
+
+impl std::str::FromStr for Recurrence {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        match s.as_str() {
+            "daily" => return Ok(Recurrence::Daily),
+            "weekly" => return Ok(Recurrence::Weekly),
+            "monthly" => return Ok(Recurrence::Monthly),
+            _ => {}
+        }
+        
+        // Simple intervals: <n>d or <n>w
+        let invalid = || format!("'{}' is not a recurrence (daily, weekly, monthly, <n>d, <n>w)", s);
+        let unit_start = s.char_indices().last().map(|(i, _)| i).unwrap_or(0);
+        let (count, unit) = s.split_at(unit_start);
+        let count: u32 = count.parse().map_err(|_| invalid())?;
+        if count == 0 {
+            return Err(invalid());
+        }
+        match unit {
+            "d" => Ok(Recurrence::Days(count)),
+            "w" => count.checked_mul(7).map(Recurrence::Days).ok_or_else(invalid),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl std::fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Recurrence::Daily => write!(f, "daily"),
+            Recurrence::Weekly => write!(f, "weekly"),
+            Recurrence::Monthly => write!(f, "monthly"),
+            Recurrence::Days(days) => write!(f, "{}d", days),
+        }
+    }
+}
 
+impl Recurrence {
+    /// Fails when the next occurrence would be past the last date chrono can represent
+    pub fn next_after(&self, from: DateTime<Utc>) -> Result<DateTime<Utc>> {
+        let next = match self {
+            Recurrence::Daily => from.checked_add_signed(Duration::days(1)),
+            Recurrence::Weekly => from.checked_add_signed(Duration::weeks(1)),
+            Recurrence::Monthly => from.checked_add_months(Months::new(1))
+                .or_else(|| from.checked_add_signed(Duration::days(30))),
+            Recurrence::Days(days) => from.checked_add_signed(Duration::days(i64::from(*days))),
+        };
+        next.ok_or_else(|| anyhow::anyhow!("recurring {} from {} is out of range", self, from.format("%Y-%m-%d")))
+    }
+}
+
+pub async fn init_command(adopt: bool, columns: Option<Vec<ColumnSpec>>) -> Result<()> {
     // Check if we're in a git repository
     let repo_path = std::env::current_dir()?;
     let git_repo = GitRepo::open(&repo_path)?;
+    // Checked before anything is created, since SQLite can't open the database otherwise
+    let repo_path_str = repo_path.to_str()
+        .ok_or_else(|| anyhow::anyhow!("Cannot initialize in {}: ProjectBoard needs a UTF-8 path", repo_path.display()))?;
     
     // Create .projectboard directory
     let pb_dir = repo_path.join(".projectboard");
     if pb_dir.exists() {
+        if adopt {
+            // Re-running --adopt only picks up branches created since
+            let db = get_database().await?;
+            return adopt_branches(&db, &git_repo).await;
+        }
         bail!("ProjectBoard already initialized in this repository");
     }
     
+    // --columns beats the user's defaults.toml, which beats the built-in columns
+    let columns = match columns {
+        Some(columns) => Some(columns),
+        None => UserDefaults::load()?.columns,
+    };
+    if let Some(columns) = &columns {
+        check_column_specs(columns)?;
+    }
+    
+    say!("{} Initializing ProjectBoard...", glyphs::icon("🚀"));
+    
     fs::create_dir_all(&pb_dir)
         .context("Failed to create .projectboard directory")?;
 
@@ -31,457 +253,5059 @@ pub async fn init_command() -> Result<()> {
     db.migrate().await?;
     
     // Create default columns
-    let columns = db.create_default_columns().await?;
-    println!("📋 Created default columns:");
+    let columns = match columns {
+        Some(specs) => {
+            let specs: Vec<_> = specs.iter().map(|c| (c.name.as_str(), c.role())).collect();
+            db.create_columns(&specs).await?
+        }
+        None => db.create_default_columns().await?,
+    };
+    say!("{} Created columns:", glyphs::icon("📋"));
     for column in &columns {
-        println!("  - {}", column.name);
+        match &column.role {
+            Some(role) => say!("  - {} ({})", column.name, role),
+            None => say!("  - {}", column.name),
+        }
+    }
+    for role in [ColumnRole::Backlog, ColumnRole::Doing, ColumnRole::Review, ColumnRole::Done] {
+        if !columns.iter().any(|c| c.role() == Some(role)) {
+            println!("{}  No column has the {} role; set one with 'pb column set-role <name> {}'", glyphs::icon("⚠️"), role, role);
+        }
     }
     
     // Create project entry
+    // `/` has no name at all
     let repo_name = repo_path.file_name()
         .and_then(|n| n.to_str())
         .unwrap_or("project")
         .to_string();
     
-    let project = db.create_project(&repo_name, repo_path.to_str().unwrap()).await?;
+    let project = db.create_project(&repo_name, repo_path_str).await?;
     
     // Log activity
     db.log_activity("project_initialized", Some(format!("Project: {}", project.name))).await?;
     
-    println!("✅ ProjectBoard initialized successfully!");
-    println!("   Database: {}", db_path.display());
-    println!("   Use 'pb add \"Task title\"' to create your first task");
+    say!("{} ProjectBoard initialized successfully!", glyphs::icon("✅"));
+    say!("   Database: {}", db_path.display());
+    if adopt {
+        return adopt_branches(&db, &git_repo).await;
+    }
+    say!("   Use 'pb add \"Task title\"' to create your first task");
+    
+    Ok(())
+}
+
+/// Reject column lists with no columns, duplicate names, or a role used twice
+fn check_column_specs(columns: &[ColumnSpec]) -> Result<()> {
+    if columns.is_empty() {
+        bail!("A board needs at least one column");
+    }
+    for (i, column) in columns.iter().enumerate() {
+        let earlier = &columns[..i];
+        if earlier.iter().any(|c| c.name == column.name) {
+            bail!("Column '{}' is listed twice", column.name);
+        }
+        if let Some(role) = column.role().filter(|&r| earlier.iter().any(|c| c.role() == Some(r))) {
+            bail!("More than one column has the {} role", role);
+        }
+    }
+    Ok(())
+}
+
+/// Create a Doing task for each `feature/<id>-<slug>` branch no task owns yet
+async fn adopt_branches(db: &Database, git_repo: &GitRepo) -> Result<()> {
+    let doing_column = column_for_role(db, ColumnRole::Doing).await?;
+    let tracked: std::collections::HashSet<String> = db.query_tasks(&TaskQuery {
+            include_archived: true,
+            include_deleted: true,
+            ..TaskQuery::default()
+        }).await?
+        .into_iter()
+        .filter_map(|task| task.branch_name)
+        .collect();
+    
+    let mut adopted = 0;
+    for branch in git_repo.list_local_branches()? {
+        let Some(title) = title_from_branch(&branch) else {
+            continue;
+        };
+        if tracked.contains(&branch) {
+            continue;
+        }
+        
+        let mut task = db.create_task(&title, None, doing_column.id, None).await?;
+        db.update_task_branch(&mut task, Some(&branch)).await?;
+        db.log_task_activity("task_adopted", task.id, None, Some(format!("Task #{}: from branch {}", task.id, branch))).await?;
+        say!("{} Adopted {} as task {}: {}", glyphs::icon("🌿"), branch, task_ref(task.id), task.title);
+        adopted += 1;
+    }
+    
+    if adopted == 0 {
+        say!("{} No untracked feature branches to adopt", glyphs::icon("🌿"));
+    } else {
+        say!("{} Adopted {} branch(es) into {}", glyphs::icon("📦"), adopted, doing_column.name);
+    }
     
     Ok(())
 }
 
-pub async fn add_command(title: String, description: Option<String>) -> Result<()> {
+/// "Fix login page" from a `feature/<id>-fix-login-page` branch name
+fn title_from_branch(branch: &str) -> Option<String> {
+    let (id, slug) = branch.strip_prefix("feature/")?.split_once('-')?;
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    
+    let words: Vec<&str> = slug.split('-').filter(|w| !w.is_empty()).collect();
+    let title = words.join(" ");
+    let mut chars = title.chars();
+    let first = chars.next()?;
+    Some(first.to_uppercase().chain(chars).collect())
+}
+
+pub async fn add_command(
+    title: String,
+    description: Option<String>,
+    assignee: Option<String>,
+    edit: bool,
+    labels: Vec<String>,
+) -> Result<()> {
+    let db = get_database().await?;
+    
+    let labels = label_names(&labels)?;
+    let assignee = match assignee.as_deref().map(resolve_assignee_token).transpose()? {
+        Some(AssigneeFilter::User(user)) => Some(user),
+        _ => None,
+    };
+    
+    let description = if edit {
+        let text = edit_in_editor(&format!("Description for: {}", title), "")?;
+        (!text.is_empty()).then_some(text)
+    } else {
+        description
+    };
+    
+    create_backlog_task(&db, &title, description, assignee.as_deref(), None, &labels).await
+}
+
+/// `pb add --interactive`: prompt for each field instead
+pub async fn add_interactive_command() -> Result<()> {
+    use dialoguer::{Input, MultiSelect, Select};
+    
+    if !std::io::IsTerminal::is_terminal(&std::io::stdin()) {
+        bail!("--interactive needs a terminal; pass the title instead (pb add \"Task title\")");
+    }
     let db = get_database().await?;
+    let labels = db.get_labels().await?;
     
+    let title: String = Input::new()
+        .with_prompt("Title")
+        .validate_with(|input: &String| if input.trim().is_empty() { Err("Title cannot be empty") } else { Ok(()) })
+        .interact_text()?;
+    let description: String = Input::new()
+        .with_prompt("Description (optional)")
+        .allow_empty(true)
+        .interact_text()?;
+    
+    let priorities = [None, Some(Priority::Low), Some(Priority::Medium), Some(Priority::High), Some(Priority::Critical)];
+    let names: Vec<_> = priorities.iter()
+        .map(|p| p.map(|p| p.to_string()).unwrap_or_else(|| "none".to_string()))
+        .collect();
+    let priority = Select::new()
+        .with_prompt("Priority")
+        .items(&names)
+        .default(0)
+        .interact()?;
+    
+    let chosen_labels = if labels.is_empty() {
+        Vec::new()
+    } else {
+        let names: Vec<_> = labels.iter().map(|l| l.name.as_str()).collect();
+        MultiSelect::new()
+            .with_prompt("Labels (space to toggle, enter to confirm)")
+            .items(&names)
+            .interact()?
+    };
+    let chosen_labels: Vec<_> = chosen_labels.into_iter().map(|i| labels[i].name.clone()).collect();
+    
+    let assignee: String = Input::new()
+        .with_prompt("Assignee (optional)")
+        .allow_empty(true)
+        .interact_text()?;
+    
+    let description = Some(description.trim().to_string()).filter(|d| !d.is_empty());
+    let assignee = match Some(assignee.trim()).filter(|a| !a.is_empty()).map(resolve_assignee_token).transpose()? {
+        Some(AssigneeFilter::User(user)) => Some(user),
+        _ => None,
+    };
+    create_backlog_task(&db, title.trim(), description, assignee.as_deref(), priorities[priority], &chosen_labels).await
+}
+
+async fn create_backlog_task(
+    db: &Database,
+    title: &str,
+    description: Option<String>,
+    assignee: Option<&str>,
+    priority: Option<Priority>,
+    labels: &[String],
+) -> Result<()> {
     // Get the Backlog column
-    let backlog_column = db.get_column_by_name("Backlog").await?
-        .ok_or_else(|| anyhow::anyhow!("Backlog column not found"))?;
+    let backlog_column = column_for_role(db, ColumnRole::Backlog).await?;
     
-    // Create the task
-    let task = db.create_task(&title, description.clone(), backlog_column.id).await?;
+    // Create the task, with its labels in the same transaction
+    let (mut task, created_labels) = db.create_task_with_labels(title, description.clone(), backlog_column.id, assignee, labels).await?;
+    if priority.is_some() {
+        db.update_task_priority(&mut task, priority).await?;
+    }
     
     // Log activity
-    db.log_activity("task_created", Some(format!("Task #{}: {}", task.id, task.title))).await?;
+    for label in &created_labels {
+        db.log_activity("label_created", Some(format!("Label: {}", label.name))).await?;
+    }
+    db.log_task_activity("task_created", task.id, None, Some(format!("Task #{}: {}", task.id, task.title))).await?;
     
-    println!("📝 Created task #{}: {}", task.id, title);
+    say!("{} Created task {}: {}", glyphs::icon("📝"), task_ref(task.id), title);
     if let Some(desc) = description {
-        println!("   Description: {}", desc);
+        say!("   Description: {}", desc);
+    }
+    if let Some(assignee) = &task.assignee {
+        say!("   Assignee: {}", assignee);
+    }
+    if let Some(priority) = priority {
+        say!("   Priority: {}", priority);
+    }
+    if !labels.is_empty() {
+        say!("   Labels: {}", labels.join(", "));
+    }
+    say!("   Column: {}", backlog_column.name);
+    for label in &created_labels {
+        say!("{}  Created label: {}", glyphs::icon("🏷️"), label.name);
     }
-    println!("   Column: Backlog");
     
     Ok(())
 }
 
-pub async fn list_command(column_filter: Option<String>) -> Result<()> {
+/// Which side of the dependency split `list --ready` / `--blocked` shows
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReadinessFilter {
+    Ready,
+    Blocked,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn list_command(
+    column_filter: Option<String>,
+    limit: Option<u32>,
+    oldest_first: bool,
+    readiness: Option<ReadinessFilter>,
+    stale: Option<String>,
+    group_by: Option<ListGrouping>,
+    assignee: Option<String>,
+    tree: bool,
+    count: bool,
+    json: bool,
+) -> Result<()> {
     let db = get_database().await?;
+    let assignee = assignee.as_deref().map(resolve_assignee_token).transpose()?.unwrap_or_default();
     
-    let columns = db.get_columns().await?;
+    // The notice would land in front of --json output
+    let archived = auto_archive_done(&db).await?;
+    if archived > 0 && !json {
+        say!("{}  Auto-archived {} task(s) that sat in Done past auto_archive_done_after", glyphs::icon("🗄️"), archived);
+    }
+    
+    if let Some(filter) = readiness {
+        return list_by_readiness(&db, column_filter, &assignee, filter).await;
+    }
+    if tree {
+        return list_tree(&db, column_filter, &assignee).await;
+    }
+    if count {
+        return list_counts(&db, column_filter, &assignee, json).await;
+    }
+    let now = Utc::now();
+    let stale_before = stale.as_deref().map(|s| parse_since(s, now)).transpose()?;
+    if let Some(grouping) = group_by {
+        return list_grouped(&db, column_filter, &assignee, limit, oldest_first, stale_before, grouping).await;
+    }
     
-    if let Some(filter) = column_filter {
+    let mut columns = if let Some(filter) = &column_filter {
         // List tasks in specific column
-        let column = db.get_column_by_name(&filter).await?
+        let column = db.get_column_by_name(filter).await?
             .ok_or_else(|| anyhow::anyhow!("Column '{}' not found", filter))?;
+        vec![column]
+    } else {
+        // List all tasks grouped by column
+        db.get_columns().await?
+    };
+    if stale_before.is_some() && column_filter.is_none() {
+        columns.retain(is_stale_candidate);
+    }
+    let show_empty = columns.len() > 1;
+    
+    for column in columns {
+        let (total, tasks) = if stale_before.is_some() || assignee != AssigneeFilter::All {
+            let mut tasks = db.query_tasks(&TaskQuery {
+                column_id: Some(column.id),
+                assignee: assignee.clone(),
+                oldest_first,
+                updated_before: stale_before,
+                ..TaskQuery::default()
+            }).await?;
+            let total = tasks.len() as i64;
+            tasks.truncate(limit.map_or(tasks.len(), |l| l as usize));
+            (total, tasks)
+        } else {
+            (
+                db.count_tasks(column.id).await?,
+                db.get_tasks_paged(column.id, limit.map(i64::from), oldest_first).await?,
+            )
+        };
         
-        let tasks = db.get_tasks(Some(column.id)).await?;
+        if show_empty {
+            println!();
+        }
+        println!("{} {} ({} tasks)", glyphs::icon("📋"), paint(&column.name, "1"), total);
+        if tasks.is_empty() && show_empty {
+            println!("  (no tasks)");
+        }
+        for task in &tasks {
+            print_task_line(task, None);
+            if stale_before.is_some() {
+                println!("      {} Last updated {} days ago", glyphs::icon("💤"), (now - task.updated_at).num_days());
+            }
+        }
         
-        println!("📋 {} ({} tasks)", column.name, tasks.len());
-        for task in tasks {
-            println!("  #{}: {}", task.id, task.title);
-            if let Some(desc) = &task.description {
-                println!("      {}", desc);
+        let hidden = total - tasks.len() as i64;
+        if hidden > 0 {
+            println!("  … and {} more", hidden);
+        }
+    }
+    
+    Ok(())
+}
+
+/// What `list --group-by` groups tasks by instead of their column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ListGrouping {
+    Label,
+    Assignee,
+}
+
+/// Whether `list --stale` looks in a column unless one is named; finished
+/// work is expected to sit still
+fn is_stale_candidate(column: &Column) -> bool {
+    column.role() != Some(ColumnRole::Done)
+}
+
+async fn list_grouped(
+    db: &Database,
+    column_filter: Option<String>,
+    assignee: &AssigneeFilter,
+    limit: Option<u32>,
+    oldest_first: bool,
+    stale_before: Option<DateTime<Utc>>,
+    grouping: ListGrouping,
+) -> Result<()> {
+    let now = Utc::now();
+    let columns = db.get_columns().await?;
+    let column_id = match &column_filter {
+        Some(filter) => Some(
+            db.get_column_by_name(filter).await?
+                .ok_or_else(|| anyhow::anyhow!("Column '{}' not found", filter))?
+                .id,
+        ),
+        None => None,
+    };
+    
+    let mut tasks = db.query_tasks(&TaskQuery {
+        column_id,
+        assignee: assignee.clone(),
+        oldest_first,
+        updated_before: stale_before,
+        ..TaskQuery::default()
+    }).await?;
+    if stale_before.is_some() && column_filter.is_none() {
+        tasks.retain(|t| columns.iter().find(|c| c.id == t.column_id).is_some_and(is_stale_candidate));
+    }
+    let labels = match grouping {
+        ListGrouping::Label => db.get_all_task_labels().await?,
+        ListGrouping::Assignee => Vec::new(),
+    };
+    
+    let groups = group_tasks(&tasks, grouping, &labels);
+    if groups.is_empty() {
+        println!("{} No tasks", glyphs::icon("📋"));
+    }
+    for (i, (name, tasks)) in groups.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        match grouping {
+            ListGrouping::Label => println!("{}  {} ({} tasks)", glyphs::icon("🏷️"), paint(name, "1"), tasks.len()),
+            ListGrouping::Assignee => println!("{} {} ({} tasks)", glyphs::icon("👤"), paint(name, "1"), tasks.len()),
+        }
+        let shown = limit.map_or(tasks.len(), |l| tasks.len().min(l as usize));
+        for task in &tasks[..shown] {
+            let column_name = columns.iter()
+                .find(|c| c.id == task.column_id)
+                .map(|c| c.name.as_str())
+                .unwrap_or("Unknown");
+            print_task_line(task, Some(column_name));
+            if stale_before.is_some() {
+                println!("      {} Last updated {} days ago", glyphs::icon("💤"), (now - task.updated_at).num_days());
             }
-            if let Some(branch) = &task.branch_name {
-                println!("      🌿 Branch: {}", branch);
+        }
+        if tasks.len() > shown {
+            println!("  … and {} more", tasks.len() - shown);
+        }
+    }
+    
+    Ok(())
+}
+
+/// Tasks bucketed by label or assignee, in the order they came in. Groups are
+/// sorted by name with the catch-all (no label, unassigned) last; a task with
+/// several labels shows up under each.
+fn group_tasks<'a>(tasks: &'a [Task], grouping: ListGrouping, labels: &[TaskLabel]) -> Vec<(String, Vec<&'a Task>)> {
+    let mut groups: Vec<(Option<String>, Vec<&Task>)> = Vec::new();
+    for task in tasks {
+        let keys: Vec<Option<String>> = match grouping {
+            ListGrouping::Assignee => vec![task.assignee.clone()],
+            ListGrouping::Label => {
+                let names: Vec<_> = labels.iter()
+                    .filter(|l| l.task_id == task.id)
+                    .map(|l| Some(l.name.clone()))
+                    .collect();
+                if names.is_empty() { vec![None] } else { names }
             }
-            if let Some(pr) = &task.pr_url {
-                println!("      🔗 PR: {}", pr);
+        };
+        for key in keys {
+            match groups.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, group)) => group.push(task),
+                None => groups.push((key, vec![task])),
             }
         }
-    } else {
-        // List all tasks grouped by column
-        for column in columns {
-            let tasks = db.get_tasks(Some(column.id)).await?;
-            
-            println!("\n📋 {} ({} tasks)", column.name, tasks.len());
-            if tasks.is_empty() {
-                println!("  (no tasks)");
-            } else {
-                for task in tasks {
-                    println!("  #{}: {}", task.id, task.title);
-                    if let Some(desc) = &task.description {
-                        println!("      {}", desc);
-                    }
-                    if let Some(branch) = &task.branch_name {
-                        println!("      🌿 Branch: {}", branch);
-                    }
-                    if let Some(pr) = &task.pr_url {
-                        println!("      🔗 PR: {}", pr);
-                    }
-                }
-            }
+    }
+    // None sorts first, so compare on (is_none, name) to put it last
+    groups.sort_by(|(a, _), (b, _)| (a.is_none(), a).cmp(&(b.is_none(), b)));
+    
+    let catch_all = match grouping {
+        ListGrouping::Label => "(no label)",
+        ListGrouping::Assignee => "(unassigned)",
+    };
+    groups.into_iter()
+        .map(|(key, tasks)| (key.unwrap_or_else(|| catch_all.to_string()), tasks))
+        .collect()
+}
+
+async fn list_counts(db: &Database, column_filter: Option<String>, assignee: &AssigneeFilter, json: bool) -> Result<()> {
+    let mut columns = db.get_columns().await?;
+    if let Some(filter) = &column_filter {
+        columns.retain(|c| &c.name == filter);
+        if columns.is_empty() {
+            bail!("Column '{}' not found", filter);
         }
     }
+    let counts = db.count_tasks_by_column(assignee).await?;
+    let counts: Vec<(&str, i64)> = columns.iter()
+        .map(|column| {
+            let count = counts.iter().find(|c| c.column_id == column.id).map_or(0, |c| c.count);
+            (column.name.as_str(), count)
+        })
+        .collect();
+    let total: i64 = counts.iter().map(|(_, count)| count).sum();
+    
+    if json {
+        let columns: Vec<_> = counts.iter()
+            .map(|(name, count)| serde_json::json!({ "column": name, "count": count }))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&serde_json::json!({ "columns": columns, "total": total }))?);
+        return Ok(());
+    }
+    for (name, count) in &counts {
+        println!("{}: {}", name, count);
+    }
+    println!("Total: {}", total);
     
     Ok(())
 }
 
-pub async fn move_command(task_id: u32, column_name: String) -> Result<()> {
-    let db = get_database().await?;
+async fn list_tree(db: &Database, column_filter: Option<String>, assignee: &AssigneeFilter) -> Result<()> {
+    let columns = db.get_columns().await?;
+    let column_id = match &column_filter {
+        Some(filter) => Some(
+            db.get_column_by_name(filter).await?
+                .ok_or_else(|| anyhow::anyhow!("Column '{}' not found", filter))?
+                .id,
+        ),
+        None => None,
+    };
+    let tasks = db.query_tasks(&TaskQuery {
+        column_id,
+        assignee: assignee.clone(),
+        ..TaskQuery::default()
+    }).await?;
+    let dependencies = db.get_dependencies().await?;
     
-    // Get the task
-    let task = db.get_task(task_id as i64).await?
-        .ok_or_else(|| anyhow::anyhow!("Task #{} not found", task_id))?;
+    println!("{} Dependency tree ({} tasks)", glyphs::icon("🔗"), tasks.len());
+    if tasks.is_empty() {
+        println!("  (no tasks)");
+    }
+    print!("{}", format_dependency_tree(&tasks, &dependencies, &columns));
     
-    // Get the target column
-    let target_column = db.get_column_by_name(&column_name).await?
-        .ok_or_else(|| anyhow::anyhow!("Column '{}' not found", column_name))?;
+    Ok(())
+}
+
+/// `tasks` as a forest with each blocker above the tasks it blocks, drawn with
+/// box-drawing connectors. A task blocked by several others is expanded under
+/// the first and only named under the rest; an edge back into the branch being
+/// drawn is a cycle and is shown as ↻ rather than followed. Dependencies on
+/// tasks not in `tasks` are left out.
+fn format_dependency_tree(tasks: &[Task], dependencies: &[TaskDependency], columns: &[Column]) -> String {
+    struct Tree<'a> {
+        tasks: &'a [Task],
+        columns: &'a [Column],
+        blocks: std::collections::BTreeMap<i64, Vec<i64>>,
+        expanded: std::collections::HashSet<i64>,
+        path: Vec<i64>,
+        out: String,
+    }
     
-    // Get current column for logging
-    let current_column = db.get_columns().await?
-        .into_iter()
-        .find(|c| c.id == task.column_id)
-        .ok_or_else(|| anyhow::anyhow!("Current column not found"))?;
+    fn draw(tree: &mut Tree, id: i64, prefix: &str, last: Option<bool>) {
+        let connector = match last {
+            None => "  ",
+            Some(false) => "├── ",
+            Some(true) => "└── ",
+        };
+        if tree.path.contains(&id) {
+            tree.out.push_str(&format!("{}{}↻ {} (cycle)\n", prefix, connector, task_ref(id)));
+            return;
+        }
+        let Some(task) = tree.tasks.iter().find(|t| t.id == id) else {
+            return;
+        };
+        let column = tree.columns.iter()
+            .find(|c| c.id == task.column_id)
+            .map(|c| c.name.as_str())
+            .unwrap_or("Unknown");
+        let children = tree.blocks.get(&id).cloned().unwrap_or_default();
+        let repeat = !children.is_empty() && tree.expanded.contains(&id);
+        tree.out.push_str(&format!(
+            "{}{}{}: {} [{}]{}\n",
+            prefix, connector, task_ref(id), task.title, column,
+            if repeat { " (see above)" } else { "" }
+        ));
+        if repeat {
+            return;
+        }
+        
+        tree.expanded.insert(id);
+        tree.path.push(id);
+        let child_prefix = format!("{}{}", prefix, match last {
+            None => "  ",
+            Some(false) => "│   ",
+            Some(true) => "    ",
+        });
+        for (i, &child) in children.iter().enumerate() {
+            draw(tree, child, &child_prefix, Some(i + 1 == children.len()));
+        }
+        tree.path.pop();
+    }
     
-    // Update the task
-    db.update_task_column(task.id, target_column.id).await?;
+    let shown = |id: i64| tasks.iter().any(|t| t.id == id);
+    let mut tree = Tree {
+        tasks,
+        columns,
+        blocks: std::collections::BTreeMap::new(),
+        expanded: std::collections::HashSet::new(),
+        path: Vec::new(),
+        out: String::new(),
+    };
+    for dependency in dependencies.iter().filter(|d| shown(d.task_id) && shown(d.blocked_by)) {
+        tree.blocks.entry(dependency.blocked_by).or_default().push(dependency.task_id);
+    }
+    for blocked in tree.blocks.values_mut() {
+        blocked.sort_unstable();
+        blocked.dedup();
+    }
     
-    // Log activity
-    db.log_activity(
-        "task_moved", 
-        Some(format!("Task #{}: {} → {}", task.id, current_column.name, target_column.name))
-    ).await?;
+    let mut ids: Vec<i64> = tasks.iter().map(|t| t.id).collect();
+    ids.sort_unstable();
+    let is_blocked = |id: i64| tree.blocks.values().any(|blocked| blocked.contains(&id));
+    let roots: Vec<i64> = ids.iter().copied().filter(|&id| !is_blocked(id)).collect();
+    for root in roots {
+        draw(&mut tree, root, "", None);
+    }
+    // Tasks waiting on each other in a cycle have no root; start from the lowest ID left
+    while let Some(&id) = ids.iter().find(|id| !tree.expanded.contains(id)) {
+        draw(&mut tree, id, "", None);
+    }
+    
+    tree.out
+}
+
+async fn list_by_readiness(
+    db: &Database,
+    column_filter: Option<String>,
+    assignee: &AssigneeFilter,
+    filter: ReadinessFilter,
+) -> Result<()> {
+    let column_id = match column_filter {
+        Some(filter) => Some(
+            db.get_column_by_name(&filter).await?
+                .ok_or_else(|| anyhow::anyhow!("Column '{}' not found", filter))?
+                .id,
+        ),
+        None => None,
+    };
+    
+    let tasks: Vec<_> = db.compute_readiness().await?
+        .into_iter()
+        .filter(|r| column_id.is_none_or(|id| r.task.column_id == id))
+        .filter(|r| assignee.matches(r.task.assignee.as_deref()))
+        .filter(|r| r.is_ready() == (filter == ReadinessFilter::Ready))
+        .collect();
     
-    println!("📦 Moved task #{}: {} → {}", task_id, current_column.name, target_column.name);
-    println!("   {}", task.title);
+    match filter {
+        ReadinessFilter::Ready => println!("{} Ready ({} tasks)", glyphs::icon("✅"), tasks.len()),
+        ReadinessFilter::Blocked => println!("{} Blocked ({} tasks)", glyphs::icon("⛔"), tasks.len()),
+    }
+    if tasks.is_empty() {
+        println!("  (no tasks)");
+    }
+    for readiness in &tasks {
+        print_task_line(&readiness.task, None);
+        if !readiness.is_ready() {
+            let blockers: Vec<_> = readiness.open_blockers.iter().map(|&id| task_ref(id)).collect();
+            println!("      {} Blocked by: {}", glyphs::icon("⛔"), blockers.join(", "));
+        }
+    }
     
     Ok(())
 }
 
-pub async fn comment_command(task_id: u32, text: String) -> Result<()> {
+pub async fn clone_command(task_id: TaskId) -> Result<()> {
     let db = get_database().await?;
     
-    // Verify task exists
-    let task = db.get_task(task_id as i64).await?
-        .ok_or_else(|| anyhow::anyhow!("Task #{} not found", task_id))?;
+    // Get the source task
+    let source = db.get_task(task_id).await?
+        .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_ref(task_id)))?;
     
-    // Get current user (from git config)
-    let author = get_git_user().unwrap_or_else(|| "unknown".to_string());
+    // Get the Backlog column
+    let backlog_column = column_for_role(&db, ColumnRole::Backlog).await?;
     
-    // Create comment
-    let comment = db.create_comment(task.id, &author, &text).await?;
+    // Copy the card contents only; branch, PR and comments stay with the source
+    let title = format!("Copy of {}", source.title);
+    let task = db.create_task(&title, source.description.clone(), backlog_column.id, None).await?;
     
     // Log activity
-    db.log_activity(
-        "comment_added", 
-        Some(format!("Task #{}: comment by {}", task.id, author))
+    db.log_task_activity(
+        "task_cloned", 
+        task.id,
+        None,
+        Some(format!("Task #{} → Task #{}: {}", source.id, task.id, task.title))
     ).await?;
     
-    println!("💬 Added comment to task #{}: {}", task_id, task.title);
-    println!("   {}: {}", author, text);
+    say!("{} Cloned task {} into {}: {}", glyphs::icon("📝"), task_ref(source.id), task_ref(task.id), task.title);
+    say!("   Column: {}", backlog_column.name);
     
     Ok(())
 }
 
-pub async fn idea_command(content: String) -> Result<()> {
+pub async fn template_save_command(name: String, from_task: TaskId) -> Result<()> {
     let db = get_database().await?;
     
-    let idea = db.create_idea(&content).await?;
+    // Get the source task
+    let task = db.get_task(from_task).await?
+        .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_ref(from_task)))?;
+    
+    let template = db.save_template(&name, &task.title, task.description.clone()).await?;
     
     // Log activity
-    db.log_activity("idea_created", Some(format!("Idea #{}: {}", idea.id, content))).await?;
+    db.log_activity(
+        "template_saved", 
+        Some(format!("Template {}: from task #{}", template.name, task.id))
+    ).await?;
     
-    println!("💡 Created idea #{}: {}", idea.id, content);
+    say!("{} Saved template '{}' from task {}", glyphs::icon("📐"), template.name, task_ref(task.id));
+    say!("   Title: {}", template.title);
     
     Ok(())
 }
 
-pub async fn promote_command(idea_id: u32) -> Result<()> {
+pub async fn template_apply_command(name: String) -> Result<()> {
     let db = get_database().await?;
     
-    // Get the idea
-    let idea = db.get_idea(idea_id as i64).await?
-        .ok_or_else(|| anyhow::anyhow!("Idea #{} not found", idea_id))?;
-    
-    // Get Backlog column
-    let backlog_column = db.get_column_by_name("Backlog").await?
-        .ok_or_else(|| anyhow::anyhow!("Backlog column not found"))?;
+    let template = db.get_template(&name).await?
+        .ok_or_else(|| anyhow::anyhow!("Template '{}' not found", name))?;
     
-    // Create task from idea
-    let task = db.create_task(&idea.content, None, backlog_column.id).await?;
+    // Get the Backlog column
+    let backlog_column = column_for_role(&db, ColumnRole::Backlog).await?;
     
-    // Delete the idea
-    db.delete_idea(idea.id).await?;
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let title = template.title.replace("{date}", &today);
+    let task = db.create_task(&title, template.description.clone(), backlog_column.id, None).await?;
     
     // Log activity
-    db.log_activity(
-        "idea_promoted", 
-        Some(format!("Idea #{} → Task #{}: {}", idea_id, task.id, idea.content))
+    db.log_task_activity(
+        "template_applied", 
+        task.id,
+        None,
+        Some(format!("Template {} → Task #{}: {}", template.name, task.id, task.title))
     ).await?;
     
-    println!("🚀 Promoted idea #{} to task #{}: {}", idea_id, task.id, idea.content);
+    say!("{} Created task {} from template '{}': {}", glyphs::icon("📝"), task_ref(task.id), template.name, task.title);
+    say!("   Column: {}", backlog_column.name);
     
     Ok(())
 }
 
-pub async fn start_command(task_id: u32) -> Result<()> {
+pub async fn priority_command(task_id: TaskId, level: String) -> Result<()> {
     let db = get_database().await?;
-    let repo_path = std::env::current_dir()?;
-    let git_repo = GitRepo::open(&repo_path)?;
     
     // Get the task
-    let task = db.get_task(task_id as i64).await?
-        .ok_or_else(|| anyhow::anyhow!("Task #{} not found", task_id))?;
-    
-    // Generate branch name
-    let slug = task.title
-        .to_lowercase()
-        .replace(' ', "-")
-        .chars()
-        .filter(|c| c.is_alphanumeric() || *c == '-')
-        .collect::<String>();
-    let branch_name = format!("feature/{}-{}", task_id, slug);
+    let mut task = db.get_task(task_id).await?
+        .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_ref(task_id)))?;
     
-    // Create and checkout branch
-    git_repo.create_branch(&branch_name)?;
-    git_repo.checkout_branch(&branch_name)?;
-    
-    // Update task with branch name
-    db.update_task_branch(task.id, &branch_name).await?;
+    let priority = if level == "none" {
+        None
+    } else {
+        Some(level.parse::<Priority>().map_err(|e| anyhow::anyhow!(e))?)
+    };
+    db.update_task_priority(&mut task, priority).await?;
     
-    // Move task to "Doing" column
-    let doing_column = db.get_column_by_name("Doing").await?
-        .ok_or_else(|| anyhow::anyhow!("Doing column not found"))?;
-    db.update_task_column(task.id, doing_column.id).await?;
+    let label = priority.map(|p| p.to_string()).unwrap_or_else(|| "none".to_string());
     
     // Log activity
-    db.log_activity(
-        "task_started", 
-        Some(format!("Task #{}: created branch {}", task.id, branch_name))
+    db.log_task_activity(
+        "priority_set", 
+        task.id,
+        None,
+        Some(format!("Task #{}: {}", task.id, label))
     ).await?;
     
-    println!("🚀 Started task #{}: {}", task_id, task.title);
-    println!("   🌿 Created and checked out branch: {}", branch_name);
-    println!("   📦 Moved to: Doing");
+    say!("{} Set priority of task {} to {}: {}", glyphs::icon("🚩"), task_ref(task.id), label, task.title);
     
     Ok(())
 }
 
-pub async fn done_command(task_id: u32, message: Option<String>) -> Result<()> {
+pub async fn recur_command(task_id: TaskId, rule: String) -> Result<()> {
     let db = get_database().await?;
-    let repo_path = std::env::current_dir()?;
-    let git_repo = GitRepo::open(&repo_path)?;
     
     // Get the task
-    let task = db.get_task(task_id as i64).await?
-        .ok_or_else(|| anyhow::anyhow!("Task #{} not found", task_id))?;
+    let mut task = db.get_task(task_id).await?
+        .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_ref(task_id)))?;
     
-    // Check if there are staged changes to commit
-    if git_repo.has_staged_changes()? {
-        let commit_message = message.unwrap_or_else(|| format!("Closes #{}: {}", task_id, task.title));
-        git_repo.commit(&commit_message)?;
-        println!("💾 Committed changes: {}", commit_message);
-    }
-    
-    // Push branch if it exists
-    if let Some(branch_name) = &task.branch_name {
-        git_repo.push_branch(branch_name)?;
-        println!("📤 Pushed branch: {}", branch_name);
+    if rule == "none" {
+        db.update_task_recurrence(&mut task, None).await?;
+        db.log_task_activity("recurrence_cleared", task.id, None, Some(format!("Task #{}", task.id))).await?;
+        say!("{} Task {} no longer recurs: {}", glyphs::icon("🔁"), task_ref(task.id), task.title);
+        return Ok(());
     }
     
-    // Move task to "Done" column
-    let done_column = db.get_column_by_name("Done").await?
-        .ok_or_else(|| anyhow::anyhow!("Done column not found"))?;
-    db.update_task_column(task.id, done_column.id).await?;
+    let recurrence: Recurrence = rule.parse().map_err(|e: String| anyhow::anyhow!(e))?;
+    db.update_task_recurrence(&mut task, Some(&recurrence.to_string())).await?;
     
     // Log activity
-    db.log_activity(
-        "task_completed", 
-        Some(format!("Task #{}: {}", task.id, task.title))
+    db.log_task_activity(
+        "recurrence_set", 
+        task.id,
+        None,
+        Some(format!("Task #{}: {}", task.id, recurrence))
     ).await?;
     
-    println!("✅ Completed task #{}: {}", task_id, task.title);
-    println!("   📦 Moved to: Done");
+    say!("{} Task {} recurs {}: {}", glyphs::icon("🔁"), task_ref(task.id), recurrence, task.title);
     
     Ok(())
 }
 
-pub async fn submit_command(task_id: u32) -> Result<()> {
+pub async fn tick_command() -> Result<()> {
     let db = get_database().await?;
-    let repo_path = std::env::current_dir()?;
-    let git_repo = GitRepo::open(&repo_path)?;
+    
+    let done_column = column_for_role(&db, ColumnRole::Done).await?;
+    let backlog_column = column_for_role(&db, ColumnRole::Backlog).await?;
+    
+    let now = Utc::now();
+    let mut created = 0;
+    for mut task in db.get_tasks(Some(done_column.id)).await? {
+        let Some(rule) = task.recurrence.clone() else {
+            continue;
+        };
+        let recurrence: Recurrence = match rule.parse() {
+            Ok(recurrence) => recurrence,
+            Err(e) => {
+                println!("{}  Skipping task {}: {}", glyphs::icon("⚠️"), task_ref(task.id), e);
+                continue;
+            }
+        };
+        
+        // The next occurrence is scheduled from when the task was completed, not
+        // last edited; tasks that never moved into Done fall back to their last edit
+        let activity = db.query_activity(&ActivityFilter {
+            task_id: Some(task.id),
+            ..Default::default()
+        }).await?;
+        let completed_at = completed_at(&activity, done_column.id).unwrap_or(task.updated_at);
+        let due = match recurrence.next_after(completed_at) {
+            Ok(due) => due,
+            Err(e) => {
+                println!("{}  Skipping task {}: {}", glyphs::icon("⚠️"), task_ref(task.id), e);
+                continue;
+            }
+        };
+        if due > now {
+            continue;
+        }
+        
+        // Hand the rule over to the new occurrence so this one is not regenerated again
+        let mut next = db.create_task(&task.title, task.description.clone(), backlog_column.id, None).await?;
+        db.update_task_recurrence(&mut next, Some(&rule)).await?;
+        db.update_task_recurrence(&mut task, None).await?;
+        
+        // Log activity
+        db.log_task_activity(
+            "task_recurred", 
+            next.id,
+            None,
+            Some(format!("Task #{} → Task #{}: {}", task.id, next.id, next.title))
+        ).await?;
+        
+        say!("{} Created task {} from recurring task {}: {}", glyphs::icon("🔁"), task_ref(next.id), task_ref(task.id), next.title);
+        created += 1;
+    }
+    
+    if created == 0 {
+        say!("{} No recurring tasks are due", glyphs::icon("🔁"));
+    }
+    
+    Ok(())
+}
+
+/// When the task last moved into the Done column, given its activity newest first
+fn completed_at(activity: &[ActivityLog], done_column: i64) -> Option<DateTime<Utc>> {
+    activity.iter()
+        .find(|entry| entry.column_to_id == Some(done_column))
+        .map(|entry| entry.created_at)
+}
+
+/// `pb show --json`: everything `show` prints, plus the column's name
+#[derive(Debug, Serialize)]
+struct TaskDetailJson<'a> {
+    column: &'a str,
+    #[serde(flatten)]
+    detail: &'a TaskDetail,
+}
+
+pub async fn show_command(selector: TaskSelector, json: bool, author: Option<String>, since: Option<String>) -> Result<()> {
+    let db = get_database().await?;
+    let filter = CommentFilter {
+        author: author.map(|a| a.trim_start_matches('@').to_string()),
+        since: since.map(|s| parse_since(&s, Utc::now())).transpose()?,
+    };
+    let filtered = filter.author.is_some() || filter.since.is_some();
     
     // Get the task
-    let task = db.get_task(task_id as i64).await?
-        .ok_or_else(|| anyhow::anyhow!("Task #{} not found", task_id))?;
+    let task = resolve_task(&db, &selector).await?;
+    let mut detail = db.get_task_with_details(task.id).await?
+        .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_ref(task.id)))?;
+    let column_name = column_name(&db, detail.task.column_id).await?;
     
-    let branch_name = task.branch_name
-        .ok_or_else(|| anyhow::anyhow!("Task #{} has no associated branch", task_id))?;
+    let total_comments = detail.comments.len();
+    if filtered {
+        detail.comments = db.get_comments_filtered(task.id, &filter).await?;
+        let shown: std::collections::HashSet<i64> = detail.comments.iter().map(|c| c.id).collect();
+        detail.reactions.retain(|r| shown.contains(&r.comment_id));
+        detail.mentions.retain(|m| shown.contains(&m.comment_id));
+    }
     
-    // Push branch
-    git_repo.push_branch(&branch_name)?;
-    println!("📤 Pushed branch: {}", branch_name);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&TaskDetailJson { column: &column_name, detail: &detail })?);
+        return Ok(());
+    }
+    let TaskDetail { task, labels, links, comments, reactions, mentions, blocked_by: blockers, logged_minutes } = detail;
     
-    // Create GitHub PR
-    let pr_url = if let Some(remote_url) = git_repo.get_remote_url()? {
-        if let Some((owner, repo)) = extract_github_info(&remote_url) {
-            let github = GitHubClient::new(owner, repo);
-            let pr_title = format!("Task #{}: {}", task_id, task.title);
-            let pr_body = task.description.unwrap_or_default();
-            let base_branch = "main"; // TODO: get from config
+    println!("{} {}: {}", glyphs::icon("📝"), paint(&format!("Task {}", task_ref(task.id)), "33"), paint(&task.title, "1"));
+    println!("   {} Column: {}", glyphs::icon("📦"), column_name);
+    if let Some(desc) = &task.description {
+        println!("   {}", desc);
+    }
+    if let Some(assignee) = &task.assignee {
+        println!("   {} Assignee: {}", glyphs::icon("👤"), assignee);
+    }
+    if let Some(branch) = &task.branch_name {
+        println!("   {} Branch: {}", glyphs::icon("🌿"), branch);
+    }
+    match (&task.pr_url, task.pr_number) {
+        (Some(pr), Some(number)) => println!("   {} PR #{}: {}", glyphs::icon("🔗"), number, pr),
+        (Some(pr), None) => println!("   {} PR: {}", glyphs::icon("🔗"), pr),
+        _ => {}
+    }
+    if let Some(priority) = &task.priority {
+        println!("   {} Priority: {}", glyphs::icon("🚩"), priority);
+    }
+    if let Some(recurrence) = &task.recurrence {
+        println!("   {} Recurs: {}", glyphs::icon("🔁"), recurrence);
+    }
+    if let Some(archived_at) = task.archived_at {
+        println!("   {}  Archived: {}", glyphs::icon("🗄️"), archived_at.format("%Y-%m-%d %H:%M:%S"));
+    }
+    if let Some(deleted_at) = task.deleted_at {
+        println!("   {}  Deleted: {}", glyphs::icon("🗑️"), deleted_at.format("%Y-%m-%d %H:%M:%S"));
+    }
+    if !labels.is_empty() {
+        let names: Vec<_> = labels.iter().map(|l| l.name.as_str()).collect();
+        println!("   {}  Labels: {}", glyphs::icon("🏷️"), names.join(", "));
+    }
+    if logged_minutes > 0 {
+        println!("   {}  Logged: {}", glyphs::icon("⏱️"), format_minutes(logged_minutes));
+    }
+    println!("   Created: {}", task.created_at.format("%Y-%m-%d %H:%M:%S"));
+    println!("   Updated: {}", task.updated_at.format("%Y-%m-%d %H:%M:%S"));
+    
+    if !blockers.is_empty() {
+        let done_column_id = db.get_column_by_role(ColumnRole::Done).await?.map(|c| c.id);
+        println!("\n{} Blocked by ({})", glyphs::icon("⛔"), blockers.len());
+        for blocker in &blockers {
+            let state = if Some(blocker.column_id) == done_column_id { "done" } else { "open" };
+            println!("  {}: {} ({})", task_ref(blocker.id), blocker.title, state);
+        }
+    }
+    
+    if !links.is_empty() {
+        println!("\n{} Links ({})", glyphs::icon("🔗"), links.len());
+        for link in &links {
+            println!("  - {}: {}", link.label, link.url);
+        }
+    }
+    
+    if !comments.is_empty() {
+        // Bold @mentions, but keep piped output free of escape codes
+        let highlight = color_enabled();
+        let print_comment = |comment: &Comment| {
+            let text = if highlight {
+                highlight_mentions(&comment.text)
+            } else {
+                comment.text.clone()
+            };
+            println!("  [{}] {} ({}): {}", comment.id, comment.author, comment.created_at.format("%Y-%m-%d %H:%M"), text);
             
-            match github.create_pull_request(&pr_title, &pr_body, &branch_name, base_branch).await {
-                Ok(url) => {
-                    println!("🔗 Created PR: {}", url);
-                    url
-                }
-                Err(e) => {
-                    println!("⚠️  Failed to create PR: {}", e);
-                    format!("https://github.com/{}/{}/compare/{}...{}", "owner", "repo", base_branch, branch_name)
+            // Count each emoji, keeping the order reactions first appeared in
+            let mut counts: Vec<(&str, usize)> = Vec::new();
+            for reaction in reactions.iter().filter(|r| r.comment_id == comment.id) {
+                match counts.iter_mut().find(|(emoji, _)| *emoji == reaction.emoji) {
+                    Some((_, count)) => *count += 1,
+                    None => counts.push((&reaction.emoji, 1)),
                 }
             }
+            if !counts.is_empty() {
+                let summary: Vec<_> = counts.iter().map(|(emoji, count)| format!("{} {}", emoji, count)).collect();
+                println!("      {}", summary.join("  "));
+            }
+        };
+        
+        // Open threads first; resolved ones are kept for the record below them
+        let (resolved, open): (Vec<&Comment>, Vec<&Comment>) = comments.iter().partition(|c| c.resolved);
+        if filtered {
+            println!("\n{} Comments ({} of {}, {} unresolved)", glyphs::icon("💬"), comments.len(), total_comments, open.len());
         } else {
-            println!("⚠️  Not a GitHub repository, cannot create PR");
-            format!("Manual PR needed for branch: {}", branch_name)
+            println!("\n{} Comments ({}, {} unresolved)", glyphs::icon("💬"), comments.len(), open.len());
         }
-    } else {
-        println!("⚠️  No remote URL found, cannot create PR");
-        format!("Manual PR needed for branch: {}", branch_name)
-    };
+        open.into_iter().for_each(print_comment);
+        if !resolved.is_empty() {
+            println!("  {} Resolved ({})", glyphs::icon("✅"), resolved.len());
+            resolved.into_iter().for_each(print_comment);
+        }
+        
+        let mut mentioned: Vec<String> = Vec::new();
+        for mention in &mentions {
+            let name = format!("@{}", mention.username);
+            if !mentioned.contains(&name) {
+                mentioned.push(name);
+            }
+        }
+        if !mentioned.is_empty() {
+            println!("  {} Mentioned: {}", glyphs::icon("📣"), mentioned.join(", "));
+        }
+    } else if filtered && total_comments > 0 {
+        println!("\n{} No comments match the filter ({} in total)", glyphs::icon("💬"), total_comments);
+    }
     
-    // Update task with PR URL
-    db.update_task_pr(task.id, &pr_url).await?;
+    Ok(())
+}
+
+pub async fn find_command(query: String, regex: bool) -> Result<()> {
+    let db = get_database().await?;
+    if regex {
+        return find_regex(&db, &query).await;
+    }
     
-    // Move task to "Review" column
-    let review_column = db.get_column_by_name("Review").await?
-        .ok_or_else(|| anyhow::anyhow!("Review column not found"))?;
-    db.update_task_column(task.id, review_column.id).await?;
+    let tasks = db.search_tasks(&query).await?;
+    let columns = db.get_columns().await?;
     
-    // Log activity
-    db.log_activity(
-        "task_submitted", 
-        Some(format!("Task #{}: PR created", task.id))
-    ).await?;
+    if tasks.is_empty() {
+        println!("{} No tasks match '{}'", glyphs::icon("🔍"), query);
+        return Ok(());
+    }
     
-    println!("📋 Submitted task #{} for review: {}", task_id, task.title);
-    println!("   📦 Moved to: Review");
+    println!("{} {} tasks match '{}'", glyphs::icon("🔍"), tasks.len(), query);
+    for task in tasks {
+        let column_name = columns.iter()
+            .find(|c| c.id == task.column_id)
+            .map(|c| c.name.as_str())
+            .unwrap_or("Unknown");
+        println!("  {}: {} [{}]", task_ref(task.id), task.title, column_name);
+    }
     
     Ok(())
 }
 
-pub async fn review_command(task_id: u32) -> Result<()> {
-    let db = get_database().await?;
+/// Compiled regexes stay well under a megabyte; the regex crate matches in
+/// linear time, so capping the size is enough to keep hostile patterns cheap
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+async fn find_regex(db: &Database, query: &str) -> Result<()> {
+    let re = regex::RegexBuilder::new(query)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .dfa_size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .context(format!("Invalid regex '{}'", query))?;
     
-    // Get the task
-    let task = db.get_task(task_id as i64).await?
-        .ok_or_else(|| anyhow::anyhow!("Task #{} not found", task_id))?;
+    // SQLite has no portable regex, so fetch everything and match here
+    let mut tasks = db.query_tasks(&TaskQuery::default()).await?;
+    tasks.sort_by_key(|t| t.id);
+    let comments = db.get_all_comments().await?;
+    let columns = db.get_columns().await?;
     
-    if let Some(pr_url) = &task.pr_url {
-        println!("🔍 Checking PR status for task #{}: {}", task_id, task.title);
-        println!("   🔗 PR: {}", pr_url);
-        println!("   ⏳ PR status check not implemented yet");
-    } else {
-        println!("❌ Task #{} has no associated PR", task_id);
+    let hits = regex_hits(&re, &tasks, &comments);
+    if hits.is_empty() {
+        println!("{} No tasks match /{}/", glyphs::icon("🔍"), query);
+        return Ok(());
+    }
+    
+    println!("{} {} tasks match /{}/", glyphs::icon("🔍"), hits.len(), query);
+    for (task, lines) in hits {
+        let column_name = columns.iter()
+            .find(|c| c.id == task.column_id)
+            .map(|c| c.name.as_str())
+            .unwrap_or("Unknown");
+        println!("  {}: {} [{}]", task_ref(task.id), highlight_regex(&task.title, &re), column_name);
+        for line in lines {
+            println!("      {}", line);
+        }
     }
     
     Ok(())
 }
 
-pub async fn board_command() -> Result<()> {
-    use crate::tui::run_board_interface;
-    run_board_interface().await
+/// Tasks whose title, description, or comments match, each with the matching
+/// description and comment lines (highlighted) to show under it
+fn regex_hits<'a>(re: &regex::Regex, tasks: &'a [Task], comments: &[Comment]) -> Vec<(&'a Task, Vec<String>)> {
+    tasks.iter()
+        .filter_map(|task| {
+            let description = task.description.as_deref().unwrap_or_default().lines()
+                .filter(|line| re.is_match(line))
+                .map(|line| format!("description: {}", highlight_regex(line.trim(), re)));
+            let comment_lines = comments.iter()
+                .filter(|c| c.task_id == task.id)
+                .flat_map(|c| c.text.lines().filter(|line| re.is_match(line)).map(move |line| (c.id, line)))
+                .map(|(id, line)| format!("comment #{}: {}", id, highlight_regex(line.trim(), re)));
+            let lines: Vec<_> = description.chain(comment_lines).collect();
+            (re.is_match(&task.title) || !lines.is_empty()).then_some((task, lines))
+        })
+        .collect()
+}
+
+fn highlight_regex(text: &str, re: &regex::Regex) -> String {
+    let mut out = String::new();
+    let mut last = 0;
+    for m in re.find_iter(text).filter(|m| !m.is_empty()) {
+        out.push_str(&text[last..m.start()]);
+        out.push_str(&paint(m.as_str(), "1;31"));
+        last = m.end();
+    }
+    out.push_str(&text[last..]);
+    out
 }
 
-pub async fn export_command(format: ExportFormat) -> Result<()> {
+pub async fn mine_command(include_archived: bool) -> Result<()> {
     let db = get_database().await?;
-    let tasks = db.get_tasks(None).await?;
+    let user = current_user()?;
+    
+    let tasks = db.query_tasks(&TaskQuery {
+        assignee: AssigneeFilter::User(user.clone()),
+        include_archived,
+        ..TaskQuery::default()
+    }).await?;
+    
+    println!("{} Tasks assigned to {} ({})", glyphs::icon("👤"), user, tasks.len());
+    if tasks.is_empty() {
+        println!("  (no tasks)");
+    }
+    for column in db.get_columns().await? {
+        let column_tasks: Vec<_> = tasks.iter().filter(|t| t.column_id == column.id).collect();
+        if column_tasks.is_empty() {
+            continue;
+        }
+        println!("\n{} {} ({})", glyphs::icon("📋"), column.name, column_tasks.len());
+        for task in column_tasks {
+            println!("  {}: {}{}", task_ref(task.id), task.title, archived_marker(task));
+        }
+    }
+    
+    Ok(())
+}
+
+pub async fn standup_command(include_archived: bool) -> Result<()> {
+    let db = get_database().await?;
+    let user = current_user()?;
+    
+    let tasks = db.query_tasks(&TaskQuery {
+        assignee: AssigneeFilter::User(user.clone()),
+        include_archived,
+        ..TaskQuery::default()
+    }).await?;
     let columns = db.get_columns().await?;
+    let in_column = |roles: &[ColumnRole]| -> Vec<&Task> {
+        let ids: Vec<i64> = columns.iter()
+            .filter(|c| c.role().is_some_and(|role| roles.contains(&role)))
+            .map(|c| c.id)
+            .collect();
+        tasks.iter().filter(|t| ids.contains(&t.column_id)).collect()
+    };
     
-    match format {
-        ExportFormat::Csv => {
-            println!("ID,Title,Description,Column,Created,Updated,Branch,PR");
-            for task in tasks {
-                let column_name = columns.iter()
-                    .find(|c| c.id == task.column_id)
-                    .map(|c| &c.name)
-                    .unwrap_or("Unknown");
-                
-                println!("{},{},{},{},{},{},{},{}",
-                    task.id,
-                    escape_csv(&task.title),
-                    escape_csv(&task.description.unwrap_or_default()),
-                    column_name,
-                    task.created_at.format("%Y-%m-%d %H:%M:%S"),
-                    task.updated_at.format("%Y-%m-%d %H:%M:%S"),
-                    task.branch_name.unwrap_or_default(),
-                    task.pr_url.unwrap_or_default()
-                );
-            }
+    let since = Utc::now() - Duration::days(1);
+    let done: Vec<_> = in_column(&[ColumnRole::Done]).into_iter().filter(|t| t.updated_at >= since).collect();
+    let sections = [
+        (format!("{} Done since yesterday", glyphs::icon("✅")), done),
+        (format!("{} In progress", glyphs::icon("🚧")), in_column(&[ColumnRole::Doing, ColumnRole::Review])),
+        (format!("{}  Up next", glyphs::icon("⏭️")), in_column(&[ColumnRole::Todo])),
+    ];
+    
+    println!("{}  Standup for {}", glyphs::icon("🗓️"), user);
+    for (heading, section) in sections {
+        println!("\n{} ({})", heading, section.len());
+        if section.is_empty() {
+            println!("  (nothing)");
         }
-        ExportFormat::Markdown => {
-            println!("# ProjectBoard Export\n");
-            for column in columns {
-                let column_tasks: Vec<_> = tasks.iter()
-                    .filter(|t| t.column_id == column.id)
-                    .collect();
-                
-                println!("## {} ({})\n", column.name, column_tasks.len());
-                for task in column_tasks {
-                    println!("- **#{}**: {}", task.id, task.title);
-                    if let Some(desc) = &task.description {
-                        println!("  - {}", desc);
-                    }
-                    if let Some(branch) = &task.branch_name {
-                        println!("  - Branch: `{}`", branch);
-                    }
-                    if let Some(pr) = &task.pr_url {
-                        println!("  - PR: {}", pr);
-                    }
-                    println!();
-                }
-            }
+        for task in section {
+            println!("  {}: {}{}", task_ref(task.id), task.title, archived_marker(task));
         }
     }
     
     Ok(())
 }
 
-// Helper functions
-async fn get_database() -> Result<Database> {
-    let repo_path = std::env::current_dir()?;
-    let db_path = repo_path.join(".projectboard").join("board.sqlite");
+pub async fn archive_command(task_id: TaskId) -> Result<()> {
+    let db = get_database().await?;
     
-    if !db_path.exists() {
-        bail!("ProjectBoard not initialized. Run 'pb init' first.");
+    let mut task = db.get_task(task_id).await?
+        .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_ref(task_id)))?;
+    if task.deleted_at.is_some() {
+        bail!("Task {} is deleted; restore it first with 'pb restore {}'", task_ref(task.id), task.id);
+    }
+    if task.archived_at.is_some() {
+        say!("{}  Task {} is already archived", glyphs::icon("ℹ️"), task_ref(task.id));
+        return Ok(());
     }
     
-    Database::new(&db_path).await
+    db.archive_task(&mut task).await?;
+    
+    // Log activity
+    db.log_task_activity("task_archived", task.id, None, Some(format!("Task #{}: {}", task.id, task.title))).await?;
+    
+    say!("{}  Archived task {}: {}", glyphs::icon("🗄️"), task_ref(task.id), task.title);
+    
+    Ok(())
 }
 
-fn get_git_user() -> Option<String> {
-    // Try to get git user name
-    std::process::Command::new("git")
-        .args(["config", "user.name"])
-        .output()
-        .ok()
-        .and_then(|output| {
-            if output.status.success() {
-                String::from_utf8(output.stdout).ok()
-                    .map(|s| s.trim().to_string())
-            } else {
-                None
-            }
-        })
+pub async fn delete_command(task_id: TaskId) -> Result<()> {
+    let db = get_database().await?;
+    
+    let mut task = db.get_task(task_id).await?
+        .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_ref(task_id)))?;
+    if task.deleted_at.is_some() {
+        say!("{}  Task {} is already deleted", glyphs::icon("ℹ️"), task_ref(task.id));
+        return Ok(());
+    }
+    
+    db.delete_task(&mut task).await?;
+    
+    // Log activity
+    db.log_task_activity("task_deleted", task.id, None, Some(format!("Task #{}: {}", task.id, task.title))).await?;
+    
+    say!("{}  Deleted task {}: {}", glyphs::icon("🗑️"), task_ref(task.id), task.title);
+    say!("   Undo with: pb restore {}", task.id);
+    
+    Ok(())
 }
 
-fn escape_csv(text: &str) -> String {
-    if text.contains(',') || text.contains('"') || text.contains('\n') {
-        format!("\"{}\"", text.replace('"', "\"\""))
-    } else {
-        text.to_string()
+pub async fn restore_command(task_id: TaskId) -> Result<()> {
+    let db = get_database().await?;
+    
+    let mut task = db.get_task(task_id).await?
+        .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_ref(task_id)))?;
+    if task.archived_at.is_none() && task.deleted_at.is_none() {
+        bail!("Task {} is not archived or deleted", task_ref(task.id));
+    }
+    
+    db.restore_task(&mut task).await?;
+    
+    // Log activity
+    db.log_task_activity("task_restored", task.id, None, Some(format!("Task #{}: {}", task.id, task.title))).await?;
+    
+    say!("{}  Restored task {}: {}", glyphs::icon("♻️"), task_ref(task.id), task.title);
+    
+    Ok(())
+}
+
+pub async fn touch_command(selector: TaskSelector) -> Result<()> {
+    let db = get_database().await?;
+    
+    let mut task = resolve_task(&db, &selector).await?;
+    db.touch_task(&mut task).await?;
+    
+    // Log activity
+    db.log_task_activity("task_touched", task.id, None, Some(format!("Task #{}: {}", task.id, task.title))).await?;
+    
+    say!("{} Touched task {}: {}", glyphs::icon("👆"), task_ref(task.id), task.title);
+    
+    Ok(())
+}
+
+pub async fn log_time_command(selector: TaskSelector, duration: String) -> Result<()> {
+    let db = get_database().await?;
+    
+    let minutes = parse_work_duration(&duration)?;
+    let task = resolve_task(&db, &selector).await?;
+    db.log_time(task.id, minutes).await?;
+    let total = db.get_logged_minutes(task.id).await?;
+    
+    // Log activity
+    db.log_task_activity(
+        "time_logged",
+        task.id,
+        None,
+        Some(format!("Logged {} on task #{}", format_minutes(minutes), task.id))
+    ).await?;
+    
+    say!("{}  Logged {} on task {}: {}", glyphs::icon("⏱️"), format_minutes(minutes), task_ref(task.id), task.title);
+    say!("   Total: {}", format_minutes(total));
+    
+    Ok(())
+}
+
+/// Minutes in a duration like `2h`, `30m`, or `1h30m`
+fn parse_work_duration(value: &str) -> Result<i64> {
+    let invalid = || anyhow::anyhow!("Invalid duration '{}': use <n>h, <n>m, or both, e.g. 1h30m", value);
+    
+    let mut minutes: i64 = 0;
+    let mut rest = value.trim();
+    if rest.is_empty() {
+        return Err(invalid());
+    }
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+        let amount: i64 = rest[..digits].parse().map_err(|_| invalid())?;
+        let per_unit = match rest[digits..].chars().next() {
+            Some('h') => 60,
+            Some('m') => 1,
+            _ => return Err(invalid()),
+        };
+        minutes = amount.checked_mul(per_unit)
+            .and_then(|m| minutes.checked_add(m))
+            .ok_or_else(invalid)?;
+        rest = &rest[digits + 1..];
+    }
+    
+    if minutes == 0 {
+        bail!("Duration '{}' is zero; there is no time to log", value);
+    }
+    Ok(minutes)
+}
+
+/// `150` as `2h 30m`
+pub(crate) fn format_minutes(minutes: i64) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h {}m", h, m),
+    }
+}
+
+/// Stop the task's timer and log the time it ran, returning the minutes logged,
+/// or None if no timer was running
+pub(crate) async fn stop_timer(db: &Database, task_id: i64) -> Result<Option<i64>> {
+    let Some(timer) = db.stop_timer(task_id).await? else {
+        return Ok(None);
+    };
+    
+    let minutes = elapsed_minutes(timer.started_at, Utc::now());
+    if minutes > 0 {
+        db.log_time(task_id, minutes).await?;
+        db.log_task_activity(
+            "time_logged",
+            task_id,
+            None,
+            Some(format!("Timer logged {} on task #{}", format_minutes(minutes), task_id))
+        ).await?;
+    }
+    
+    Ok(Some(minutes))
+}
+
+/// Whole minutes between `start` and `end`, rounded to the nearest minute
+fn elapsed_minutes(start: DateTime<Utc>, end: DateTime<Utc>) -> i64 {
+    ((end - start).num_seconds().max(0) + 30) / 60
+}
+
+pub async fn move_command(selector: TaskSelector, target: MoveTarget, force: bool) -> Result<()> {
+    let db = get_database().await?;
+    
+    // Get the task
+    let mut task = resolve_task(&db, &selector).await?;
+    
+    // Get current column for logging
+    let columns = db.get_columns().await?;
+    let current_index = columns.iter()
+        .position(|c| c.id == task.column_id)
+        .ok_or_else(|| anyhow::anyhow!("Current column not found"))?;
+    let current_column = &columns[current_index];
+    
+    // Get the target column
+    let target_column = match target {
+        MoveTarget::Column(column_name) => db.get_column_by_name(&column_name).await?
+            .ok_or_else(|| anyhow::anyhow!("Column '{}' not found", column_name))?,
+        MoveTarget::Role(role) => column_for_role(&db, role).await?,
+        MoveTarget::Next => columns.get(current_index + 1)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Task {} is already in the last column ({})", task_ref(task.id), current_column.name))?,
+        MoveTarget::Prev => current_index.checked_sub(1)
+            .and_then(|i| columns.get(i))
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Task {} is already in the first column ({})", task_ref(task.id), current_column.name))?,
+        MoveTarget::Undo => {
+            let last = db.query_activity(&ActivityFilter {
+                limit: Some(1),
+                task_id: Some(task.id),
+                ..Default::default()
+            }).await?;
+            let column_name = undo_move_source(task.id, last.first(), &current_column.name)?;
+            db.get_column_by_name(&column_name).await?
+                .ok_or_else(|| anyhow::anyhow!("Column '{}' no longer exists", column_name))?
+        }
+        MoveTarget::Back => match db.get_column_by_name("back").await? {
+            // A column really named "back" wins over the history lookup
+            Some(column) => column,
+            None => {
+                let last_move = db.query_activity(&ActivityFilter {
+                    limit: Some(1),
+                    event: Some("task_moved".to_string()),
+                    task_id: Some(task.id),
+                    ..Default::default()
+                }).await?;
+                let column_name = back_move_source(task.id, last_move.first(), &current_column.name)?;
+                db.get_column_by_name(&column_name).await?
+                    .ok_or_else(|| anyhow::anyhow!("Column '{}' no longer exists", column_name))?
+            }
+        },
+    };
+    
+    if target_column.id != current_column.id && !force {
+        let load = db.get_columns_with_limits().await?
+            .into_iter()
+            .find(|c| c.column.id == target_column.id);
+        if let Some(ColumnWithLimit { task_count, wip_limit: Some(limit), .. }) = load.filter(|c| c.is_full()) {
+            bail!(
+                "{} is at its WIP limit ({}/{}); use --force to move anyway",
+                target_column.name, task_count, limit
+            );
+        }
+    }
+    
+    // With move_triggers_git, entering Doing or Done does the git half of start or done first
+    let mut git_actions = Vec::new();
+    if target_column.id != current_column.id {
+        match target_column.role() {
+            Some(ColumnRole::Doing) => {
+                let config = get_config()?;
+                if config.move_triggers_git && config.create_branches && task.branch_name.is_none() {
+                    let (name, _) = create_task_branch(&db, &mut task, false, None).await?;
+                    say!("{} Created and checked out branch: {}", glyphs::icon("🌿"), name);
+                    git_actions.push(format!("created branch {}", name));
+                }
+            }
+            Some(ColumnRole::Done) => {
+                let config = get_config()?;
+                if config.move_triggers_git && task.branch_name.is_some() {
+                    git_actions = finish_task_git(&db, &config, &mut task, None, false, false).await?;
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    // Update the task
+    db.update_task_column(&mut task, target_column.id).await?;
+    
+    // Log activity
+    let mut details = format!("Task #{}: {} → {}", task.id, current_column.name, target_column.name);
+    if !git_actions.is_empty() {
+        details.push_str(&format!(" ({})", git_actions.join(", ")));
+    }
+    db.log_task_activity(
+        "task_moved", 
+        task.id,
+        Some((&current_column.name, &target_column)),
+        Some(details)
+    ).await?;
+    
+    // Work stops once the task is done, however it got there
+    let logged = match target_column.role() {
+        Some(ColumnRole::Done) if target_column.id != current_column.id => stop_timer(&db, task.id).await?,
+        _ => None,
+    };
+    
+    say!("{} Moved task {}: {} → {}", glyphs::icon("📦"), task_ref(task.id), current_column.name, target_column.name);
+    say!("   {}", task.title);
+    if let Some(minutes) = logged {
+        say!("   {}  Timer stopped: {} logged", glyphs::icon("⏱️"), format_minutes(minutes));
+    }
+    
+    Ok(())
+}
+
+/// The column to send a task back to, provided its last activity was a move
+/// that left it in `current_column`
+fn undo_move_source(task_id: i64, last: Option<&ActivityLog>, current_column: &str) -> Result<String> {
+    let entry = last.ok_or_else(|| anyhow::anyhow!("Task {} has no activity to undo", task_ref(task_id)))?;
+    if entry.event != "task_moved" {
+        bail!("Task {}'s last change was '{}', not a move; nothing to undo", task_ref(task_id), entry.event);
+    }
+    match (&entry.column_from, &entry.column_to) {
+        (Some(from), Some(to)) if to == current_column => Ok(from.clone()),
+        (Some(_), Some(to)) => bail!("Task {} was moved to {} but is now in {}", task_ref(task_id), to, current_column),
+        _ => bail!("Task {}'s last move predates column tracking and can't be undone", task_ref(task_id)),
+    }
+}
+
+/// The column the task's last `task_moved` event came from. Unlike
+/// `undo_move_source`, other activity since that move doesn't matter.
+fn back_move_source(task_id: i64, last_move: Option<&ActivityLog>, current_column: &str) -> Result<String> {
+    let entry = last_move.ok_or_else(|| anyhow::anyhow!("Task {} has no recorded move to go back from", task_ref(task_id)))?;
+    match &entry.column_from {
+        Some(from) if from == current_column => bail!("Task {} is already in {}, where its last move came from", task_ref(task_id), from),
+        Some(from) => Ok(from.clone()),
+        None => bail!("Task {}'s last move predates column tracking, so its source is unknown", task_ref(task_id)),
+    }
+}
+
+pub async fn column_add_command(name: String) -> Result<()> {
+    let db = get_database().await?;
+    
+    if db.get_column_by_name(&name).await?.is_some() {
+        bail!("Column '{}' already exists", name);
+    }
+    
+    let column = db.create_column(&name).await?;
+    
+    // Log activity
+    db.log_activity("column_added", Some(format!("Column {}", column.name))).await?;
+    
+    say!("{} Added column: {}", glyphs::icon("📋"), column.name);
+    
+    Ok(())
+}
+
+pub async fn column_move_command(name: String, before: Option<String>, after: Option<String>) -> Result<()> {
+    let db = get_database().await?;
+    
+    let column = db.get_column_by_name(&name).await?
+        .ok_or_else(|| anyhow::anyhow!("Column '{}' not found", name))?;
+    
+    let (anchor_name, after_anchor) = match (before, after) {
+        (Some(other), None) => (other, false),
+        (None, Some(other)) => (other, true),
+        _ => bail!("Specify exactly one of --before or --after"),
+    };
+    if anchor_name == column.name {
+        bail!("Cannot move column '{}' relative to itself", column.name);
+    }
+    
+    // Work out the target index among the remaining columns
+    let remaining: Vec<_> = db.get_columns().await?
+        .into_iter()
+        .filter(|c| c.id != column.id)
+        .collect();
+    let anchor_index = remaining.iter()
+        .position(|c| c.name == anchor_name)
+        .ok_or_else(|| anyhow::anyhow!("Column '{}' not found", anchor_name))?;
+    let position = if after_anchor { anchor_index + 1 } else { anchor_index };
+    
+    let columns = db.reorder_column(column.id, position).await?;
+    
+    // Log activity
+    db.log_activity(
+        "column_moved", 
+        Some(format!("Column {}: {} {}", column.name, if after_anchor { "after" } else { "before" }, anchor_name))
+    ).await?;
+    
+    say!("{} Moved column {} {} {}", glyphs::icon("📋"), column.name, if after_anchor { "after" } else { "before" }, anchor_name);
+    let names: Vec<_> = columns.iter().map(|c| c.name.as_str()).collect();
+    say!("   {}", names.join(" → "));
+    
+    Ok(())
+}
+
+pub async fn column_rename_command(name: String, new_name: String) -> Result<()> {
+    let db = get_database().await?;
+    
+    let column = db.get_column_by_name(&name).await?
+        .ok_or_else(|| anyhow::anyhow!("Column '{}' not found", name))?;
+    let new_name = new_name.trim();
+    if new_name.is_empty() {
+        bail!("Column name cannot be empty");
+    }
+    if db.get_column_by_name(new_name).await?.is_some() {
+        bail!("Column '{}' already exists", new_name);
+    }
+    
+    db.rename_column(column.id, new_name).await?;
+    
+    // Log activity
+    db.log_activity("column_renamed", Some(format!("Column {} → {}", column.name, new_name))).await?;
+    
+    say!("{} Renamed column: {} → {}", glyphs::icon("📋"), column.name, new_name);
+    
+    Ok(())
+}
+
+pub async fn column_set_role_command(name: String, role: ColumnRole) -> Result<()> {
+    let db = get_database().await?;
+    
+    let column = db.get_column_by_name(&name).await?
+        .ok_or_else(|| anyhow::anyhow!("Column '{}' not found", name))?;
+    let previous = db.get_column_by_role(role).await?;
+    
+    db.set_column_role(column.id, role).await?;
+    
+    // Log activity
+    db.log_activity("column_role_set", Some(format!("Column {}: {}", column.name, role))).await?;
+    
+    say!("{} {} is now the {} column", glyphs::icon("🎭"), column.name, role);
+    if let Some(previous) = previous.filter(|c| c.id != column.id) {
+        say!("   (was {})", previous.name);
+    }
+    if let Some(old_role) = column.role().filter(|r| *r != role) {
+        println!("   {}  {} no longer has the {} role", glyphs::icon("⚠️"), column.name, old_role);
+    }
+    
+    Ok(())
+}
+
+pub async fn column_limit_command(name: String, limit: String) -> Result<()> {
+    let db = get_database().await?;
+    
+    let column = db.get_column_by_name(&name).await?
+        .ok_or_else(|| anyhow::anyhow!("Column '{}' not found", name))?;
+    
+    let wip_limit = if limit.eq_ignore_ascii_case("none") {
+        None
+    } else {
+        match limit.parse::<i64>() {
+            Ok(n) if n > 0 => Some(n),
+            _ => bail!("'{}' is not a WIP limit (a positive number, or none)", limit),
+        }
+    };
+    
+    db.set_column_limit(column.id, wip_limit).await?;
+    
+    // Log activity
+    let details = match wip_limit {
+        Some(n) => format!("Column {}: WIP limit {}", column.name, n),
+        None => format!("Column {}: WIP limit removed", column.name),
+    };
+    db.log_activity("column_limit_set", Some(details)).await?;
+    
+    match wip_limit {
+        Some(n) => {
+            say!("{} Set WIP limit for {} to {}", glyphs::icon("🚦"), column.name, n);
+            let count = db.count_tasks(column.id).await?;
+            if count > n {
+                println!("   {}  {} already has {} tasks", glyphs::icon("⚠️"), column.name, count);
+            }
+        }
+        None => say!("{} Removed WIP limit for {}", glyphs::icon("🚦"), column.name),
+    }
+    
+    Ok(())
+}
+
+pub async fn comment_command(task_id: TaskId, text: Option<String>, edit: bool) -> Result<()> {
+    let db = get_database().await?;
+    
+    // Verify task exists
+    let task = db.get_task(task_id).await?
+        .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_ref(task_id)))?;
+    
+    let text = match text {
+        Some(text) => text,
+        None if edit => edit_in_editor(&format!("Comment on task {}: {}", task_ref(task.id), task.title), "")?,
+        None => bail!("Give the comment text, or use --edit to write it in $EDITOR"),
+    };
+    if text.trim().is_empty() {
+        bail!("Comment is empty, nothing was added");
+    }
+    
+    // Get current user (from git config)
+    let author = get_git_user().unwrap_or_else(|| "unknown".to_string());
+    
+    // Create comment
+    let comment = db.create_comment(task.id, &author, &text).await?;
+    let mentions = parse_mentions(&text);
+    db.add_mentions(comment.id, &mentions).await?;
+    
+    // Log activity
+    db.log_task_activity(
+        "comment_added", 
+        task.id,
+        None,
+        Some(format!("Task #{}: comment by {}", task.id, author))
+    ).await?;
+    
+    say!("{} Added comment to task {}: {}", glyphs::icon("💬"), task_ref(task_id), task.title);
+    say!("   {}: {}", author, text);
+    if !mentions.is_empty() {
+        let names: Vec<_> = mentions.iter().map(|m| format!("@{}", m)).collect();
+        say!("   {} Mentioned: {}", glyphs::icon("📣"), names.join(", "));
+    }
+    
+    Ok(())
+}
+
+pub async fn comment_react_command(comment_id: u32, emoji: String) -> Result<()> {
+    let db = get_database().await?;
+    
+    let comment = db.get_comment(comment_id as i64).await?
+        .ok_or_else(|| anyhow::anyhow!("Comment #{} not found", comment_id))?;
+    
+    let emoji = emoji.trim();
+    if emoji.is_empty() || emoji.chars().any(char::is_whitespace) || emoji.chars().count() > 8 {
+        bail!("'{}' is not a reaction; use a single emoji like 👍", emoji);
+    }
+    
+    let author = get_git_user().unwrap_or_else(|| "unknown".to_string());
+    if !db.add_reaction(comment.id, &author, emoji).await? {
+        say!("{} You already reacted to comment #{} with {}", emoji, comment.id, emoji);
+        return Ok(());
+    }
+    
+    // Log activity
+    db.log_task_activity(
+        "comment_reacted", 
+        comment.task_id,
+        None,
+        Some(format!("Task #{}: {} reacted {} to comment #{}", comment.task_id, author, emoji, comment.id))
+    ).await?;
+    
+    say!("{} Reacted to comment #{} on task {}", emoji, comment.id, task_ref(comment.task_id));
+    say!("   {}: {}", comment.author, comment.text);
+    
+    Ok(())
+}
+
+pub async fn comment_resolve_command(comment_id: u32, resolved: bool) -> Result<()> {
+    let db = get_database().await?;
+    
+    let comment = db.get_comment(comment_id as i64).await?
+        .ok_or_else(|| anyhow::anyhow!("Comment #{} not found", comment_id))?;
+    
+    if !db.set_comment_resolved(comment.id, resolved).await? {
+        let state = if resolved { "resolved" } else { "unresolved" };
+        say!("{}  Comment #{} is already {}", glyphs::icon("ℹ️"), comment.id, state);
+        return Ok(());
+    }
+    
+    // Log activity
+    let (event, verb) = if resolved { ("comment_resolved", "resolved") } else { ("comment_unresolved", "reopened") };
+    db.log_task_activity(
+        event,
+        comment.task_id,
+        None,
+        Some(format!("Task #{}: {} comment #{}", comment.task_id, verb, comment.id))
+    ).await?;
+    
+    if resolved {
+        say!("{} Resolved comment #{} on task {}", glyphs::icon("✅"), comment.id, task_ref(comment.task_id));
+    } else {
+        say!("{}  Reopened comment #{} on task {}", glyphs::icon("↩️"), comment.id, task_ref(comment.task_id));
+    }
+    say!("   {}: {}", comment.author, comment.text);
+    
+    Ok(())
+}
+
+pub async fn link_add_command(task_id: TaskId, label: String, url: String) -> Result<()> {
+    let db = get_database().await?;
+    
+    // Verify task exists
+    let task = db.get_task(task_id).await?
+        .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_ref(task_id)))?;
+    
+    validate_url(&url)?;
+    
+    let link = db.create_task_link(task.id, &label, &url).await?;
+    
+    // Log activity
+    db.log_task_activity(
+        "link_added", 
+        task.id,
+        None,
+        Some(format!("Task #{}: {} → {}", task.id, link.label, link.url))
+    ).await?;
+    
+    say!("{} Added link to task {}: {}", glyphs::icon("🔗"), task_ref(task_id), task.title);
+    say!("   {}: {}", link.label, link.url);
+    
+    Ok(())
+}
+
+pub async fn link_list_command(task_id: TaskId) -> Result<()> {
+    let db = get_database().await?;
+    
+    // Verify task exists
+    let task = db.get_task(task_id).await?
+        .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_ref(task_id)))?;
+    
+    let links = db.get_task_links(task.id).await?;
+    
+    println!("{} Links for task {}: {} ({} links)", glyphs::icon("🔗"), task_ref(task_id), task.title, links.len());
+    if links.is_empty() {
+        println!("  (no links)");
+    }
+    for link in links {
+        println!("  - {}: {}", link.label, link.url);
+    }
+    
+    Ok(())
+}
+
+pub async fn dep_add_command(task_id: TaskId, blocked_by: TaskId) -> Result<()> {
+    let db = get_database().await?;
+    
+    if task_id == blocked_by {
+        bail!("A task can't block itself");
+    }
+    let task = db.get_task(task_id).await?
+        .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_ref(task_id)))?;
+    let blocker = db.get_task(blocked_by).await?
+        .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_ref(blocked_by)))?;
+    
+    let dependencies = db.get_dependencies().await?;
+    if depends_on(&dependencies, blocker.id, task.id) {
+        bail!(
+            "Task {} already depends on {}; adding this would create a cycle",
+            task_ref(blocker.id), task_ref(task.id)
+        );
+    }
+    
+    if !db.add_dependency(task.id, blocker.id).await? {
+        say!("{}  Task {} is already blocked by {}", glyphs::icon("ℹ️"), task_ref(task.id), task_ref(blocker.id));
+        return Ok(());
+    }
+    
+    // Log activity
+    db.log_task_activity(
+        "dependency_added",
+        task.id,
+        None,
+        Some(format!("Task #{} blocked by #{}", task.id, blocker.id))
+    ).await?;
+    
+    say!("{} Task {}: {}", glyphs::icon("⛔"), task_ref(task.id), task.title);
+    say!("   is now blocked by {}: {}", task_ref(blocker.id), blocker.title);
+    
+    Ok(())
+}
+
+pub async fn dep_remove_command(task_id: TaskId, blocked_by: TaskId) -> Result<()> {
+    let db = get_database().await?;
+    
+    if !db.remove_dependency(task_id.get(), blocked_by.get()).await? {
+        bail!("Task {} is not blocked by {}", task_ref(task_id), task_ref(blocked_by));
+    }
+    
+    // Log activity
+    db.log_task_activity(
+        "dependency_removed",
+        task_id.get(),
+        None,
+        Some(format!("Task #{} no longer blocked by #{}", task_id, blocked_by))
+    ).await?;
+    
+    say!("{} Task {} is no longer blocked by {}", glyphs::icon("✅"), task_ref(task_id), task_ref(blocked_by));
+    
+    Ok(())
+}
+
+/// Whether `task_id` already waits on `target`, directly or through other tasks
+fn depends_on(dependencies: &[TaskDependency], task_id: i64, target: i64) -> bool {
+    let mut pending = vec![task_id];
+    let mut seen = std::collections::HashSet::new();
+    while let Some(current) = pending.pop() {
+        if current == target {
+            return true;
+        }
+        if seen.insert(current) {
+            pending.extend(dependencies.iter().filter(|d| d.task_id == current).map(|d| d.blocked_by));
+        }
+    }
+    false
+}
+
+pub async fn label_create_command(name: String) -> Result<()> {
+    let db = get_database().await?;
+    
+    let name = label_name(&name)?;
+    if db.get_label_by_name(name).await?.is_some() {
+        bail!("Label '{}' already exists", name);
+    }
+    
+    let label = db.create_label(name).await?;
+    
+    // Log activity
+    db.log_activity("label_created", Some(format!("Label: {}", label.name))).await?;
+    
+    say!("{}  Created label: {}", glyphs::icon("🏷️"), label.name);
+    
+    Ok(())
+}
+
+/// `name` trimmed; empty names and names with a comma are refused
+fn label_name(name: &str) -> Result<&str> {
+    let name = name.trim();
+    if name.is_empty() {
+        bail!("Label name cannot be empty");
+    }
+    if name.contains(',') {
+        bail!("Label name '{}' cannot contain a comma", name);
+    }
+    Ok(name)
+}
+
+/// Each of `names` checked with `label_name`, with repeats dropped
+fn label_names(names: &[String]) -> Result<Vec<String>> {
+    let mut labels: Vec<String> = Vec::new();
+    for name in names {
+        let name = label_name(name)?;
+        if !labels.iter().any(|l| l == name) {
+            labels.push(name.to_string());
+        }
+    }
+    Ok(labels)
+}
+
+pub async fn label_add_command(task_id: TaskId, name: String) -> Result<()> {
+    let db = get_database().await?;
+    
+    let task = db.get_task(task_id).await?
+        .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_ref(task_id)))?;
+    let label = db.get_label_by_name(&name).await?
+        .ok_or_else(|| anyhow::anyhow!("Label '{}' not found. Create it with: pb label create \"{}\"", name, name))?;
+    
+    if !db.add_task_label(task.id, label.id).await? {
+        say!("{}  Task {} is already labeled {}", glyphs::icon("ℹ️"), task_ref(task.id), label.name);
+        return Ok(());
+    }
+    
+    // Log activity
+    db.log_task_activity("label_added", task.id, None, Some(format!("Task #{}: {}", task.id, label.name))).await?;
+    
+    say!("{}  Labeled task {} ({}): {}", glyphs::icon("🏷️"), task_ref(task.id), task.title, label.name);
+    
+    Ok(())
+}
+
+pub async fn label_remove_command(task_id: TaskId, name: String) -> Result<()> {
+    let db = get_database().await?;
+    
+    let task = db.get_task(task_id).await?
+        .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_ref(task_id)))?;
+    let label = db.get_label_by_name(&name).await?
+        .ok_or_else(|| anyhow::anyhow!("Label '{}' not found", name))?;
+    
+    if !db.remove_task_label(task.id, label.id).await? {
+        bail!("Task {} is not labeled {}", task_ref(task.id), label.name);
+    }
+    
+    // Log activity
+    db.log_task_activity("label_removed", task.id, None, Some(format!("Task #{}: {}", task.id, label.name))).await?;
+    
+    say!("{}  Removed label {} from task {}", glyphs::icon("🏷️"), label.name, task_ref(task.id));
+    
+    Ok(())
+}
+
+pub async fn label_list_command() -> Result<()> {
+    let db = get_database().await?;
+    
+    let labels = db.get_labels().await?;
+    let task_labels = db.get_all_task_labels().await?;
+    
+    println!("{}  Labels ({})", glyphs::icon("🏷️"), labels.len());
+    if labels.is_empty() {
+        println!("  (no labels)");
+    }
+    for label in labels {
+        let count = task_labels.iter().filter(|tl| tl.name == label.name).count();
+        println!("  - {} ({} tasks)", label.name, count);
+    }
+    
+    Ok(())
+}
+
+pub async fn idea_command(content: String, promote: bool) -> Result<()> {
+    let db = get_database().await?;
+    
+    let idea = db.create_idea(&content).await?;
+    
+    // Log activity
+    db.log_activity("idea_created", Some(format!("Idea #{}: {}", idea.id, content))).await?;
+    
+    say!("{} Created idea #{}: {}", glyphs::icon("💡"), idea.id, content);
+    
+    if promote {
+        promote_idea(&db, idea, None).await?;
+    }
+    
+    Ok(())
+}
+
+pub async fn idea_edit_command(idea_id: u32, content: Option<String>) -> Result<()> {
+    let db = get_database().await?;
+    
+    // Get the idea
+    let idea = db.get_idea(idea_id as i64).await?
+        .ok_or_else(|| anyhow::anyhow!("Idea #{} not found", idea_id))?;
+    
+    let content = match content {
+        Some(content) => content,
+        None => edit_in_editor(&format!("Editing idea #{}", idea.id), &idea.content)?,
+    };
+    if content.trim().is_empty() {
+        bail!("Idea content cannot be empty");
+    }
+    
+    db.update_idea(idea.id, &content).await?;
+    
+    // Log activity
+    db.log_activity(
+        "idea_edited", 
+        Some(format!("Idea #{}: {} → {}", idea.id, idea.content, content))
+    ).await?;
+    
+    say!("{} Updated idea #{}: {}", glyphs::icon("💡"), idea.id, content);
+    
+    Ok(())
+}
+
+pub async fn promote_command(idea_id: u32, description: Option<String>) -> Result<()> {
+    let db = get_database().await?;
+    
+    // Get the idea
+    let idea = db.get_idea(idea_id as i64).await?
+        .ok_or_else(|| anyhow::anyhow!("Idea #{} not found", idea_id))?;
+    
+    promote_idea(&db, idea, description).await?;
+    Ok(())
+}
+
+async fn promote_idea(db: &Database, idea: Idea, description: Option<String>) -> Result<Task> {
+    // Get Backlog column
+    let backlog_column = column_for_role(db, ColumnRole::Backlog).await?;
+    
+    // Replace the idea with a task, splitting "title :: description" when present
+    let (title, split_description) = split_idea_content(&idea.content);
+    let description = description.or(split_description);
+    let task = db.promote_idea(idea.id, &title, description.clone(), backlog_column.id).await?;
+    
+    // Log activity
+    db.log_task_activity(
+        "idea_promoted", 
+        task.id,
+        None,
+        Some(format!("Idea #{} → Task #{}: {}", idea.id, task.id, task.title))
+    ).await?;
+    
+    say!("{} Promoted idea #{} to task {}: {}", glyphs::icon("🚀"), idea.id, task_ref(task.id), task.title);
+    if let Some(desc) = description {
+        say!("   Description: {}", desc);
+    }
+    
+    Ok(task)
+}
+
+pub async fn start_command(selector: TaskSelector, worktree: bool, no_branch: bool, from: Option<String>) -> Result<()> {
+    let db = get_database().await?;
+    let config = get_config()?;
+    
+    // Get the task
+    let task = resolve_task(&db, &selector).await?;
+    
+    start_task(&db, &config, task, worktree, no_branch, from).await
+}
+
+/// `pb start --idea`: promote the idea and start the new task in one go
+pub async fn start_idea_command(idea_id: u32, worktree: bool, no_branch: bool, from: Option<String>) -> Result<()> {
+    let db = get_database().await?;
+    let config = get_config()?;
+    
+    let idea = db.get_idea(idea_id as i64).await?
+        .ok_or_else(|| anyhow::anyhow!("Idea #{} not found", idea_id))?;
+    // Refuse bad options before the idea is used up
+    start_creates_branch(&config, worktree, no_branch, from.as_deref())?;
+    
+    let task = promote_idea(&db, idea, None).await?;
+    let task_id = task.id;
+    // Git can't roll back with the database, so a failed start leaves the task in the backlog
+    start_task(&db, &config, task, worktree, no_branch, from).await
+        .with_context(|| format!("Task {} was created from idea #{} but not started; run 'pb start {}' once fixed", task_ref(task_id), idea_id, task_id))
+}
+
+/// Whether `pb start` makes a branch, refusing branch options when it won't
+fn start_creates_branch(config: &Config, worktree: bool, no_branch: bool, from: Option<&str>) -> Result<bool> {
+    let create_branch = !no_branch && config.create_branches;
+    if worktree && !create_branch {
+        bail!("--worktree needs a task branch, but branch creation is disabled");
+    }
+    if from.is_some() && !create_branch {
+        bail!("--from needs a task branch, but branch creation is disabled");
+    }
+    Ok(create_branch)
+}
+
+/// Tail of the `task_started` log entry for a task started without a branch;
+/// `pb check` looks for it to tell these apart from Doing tasks that lost theirs
+const STARTED_WITHOUT_BRANCH: &str = "started without a branch";
+
+async fn start_task(
+    db: &Database,
+    config: &Config,
+    mut task: Task,
+    worktree: bool,
+    no_branch: bool,
+    from: Option<String>,
+) -> Result<()> {
+    let create_branch = start_creates_branch(config, worktree, no_branch, from.as_deref())?;
+    
+    let mut branch_name = None;
+    let mut worktree_path = None;
+    if create_branch {
+        let (name, path) = create_task_branch(db, &mut task, worktree, from.as_deref()).await?;
+        branch_name = Some(name);
+        worktree_path = path;
+    }
+    
+    // Whoever starts an unassigned task picks it up
+    let assignee = match &task.assignee {
+        Some(_) => None,
+        None => get_git_user(),
+    };
+    if let Some(assignee) = &assignee {
+        db.update_task_assignee(&mut task, Some(assignee)).await?;
+    }
+    
+    // Move task to "Doing" column
+    let doing_column = column_for_role(db, ColumnRole::Doing).await?;
+    let from_column = column_name(db, task.column_id).await?;
+    db.update_task_column(&mut task, doing_column.id).await?;
+    
+    // Log activity
+    let details = match (&branch_name, &from) {
+        (Some(name), Some(base)) => format!("Task #{}: created branch {} from {}", task.id, name, base),
+        (Some(name), None) => format!("Task #{}: created branch {}", task.id, name),
+        (None, _) => format!("Task #{}: {}", task.id, STARTED_WITHOUT_BRANCH),
+    };
+    db.log_task_activity("task_started", task.id, Some((&from_column, &doing_column)), Some(details)).await?;
+    
+    // One timer at a time: starting a task stops whatever else was running
+    let mut stopped = Vec::new();
+    let mut timer_started = false;
+    if config.track_time {
+        for timer in db.get_timers().await?.into_iter().filter(|t| t.task_id != task.id) {
+            if let Some(minutes) = stop_timer(db, timer.task_id).await? {
+                stopped.push((timer.task_id, minutes));
+            }
+        }
+        timer_started = db.start_timer(task.id).await?;
+    }
+    
+    say!("{} Started task {}: {}", glyphs::icon("🚀"), task_ref(task.id), task.title);
+    match (&branch_name, &worktree_path) {
+        (Some(name), Some(path)) => {
+            say!("   {} Created branch {} in worktree: {}", glyphs::icon("🌿"), name, path.display());
+            say!("   {} cd {}", glyphs::icon("👉"), path.display());
+        }
+        (Some(name), None) => say!("   {} Created and checked out branch: {}", glyphs::icon("🌿"), name),
+        (None, _) => say!("   {} No branch created", glyphs::icon("🌿")),
+    }
+    if let (Some(_), Some(base)) = (&branch_name, &from) {
+        say!("   {} Based on: {}", glyphs::icon("🌿"), base);
+    }
+    if let Some(assignee) = &assignee {
+        say!("   {} Assigned to: {}", glyphs::icon("👤"), assignee);
+    }
+    say!("   {} Moved to: {}", glyphs::icon("📦"), doing_column.name);
+    for (task_id, minutes) in stopped {
+        say!("   {}  Stopped the timer on task {} ({} logged)", glyphs::icon("⏱️"), task_ref(task_id), format_minutes(minutes));
+    }
+    if timer_started {
+        say!("   {}  Timer started", glyphs::icon("⏱️"));
+    }
+    
+    Ok(())
+}
+
+/// Create the task's branch and check it out, either here or in a new worktree,
+/// and record both on the task
+async fn create_task_branch(
+    db: &Database,
+    task: &mut Task,
+    worktree: bool,
+    from: Option<&str>,
+) -> Result<(String, Option<PathBuf>)> {
+    let repo_path = std::env::current_dir()?;
+    let git_repo = GitRepo::open(&repo_path)?;
+    
+    // Generate branch name
+    let name = branch_name_for(task.id, &task.title);
+    
+    git_repo.create_branch(&name, from)?;
+    let mut worktree_path = None;
+    if worktree {
+        let path = git_repo.create_worktree(&name)?;
+        db.update_task_worktree(task, Some(&path.display().to_string())).await?;
+        worktree_path = Some(path);
+    } else {
+        git_repo.checkout_branch(&name)?;
+    }
+    
+    // Update task with branch name
+    db.update_task_branch(task, Some(&name)).await?;
+    
+    Ok((name, worktree_path))
+}
+
+pub async fn abandon_command(selector: TaskSelector, delete_branch: bool, column: Option<String>, force: bool) -> Result<()> {
+    let db = get_database().await?;
+    let config = get_config()?;
+    
+    let mut task = resolve_task(&db, &selector).await?;
+    let target_column = match column {
+        Some(column) => db.get_column_by_name(&column).await?
+            .ok_or_else(|| anyhow::anyhow!("Column '{}' not found", column))?,
+        None => column_for_role(&db, ColumnRole::Backlog).await?,
+    };
+    
+    if !force {
+        let mut prompt = format!("{}  Abandon task {} and move it to {}?", glyphs::icon("⚠️"), task_ref(task.id), target_column.name);
+        if let Some(path) = &task.worktree_path {
+            prompt.push_str(&format!(" Its worktree {} will be removed.", path));
+        }
+        if let (true, Some(branch)) = (delete_branch, &task.branch_name) {
+            prompt.push_str(&format!(" Branch {} will be deleted.", branch));
+        }
+        if !confirm(&prompt)? {
+            println!("Abandon cancelled");
+            return Ok(());
+        }
+    }
+    
+    if let Some(branch) = task.branch_name.clone() {
+        let repo_path = std::env::current_dir()?;
+        let git_repo = GitRepo::open(&repo_path)?;
+        
+        if let Some(path) = task.worktree_path.clone() {
+            git_repo.prune_worktree(Path::new(&path))?;
+            db.update_task_worktree(&mut task, None).await?;
+            say!("   {} Removed worktree: {}", glyphs::icon("🧹"), path);
+        }
+        
+        // Get off the task branch before it goes away
+        if git_repo.get_current_branch()?.as_deref() == Some(branch.as_str()) {
+            git_repo.checkout_branch(&config.base_branch)?;
+            say!("   {} Checked out {}", glyphs::icon("🌿"), config.base_branch);
+        }
+        
+        if delete_branch {
+            git_repo.delete_branch(&branch)?;
+            say!("   {}  Deleted branch: {}", glyphs::icon("🗑️"), branch);
+        }
+        db.update_task_branch(&mut task, None).await?;
+    }
+    
+    let from_column = column_name(&db, task.column_id).await?;
+    db.update_task_column(&mut task, target_column.id).await?;
+    
+    // Log activity
+    db.log_task_activity(
+        "task_abandoned", 
+        task.id,
+        Some((&from_column, &target_column)),
+        Some(format!("Task #{}: back to {}", task.id, target_column.name))
+    ).await?;
+    
+    // The time was still spent, even if the work wasn't finished
+    let logged = stop_timer(&db, task.id).await?;
+    
+    say!("{}  Abandoned task {}: {}", glyphs::icon("↩️"), task_ref(task.id), task.title);
+    say!("   {} Moved to: {}", glyphs::icon("📦"), target_column.name);
+    if let Some(minutes) = logged {
+        say!("   {}  Timer stopped: {} logged", glyphs::icon("⏱️"), format_minutes(minutes));
+    }
+    
+    Ok(())
+}
+
+pub async fn rename_branch_command(task_id: TaskId, remote: bool) -> Result<()> {
+    let db = get_database().await?;
+    let config = get_config()?;
+    let repo_path = std::env::current_dir()?;
+    let git_repo = GitRepo::open(&repo_path)?;
+    
+    // Get the task
+    let mut task = db.get_task(task_id).await?
+        .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_ref(task_id)))?;
+    
+    let old_branch = task.branch_name.clone()
+        .ok_or_else(|| anyhow::anyhow!("Task {} has no associated branch", task_ref(task_id)))?;
+    let new_branch = branch_name_for(task.id, &task.title);
+    
+    if old_branch == new_branch {
+        say!("{} Branch for task {} already matches its title: {}", glyphs::icon("✅"), task_ref(task_id), old_branch);
+        return Ok(());
+    }
+    
+    // Rename the local branch, then the remote one if asked
+    git_repo.rename_branch(&old_branch, &new_branch)?;
+    if remote {
+        git_repo.rename_remote_branch(&config.remote, &old_branch, &new_branch)?;
+    }
+    
+    db.update_task_branch(&mut task, Some(&new_branch)).await?;
+    
+    // Log activity
+    db.log_task_activity(
+        "branch_renamed", 
+        task.id,
+        None,
+        Some(format!("Task #{}: {} → {}", task.id, old_branch, new_branch))
+    ).await?;
+    
+    say!("{} Renamed branch for task {}: {} → {}", glyphs::icon("🌿"), task_ref(task_id), old_branch, new_branch);
+    if let Some(pr) = &task.pr_url {
+        println!("{}  PR still references the old branch: {}", glyphs::icon("⚠️"), pr);
+    }
+    
+    Ok(())
+}
+
+pub async fn done_command(task_id: TaskId, message: Option<String>, prune_worktree: bool, force: bool) -> Result<()> {
+    let db = get_database().await?;
+    let config = get_config()?;
+    
+    // Get the task
+    let mut task = db.get_task(task_id).await?
+        .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_ref(task_id)))?;
+    
+    // Branchless tasks go straight to Done without touching git
+    if task.branch_name.is_some() {
+        finish_task_git(&db, &config, &mut task, message, prune_worktree, force).await?;
+    } else {
+        say!("{} Task {} has no branch, skipping commit and push", glyphs::icon("🌿"), task_ref(task_id));
+    }
+    
+    // Move task to "Done" column
+    let done_column = column_for_role(&db, ColumnRole::Done).await?;
+    let from_column = column_name(&db, task.column_id).await?;
+    db.update_task_column(&mut task, done_column.id).await?;
+    
+    // Log activity
+    db.log_task_activity(
+        "task_completed", 
+        task.id,
+        Some((&from_column, &done_column)),
+        Some(format!("Task #{}: {}", task.id, task.title))
+    ).await?;
+    
+    let logged = stop_timer(&db, task.id).await?;
+    
+    say!("{} Completed task {}: {}", glyphs::icon("✅"), task_ref(task_id), task.title);
+    say!("   {} Moved to: {}", glyphs::icon("📦"), done_column.name);
+    if let Some(minutes) = logged {
+        say!("   {}  Timer stopped: {} logged", glyphs::icon("⏱️"), format_minutes(minutes));
+    }
+    
+    Ok(())
+}
+
+async fn finish_task_git(
+    db: &Database,
+    config: &Config,
+    task: &mut Task,
+    message: Option<String>,
+    prune_worktree: bool,
+    force: bool,
+) -> Result<Vec<String>> {
+    let repo_path = std::env::current_dir()?;
+    let git_repo = GitRepo::open(&repo_path)?;
+    let mut actions = Vec::new();
+    
+    // Commit from the task's worktree when it has one
+    let worktree_repo = match &task.worktree_path {
+        Some(path) => Some(GitRepo::open(path)?),
+        None => None,
+    };
+    let work_repo = worktree_repo.as_ref().unwrap_or(&git_repo);
+    
+    // Check if there are staged changes to commit
+    if work_repo.has_staged_changes()? {
+        check_commit_branch(work_repo, &config.base_branch, force)?;
+        let commit_message = message.unwrap_or_else(|| format!("Closes #{}: {}", task.id, task.title));
+        work_repo.commit(&commit_message, config.sign_commits)?;
+        if config.sign_commits {
+            say!("{} Committed signed changes: {}", glyphs::icon("🔏"), commit_message);
+        } else {
+            say!("{} Committed changes: {}", glyphs::icon("💾"), commit_message);
+        }
+        actions.push(format!("committed \"{}\"", commit_message));
+    }
+    
+    // Push branch if it exists
+    if let Some(branch_name) = &task.branch_name {
+        work_repo.push_branch(&config.remote, branch_name)?;
+        say!("{} Pushed branch: {}", glyphs::icon("📤"), branch_name);
+        actions.push(format!("pushed {} to {}", branch_name, config.remote));
+    }
+    
+    // Remove the worktree once the work is committed
+    if let Some(path) = task.worktree_path.clone() {
+        if prune_worktree {
+            drop(worktree_repo);
+            git_repo.prune_worktree(Path::new(&path))?;
+            db.update_task_worktree(task, None).await?;
+            say!("{} Pruned worktree: {}", glyphs::icon("🧹"), path);
+            actions.push(format!("pruned worktree {}", path));
+        }
+    }
+    
+    Ok(actions)
+}
+
+pub async fn submit_command(
+    task_id: TaskId,
+    check_conflicts: bool,
+    remote: Option<String>,
+    base: Option<String>,
+    no_template: bool,
+) -> Result<()> {
+    let db = get_database().await?;
+    let config = get_config()?;
+    
+    // Get the task
+    let mut task = db.get_task(task_id).await?
+        .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_ref(task_id)))?;
+    
+    let Some(branch_name) = task.branch_name.clone() else {
+        // Branchless tasks have nothing to push; just move them along
+        say!("{} Task {} has no branch, skipping push and PR", glyphs::icon("🌿"), task_ref(task_id));
+        let review_column = column_for_role(&db, ColumnRole::Review).await?;
+        let from_column = column_name(&db, task.column_id).await?;
+        db.update_task_column(&mut task, review_column.id).await?;
+        db.log_task_activity(
+            "task_submitted",
+            task.id,
+            Some((&from_column, &review_column)),
+            Some(format!("Task #{}: no branch", task.id))
+        ).await?;
+        say!("{} Submitted task {} for review: {}", glyphs::icon("📋"), task_ref(task_id), task.title);
+        say!("   {} Moved to: {}", glyphs::icon("📦"), review_column.name);
+        return Ok(());
+    };
+    let repo_path = std::env::current_dir()?;
+    let git_repo = GitRepo::open(&repo_path)?;
+    
+    // An explicit base (release or hotfix branch) must already exist where the PR will be opened
+    if let Some(base) = &base {
+        if !git_repo.remote_branch_exists(&config.pr_remote, base)? {
+            bail!(
+                "Base branch '{}' not found on remote '{}' (try 'git fetch {}')",
+                base, config.pr_remote, config.pr_remote
+            );
+        }
+    }
+    let base_branch = base.as_deref().unwrap_or(&config.base_branch);
+    
+    // Warn about conflicts with the base before opening a PR
+    match git_repo.has_conflicts_with(&branch_name, base_branch) {
+        Ok(conflicts) if !conflicts.is_empty() => {
+            println!("{}  Branch {} conflicts with {} in:", glyphs::icon("⚠️"), branch_name, base_branch);
+            for file in &conflicts {
+                println!("   - {}", file);
+            }
+            if check_conflicts {
+                bail!("Resolve conflicts with '{}' before submitting task {}", base_branch, task_ref(task_id));
+            }
+        }
+        Ok(_) => {}
+        Err(e) => {
+            if check_conflicts {
+                return Err(e.context("Failed to check for merge conflicts"));
+            }
+            println!("{}  Could not check for merge conflicts: {}", glyphs::icon("⚠️"), e);
+        }
+    }
+    
+    // Push branch
+    let push_remote = remote.unwrap_or_else(|| config.remote.clone());
+    git_repo.push_branch(&push_remote, &branch_name)?;
+    say!("{} Pushed branch: {} → {}", glyphs::icon("📤"), branch_name, push_remote);
+    
+    // Create GitHub PR
+    let pr_url = if let Some(remote_url) = git_repo.get_remote_url(&config.pr_remote)? {
+        if let Some((owner, repo)) = extract_github_info(&remote_url) {
+            // Namespace the head when the branch lives in a different repository (fork workflow)
+            let push_info = git_repo.get_remote_url(&push_remote)?
+                .and_then(|url| extract_github_info(&url));
+            let pr_head = match push_info {
+                Some((push_owner, push_repo)) if push_owner != owner || push_repo != repo => {
+                    format!("{}:{}", push_owner, branch_name)
+                }
+                _ => branch_name.clone(),
+            };
+            
+            let token = resolve_token(&config).map(|(token, _)| token);
+            let github = GitHubClient::new(owner, repo, token);
+            let pr_title = format!("Task {}: {}", task_ref(task.id), task.title);
+            let template = if no_template { None } else { git_repo.pull_request_template()? };
+            let pr_body = pr_body(template.as_deref(), task.description.as_deref());
+            let pr_body = with_pr_footer(&pr_body, &config.pr_footer.replace("{id}", &task_ref(task.id)));
+            
+            match github.create_pull_request(&pr_title, &pr_body, &pr_head, base_branch).await {
+                Ok(url) => {
+                    say!("{} Created PR: {}", glyphs::icon("🔗"), url);
+                    url
+                }
+                Err(e) => {
+                    println!("{}  Failed to create PR: {}", glyphs::icon("⚠️"), e);
+                    format!("https://github.com/{}/{}/compare/{}...{}", "owner", "repo", base_branch, branch_name)
+                }
+            }
+        } else {
+            println!("{}  Not a GitHub repository, cannot create PR", glyphs::icon("⚠️"));
+            format!("Manual PR needed for branch: {}", branch_name)
+        }
+    } else {
+        println!("{}  No remote URL found, cannot create PR", glyphs::icon("⚠️"));
+        format!("Manual PR needed for branch: {}", branch_name)
+    };
+    
+    // Update task with PR URL
+    let pr_number = parse_pr_url(&pr_url).map(|(_, _, number)| i64::from(number));
+    db.update_task_pr(&mut task, &pr_url, pr_number).await?;
+    
+    // Move task to "Review" column
+    let review_column = column_for_role(&db, ColumnRole::Review).await?;
+    let from_column = column_name(&db, task.column_id).await?;
+    db.update_task_column(&mut task, review_column.id).await?;
+    
+    // Log activity
+    db.log_task_activity(
+        "task_submitted", 
+        task.id,
+        Some((&from_column, &review_column)),
+        Some(format!("Task #{}: PR created", task.id))
+    ).await?;
+    
+    say!("{} Submitted task {} for review: {}", glyphs::icon("📋"), task_ref(task_id), task.title);
+    say!("   {} Moved to: {}", glyphs::icon("📦"), review_column.name);
+    
+    Ok(())
+}
+
+pub async fn review_command(task_id: TaskId, fail_fast: bool) -> Result<()> {
+    let db = get_database().await?;
+    
+    // Get the task
+    let task = db.get_task(task_id).await?
+        .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_ref(task_id)))?;
+    
+    let Some(pr_url) = &task.pr_url else {
+        println!("{} Task {} has no associated PR", glyphs::icon("❌"), task_ref(task_id));
+        return Ok(());
+    };
+    println!("{} Checking PR status for task {}: {}", glyphs::icon("🔍"), task_ref(task_id), task.title);
+    println!("   {} PR: {}", glyphs::icon("🔗"), pr_url);
+    let token = resolve_token(&get_config()?).map(|(token, _)| token);
+    let Some(review) = pr_review(pr_url, token).await? else {
+        println!("   {} Not opened as a PR yet (compare link only)", glyphs::icon("⏳"));
+        return Ok(());
+    };
+    
+    println!("   {} Status: {}", glyphs::icon("📊"), review.status);
+    if let Some(checks) = &review.checks {
+        println!("   {}", format_checks(checks));
+        if fail_fast && checks.state == CheckState::Failure {
+            bail!("Checks are failing for task {}", task_ref(task_id));
+        }
+    }
+    
+    Ok(())
+}
+
+// How many PR status requests `review --all` keeps in flight
+const REVIEW_CONCURRENCY: usize = 4;
+
+pub async fn review_all_command(fail_fast: bool) -> Result<()> {
+    use futures::stream::{self, StreamExt};
+    
+    let db = get_database().await?;
+    let token = resolve_token(&get_config()?).map(|(token, _)| token);
+    
+    let review_column = column_for_role(&db, ColumnRole::Review).await?;
+    let done_column = column_for_role(&db, ColumnRole::Done).await?;
+    
+    let tasks = db.get_tasks(Some(review_column.id)).await?;
+    if tasks.is_empty() {
+        println!("{} No tasks in {}", glyphs::icon("🔍"), review_column.name);
+        return Ok(());
+    }
+    
+    let mut results: Vec<_> = stream::iter(tasks)
+        .map(|task| {
+            let token = token.clone();
+            async move {
+                let review = match &task.pr_url {
+                    Some(pr_url) => pr_review(pr_url, token).await,
+                    None => Ok(None),
+                };
+                (task, review)
+            }
+        })
+        .buffer_unordered(REVIEW_CONCURRENCY)
+        .collect()
+        .await;
+    results.sort_by_key(|(task, _)| task.id);
+    
+    println!("{} Review sweep ({} tasks)", glyphs::icon("🔍"), results.len());
+    let mut merged = 0;
+    let mut failing = 0;
+    for (task, review) in &mut results {
+        let label = match review {
+            Ok(Some(review)) => review.status.to_string(),
+            Ok(None) => "no PR".to_string(),
+            Err(_) => "error".to_string(),
+        };
+        println!("  {:<5} {:<7} {}", task_ref(task.id), label, task.title);
+        match review {
+            Ok(Some(PrReview { checks: Some(checks), .. })) => {
+                println!("         {}", format_checks(checks));
+                if checks.state == CheckState::Failure {
+                    failing += 1;
+                }
+            }
+            // reqwest nests the same message several times; the outer and root ones are enough
+            Err(e) => println!("         {}: {}", e, e.root_cause()),
+            _ => {}
+        }
+        
+        if let Ok(Some(PrReview { status: PullRequestStatus::Merged, .. })) = review {
+            let from_column = column_name(&db, task.column_id).await?;
+            db.update_task_column(task, done_column.id).await?;
+            db.log_task_activity(
+                "task_merged",
+                task.id,
+                Some((&from_column, &done_column)),
+                Some(format!("Task #{}: PR merged, moved to {}", task.id, done_column.name))
+            ).await?;
+            if let Some(minutes) = stop_timer(&db, task.id).await? {
+                println!("         {}  Timer stopped: {} logged", glyphs::icon("⏱️"), format_minutes(minutes));
+            }
+            merged += 1;
+        }
+    }
+    
+    if merged > 0 {
+        println!("{} Moved {} merged task(s) to {}", glyphs::icon("✅"), merged, done_column.name);
+    }
+    if fail_fast && failing > 0 {
+        bail!("Checks are failing for {} task(s) in {}", failing, review_column.name);
+    }
+    
+    Ok(())
+}
+
+pub async fn merge_command(task_id: TaskId, method: Option<MergeMethod>, delete_branch: bool) -> Result<()> {
+    let db = get_database().await?;
+    let config = get_config()?;
+    
+    let mut task = db.get_task(task_id).await?
+        .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_ref(task_id)))?;
+    let pr_url = task.pr_url.clone()
+        .ok_or_else(|| anyhow::anyhow!("Task {} has no PR yet (run 'pb submit {}')", task_ref(task.id), task.id))?;
+    let (owner, repo, number) = parse_pr_url(&pr_url)
+        .ok_or_else(|| anyhow::anyhow!("Task {}'s PR is not a GitHub pull request: {}", task_ref(task.id), pr_url))?;
+    let (token, _) = resolve_token(&config)
+        .ok_or_else(|| anyhow::anyhow!("Merging needs a GitHub token (set GITHUB_TOKEN or run 'gh auth login')"))?;
+    let github = GitHubClient::new(owner, repo, Some(token));
+    
+    let pr = github.get_pull_request_info(number).await?;
+    match pr.status {
+        PullRequestStatus::Merged => say!("{}  PR #{} is already merged", glyphs::icon("ℹ️"), number),
+        PullRequestStatus::Closed => bail!("PR #{} is closed without being merged", number),
+        PullRequestStatus::Open => {
+            // Refuse on red checks rather than leave it to branch protection, which may not be set up
+            let checks = github.get_check_status(&pr.head_sha).await?;
+            match checks.state {
+                CheckState::Failure => bail!("Checks are failing for PR #{}: {}", number, checks.failing.join(", ")),
+                CheckState::Pending => println!("{}  Checks for PR #{} are still pending", glyphs::icon("⚠️"), number),
+                CheckState::Success => {}
+            }
+            
+            let method = method.unwrap_or(config.merge_method);
+            github.merge_pull_request(number, method).await?;
+            say!("{} Merged PR #{} ({})", glyphs::icon("🔀"), number, method);
+        }
+    }
+    
+    let done_column = column_for_role(&db, ColumnRole::Done).await?;
+    let from_column = column_name(&db, task.column_id).await?;
+    db.update_task_column(&mut task, done_column.id).await?;
+    
+    if let (true, Some(branch)) = (delete_branch, task.branch_name.clone()) {
+        let git_repo = GitRepo::open(std::env::current_dir()?)?;
+        if git_repo.get_current_branch()?.as_deref() == Some(branch.as_str()) {
+            git_repo.checkout_branch(&config.base_branch)?;
+            say!("   {} Checked out {}", glyphs::icon("🌿"), config.base_branch);
+        }
+        git_repo.delete_branch(&branch)?;
+        if let Err(e) = github.delete_branch(&branch).await {
+            println!("{}  Could not delete {} on GitHub: {}", glyphs::icon("⚠️"), branch, e);
+        }
+        db.update_task_branch(&mut task, None).await?;
+        say!("   {}  Deleted branch: {}", glyphs::icon("🗑️"), branch);
+    }
+    
+    // Log activity
+    db.log_task_activity(
+        "task_merged",
+        task.id,
+        Some((&from_column, &done_column)),
+        Some(format!("Task #{}: PR #{} merged", task.id, number))
+    ).await?;
+    
+    let logged = stop_timer(&db, task.id).await?;
+    
+    say!("{} Completed task {}: {}", glyphs::icon("✅"), task_ref(task.id), task.title);
+    say!("   {} Moved to: {}", glyphs::icon("📦"), done_column.name);
+    if let Some(minutes) = logged {
+        say!("   {}  Timer stopped: {} logged", glyphs::icon("⏱️"), format_minutes(minutes));
+    }
+    
+    Ok(())
+}
+
+pub async fn prune_branches_command(delete: bool) -> Result<()> {
+    use futures::stream::{self, StreamExt};
+    
+    let db = get_database().await?;
+    let config = get_config()?;
+    let token = resolve_token(&config).map(|(token, _)| token);
+    let git_repo = GitRepo::open(std::env::current_dir()?)?;
+    
+    let done_column = column_for_role(&db, ColumnRole::Done).await?;
+    let tasks = db.get_tasks(Some(done_column.id)).await?;
+    let local_branches = git_repo.list_local_branches()?;
+    let current_branch = git_repo.get_current_branch()?;
+    let candidates = prune_candidates(&tasks, &local_branches, current_branch.as_deref(), &config.base_branch);
+    
+    // Done isn't enough on its own; the work has to have landed
+    let mut prunable: Vec<_> = stream::iter(candidates)
+        .map(|task| {
+            let token = token.clone();
+            async move {
+                let review = match &task.pr_url {
+                    Some(pr_url) => pr_review(pr_url, token).await,
+                    None => Ok(None),
+                };
+                (task, review)
+            }
+        })
+        .buffer_unordered(REVIEW_CONCURRENCY)
+        .filter_map(|(task, review)| async move {
+            match review {
+                Ok(Some(PrReview { status: PullRequestStatus::Merged, .. })) => Some(task.clone()),
+                _ => None,
+            }
+        })
+        .collect()
+        .await;
+    prunable.sort_by_key(|task| task.id);
+    
+    if prunable.is_empty() {
+        println!("{} No merged task branches to prune", glyphs::icon("🧹"));
+        return Ok(());
+    }
+    
+    if !delete {
+        println!("{} Would prune {} branch(es):", glyphs::icon("🧹"), prunable.len());
+        for task in &prunable {
+            println!("  {:<5} {}", task_ref(task.id), task.branch_name.as_deref().unwrap_or_default());
+        }
+        println!("Run 'pb prune-branches --delete' to remove them");
+        return Ok(());
+    }
+    
+    let mut pruned = 0;
+    for task in &mut prunable {
+        let branch = task.branch_name.clone().unwrap_or_default();
+        // A branch checked out in a worktree can't be deleted; skip it rather than stop
+        if let Err(e) = git_repo.delete_branch(&branch) {
+            println!("{}  Skipping {}: {}", glyphs::icon("⚠️"), branch, e);
+            continue;
+        }
+        db.update_task_branch(task, None).await?;
+        db.log_task_activity(
+            "branch_pruned",
+            task.id,
+            None,
+            Some(format!("Task #{}: pruned branch {}", task.id, branch))
+        ).await?;
+        say!("{}  Deleted branch: {} (task {})", glyphs::icon("🗑️"), branch, task_ref(task.id));
+        pruned += 1;
+    }
+    
+    say!("{} Pruned {} branch(es)", glyphs::icon("🧹"), pruned);
+    
+    Ok(())
+}
+
+/// Done tasks whose branch still exists locally, leaving out the checked-out
+/// branch and the base branch
+fn prune_candidates<'a>(tasks: &'a [Task], local_branches: &[String], current_branch: Option<&str>, base_branch: &str) -> Vec<&'a Task> {
+    tasks.iter()
+        .filter(|task| task.branch_name.as_deref().is_some_and(|branch| {
+            local_branches.iter().any(|b| b == branch)
+                && Some(branch) != current_branch
+                && branch != base_branch
+        }))
+        .collect()
+}
+
+pub async fn migrate_remote_command(from: String, to: String, dry_run: bool) -> Result<()> {
+    for repo in [&from, &to] {
+        if !is_owner_repo(repo) {
+            bail!("'{}' is not an owner/repo name", repo);
+        }
+    }
+    let db = get_database().await?;
+    
+    // Hidden tasks keep their PR URLs too, and restoring one shouldn't bring back a dead link
+    let tasks = db.query_tasks(&TaskQuery {
+        include_archived: true,
+        include_deleted: true,
+        ..TaskQuery::default()
+    }).await?;
+    let pr_urls: Vec<(i64, String, String)> = tasks.iter()
+        .filter_map(|task| {
+            let old = task.pr_url.as_deref()?;
+            rewrite_repo_url(old, &from, &to).map(|new| (task.id, old.to_string(), new))
+        })
+        .collect();
+    let link_urls: Vec<(i64, i64, String, String)> = db.get_all_links().await?.into_iter()
+        .filter_map(|link| rewrite_repo_url(&link.url, &from, &to).map(|new| (link.id, link.task_id, link.url, new)))
+        .collect();
+    
+    let count = pr_urls.len() + link_urls.len();
+    if count == 0 {
+        println!("{} No stored URLs point at {}", glyphs::icon("🔗"), from);
+        return Ok(());
+    }
+    
+    if dry_run {
+        println!("{} Would update {} URL(s):", glyphs::icon("🔗"), count);
+    } else {
+        db.rewrite_urls(
+            &pr_urls.iter().map(|(id, _, new)| (*id, new.clone())).collect::<Vec<_>>(),
+            &link_urls.iter().map(|(id, _, _, new)| (*id, new.clone())).collect::<Vec<_>>(),
+        ).await?;
+        db.log_activity("remote_migrated", Some(format!("{} → {}: {} URL(s)", from, to, count))).await?;
+        say!("{} Updated {} URL(s) from {} to {}:", glyphs::icon("🔗"), count, from, to);
+    }
+    let changes = pr_urls.iter().map(|(task_id, old, new)| format!("  {:<5} PR   {} → {}", task_ref(*task_id), old, new))
+        .chain(link_urls.iter().map(|(_, task_id, old, new)| format!("  {:<5} link {} → {}", task_ref(*task_id), old, new)));
+    if dry_run {
+        changes.for_each(|line| println!("{}", line));
+        println!("Run without --dry-run to apply");
+    } else {
+        changes.for_each(|line| say!("{}", line));
+    }
+    
+    Ok(())
+}
+
+fn is_owner_repo(value: &str) -> bool {
+    value.split_once('/').is_some_and(|(owner, repo)| {
+        !owner.is_empty() && !repo.is_empty() && !repo.contains('/')
+    })
+}
+
+/// `url` moved from the GitHub repository `from` to `to`, or None if it points elsewhere.
+/// GitHub treats owner and repo names case-insensitively, so this does too.
+fn rewrite_repo_url(url: &str, from: &str, to: &str) -> Option<String> {
+    let (scheme, path) = url.split_once("github.com/")?;
+    if !matches!(scheme, "https://" | "http://" | "https://www." | "http://www.") {
+        return None;
+    }
+    let rest = path.get(from.len()..)
+        .filter(|_| path[..from.len()].eq_ignore_ascii_case(from))?;
+    // `owner/repo` must not match `owner/repo-extras`
+    if !(rest.is_empty() || rest.starts_with(['/', '#', '?'])) {
+        return None;
+    }
+    Some(format!("{}github.com/{}{}", scheme, to, rest))
+}
+
+/// What `review` reports for a task's PR
+struct PrReview {
+    status: PullRequestStatus,
+    /// CI checks on the PR head, only fetched while the PR is open
+    checks: Option<CheckStatus>,
+}
+
+/// Status and checks of the PR behind a task's `pr_url`, or None for a compare link
+async fn pr_review(pr_url: &str, token: Option<String>) -> Result<Option<PrReview>> {
+    let Some((owner, repo, number)) = parse_pr_url(pr_url) else {
+        return Ok(None);
+    };
+    let github = GitHubClient::new(owner, repo, token);
+    let pr = github.get_pull_request_info(number).await?;
+    let checks = match pr.status {
+        PullRequestStatus::Open => Some(github.get_check_status(&pr.head_sha).await?),
+        _ => None,
+    };
+    
+    Ok(Some(PrReview { status: pr.status, checks }))
+}
+
+fn format_checks(checks: &CheckStatus) -> String {
+    match checks.state {
+        CheckState::Success => format!("{} Checks: passing", glyphs::icon("✅")),
+        CheckState::Pending => format!("{} Checks: pending", glyphs::icon("⏳")),
+        CheckState::Failure => format!("{} Checks: failing ({})", glyphs::icon("❌"), checks.failing.join(", ")),
+    }
+}
+
+pub async fn diff_command(task_id: TaskId, full: bool) -> Result<()> {
+    let db = get_database().await?;
+    let repo_path = std::env::current_dir()?;
+    let git_repo = GitRepo::open(&repo_path)?;
+    
+    // Get the task
+    let task = db.get_task(task_id).await?
+        .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_ref(task_id)))?;
+    
+    let branch_name = task.branch_name
+        .ok_or_else(|| anyhow::anyhow!("Task {} has no associated branch", task_ref(task_id)))?;
+    let base_branch = get_config()?.base_branch;
+    
+    println!("{} Changes for task {}: {}", glyphs::icon("🔍"), task_ref(task_id), task.title);
+    println!("   {} {} → {}", glyphs::icon("🌿"), branch_name, base_branch);
+    
+    match git_repo.diff_against_base(&branch_name, &base_branch, full)? {
+        Some(summary) => {
+            for file in &summary.files {
+                println!("  {}", file);
+            }
+            println!(
+                "   {} files changed, {} insertions(+), {} deletions(-)",
+                summary.files.len(),
+                summary.insertions,
+                summary.deletions
+            );
+            if let Some(patch) = summary.patch {
+                println!();
+                print!("{}", patch);
+            }
+        }
+        None => {
+            println!("   (no commits on this branch yet)");
+        }
+    }
+    
+    Ok(())
+}
+
+pub async fn branch_command(selector: TaskSelector, copy: bool) -> Result<()> {
+    let db = get_database().await?;
+    let task = resolve_task(&db, &selector).await?;
+    
+    let Some(branch) = &task.branch_name else {
+        println!("{} Task {} has no branch yet (run 'pb start {}')", glyphs::icon("🌿"), task_ref(task.id), task.id);
+        return Ok(());
+    };
+    
+    // Bare name on stdout so `git checkout $(pb branch 3)` works
+    println!("{}", branch);
+    if copy {
+        copy_to_clipboard(branch)?;
+        say!("{} Copied to clipboard", glyphs::icon("📋"));
+    }
+    
+    Ok(())
+}
+
+pub async fn whoami_command(check_github: bool) -> Result<()> {
+    let identity = git_identity();
+    let config = get_config()?;
+    
+    println!("{} Git identity (commits, comments, assignment)", glyphs::icon("👤"));
+    println!("   Name:  {}", identity.name.as_deref().unwrap_or("(not set, comments show as 'unknown')"));
+    println!("   Email: {}", identity.email.as_deref().unwrap_or("(not set)"));
+    
+    let token = resolve_token(&config);
+    match &token {
+        Some((_, source)) => println!("{} GitHub token: found via {}", glyphs::icon("🔑"), source),
+        None => println!("{} GitHub token: not found (set GITHUB_TOKEN, github_token in config, or run 'gh auth login')", glyphs::icon("🔑")),
+    }
+    
+    let Some((token, source)) = token.filter(|_| check_github) else {
+        return Ok(());
+    };
+    let info = get_token_info(&token).await
+        .context(format!("Could not check the token from {}", source))?;
+    println!("   Login:  {}", info.login);
+    match &info.scopes {
+        Some(scopes) if scopes.is_empty() => println!("   Scopes: (none)"),
+        Some(scopes) => println!("   Scopes: {}", scopes.join(", ")),
+        None => println!("   Scopes: not reported (fine-grained token; PRs need Pull requests: write)"),
+    }
+    if info.can_create_prs() == Some(false) {
+        println!("{}  The token lacks the 'repo' scope, so pb submit will get a 403 creating PRs", glyphs::icon("⚠️"));
+        println!("   (public_repo only covers public repositories); regenerate it with 'repo'");
+    }
+    
+    Ok(())
+}
+
+pub async fn reset_command(force: bool, hard: bool) -> Result<()> {
+    let db = get_database().await?;
+    
+    if !force {
+        let scope = if hard {
+            "all tasks, comments, ideas, activity, labels, and custom columns"
+        } else {
+            "all tasks, comments, ideas, and activity"
+        };
+        if !confirm(&format!("{}  This deletes {}. Continue?", glyphs::icon("⚠️"), scope))? {
+            println!("Reset cancelled");
+            return Ok(());
+        }
+    }
+    
+    db.reset_board(hard).await?;
+    
+    // Log activity
+    db.log_activity(
+        "board_reset", 
+        Some(if hard { "Hard reset: columns restored to defaults".to_string() } else { "Board reset".to_string() })
+    ).await?;
+    
+    say!("{} Board reset", glyphs::icon("🧹"));
+    if hard {
+        say!("   {} Columns restored to defaults", glyphs::icon("📋"));
+    }
+    say!("   Config and templates were kept");
+    
+    Ok(())
+}
+
+pub async fn config_edit_command() -> Result<()> {
+    let db = get_database().await?;
+    
+    let path = Config::path(&std::env::current_dir()?.join(".projectboard"));
+    let original = if path.exists() {
+        fs::read_to_string(&path).context(format!("Failed to read {}", path.display()))?
+    } else {
+        Config::TEMPLATE.to_string()
+    };
+    
+    // Edit a copy so a broken config never replaces the working one
+    let draft = std::env::temp_dir().join(format!("pb-config-{}.toml", uuid::Uuid::new_v4()));
+    fs::write(&draft, &original)
+        .context(format!("Failed to write {}", draft.display()))?;
+    let status = run_editor(&draft)?;
+    if !status.success() {
+        let _ = fs::remove_file(&draft);
+        bail!("Editor exited with {}", status);
+    }
+    let edited = fs::read_to_string(&draft)
+        .context(format!("Failed to read {}", draft.display()))?;
+    
+    let checked = toml::from_str::<Config>(&edited)
+        .map_err(|e| anyhow::anyhow!(e.to_string().trim_end().to_string()))
+        .and_then(|config| config.task_prefix.as_deref().map_or(Ok(()), check_task_prefix));
+    if let Err(e) = checked {
+        bail!(
+            "Invalid config, {} was left unchanged: {}\nYour edits are saved in {}",
+            path.display(), e, draft.display()
+        );
+    }
+    let _ = fs::remove_file(&draft);
+    
+    if path.exists() && edited == original {
+        say!("{}  No changes to {}", glyphs::icon("⚙️"), path.display());
+        return Ok(());
+    }
+    fs::write(&path, &edited)
+        .context(format!("Failed to write {}", path.display()))?;
+    
+    // Log activity
+    db.log_activity("config_edited", None).await?;
+    
+    say!("{}  Saved {}", glyphs::icon("⚙️"), path.display());
+    
+    Ok(())
+}
+
+pub async fn log_command(limit: u32, since: Option<String>, event: Option<String>, json: bool) -> Result<()> {
+    let db = get_database().await?;
+    
+    let filter = ActivityFilter {
+        limit: Some(i64::from(limit)),
+        since: since.as_deref().map(|s| parse_since(s, Utc::now())).transpose()?,
+        event,
+        task_id: None,
+    };
+    let entries = db.query_activity(&filter).await?;
+    
+    if json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+    
+    println!("{} Activity ({} entries)", glyphs::icon("📜"), entries.len());
+    if entries.is_empty() {
+        println!("  (no activity)");
+    }
+    for entry in &entries {
+        println!(
+            "  {}  {} {}",
+            entry.created_at.format("%Y-%m-%d %H:%M"),
+            // Pad before painting so escape codes don't throw off the column
+            paint(&format!("{:<20}", entry.event), "36"),
+            entry.metadata.as_deref().unwrap_or("")
+        );
+    }
+    
+    Ok(())
+}
+
+pub async fn changelog_command(since: String, output: Option<PathBuf>) -> Result<()> {
+    let db = get_database().await?;
+    
+    let since = parse_since(&since, Utc::now())?;
+    let done_column = column_for_role(&db, ColumnRole::Done).await?;
+    let tasks = db.get_tasks_moved_to(&done_column, since).await?;
+    let task_labels = db.get_all_task_labels().await?;
+    
+    let changelog = format_changelog(since, &tasks, &task_labels);
+    write_export(&changelog, output.as_deref())
+}
+
+/// Markdown release notes: one bullet per task with its PR, grouped under the
+/// task's first label when any shipped task has one
+fn format_changelog(since: DateTime<Utc>, tasks: &[Task], task_labels: &[TaskLabel]) -> String {
+    let mut out = format!("## Changes since {}\n\n", since.format("%Y-%m-%d"));
+    if tasks.is_empty() {
+        out.push_str("No tasks were completed in this period.\n");
+        return out;
+    }
+    
+    let bullet = |task: &Task| match (&task.pr_url, task.pr_number) {
+        (Some(url), Some(number)) => format!("- {} ([#{}]({}))\n", task.title, number, url),
+        (Some(url), None) => format!("- {} ([PR]({}))\n", task.title, url),
+        _ => format!("- {}\n", task.title),
+    };
+    // get_all_task_labels is sorted by task then name, so the first match is the first label
+    let label_of = |task: &Task| task_labels.iter().find(|tl| tl.task_id == task.id).map(|tl| tl.name.as_str());
+    
+    if tasks.iter().all(|t| label_of(t).is_none()) {
+        for task in tasks {
+            out.push_str(&bullet(task));
+        }
+        return out;
+    }
+    
+    let mut groups: Vec<(&str, Vec<&Task>)> = Vec::new();
+    for task in tasks {
+        let label = label_of(task).unwrap_or("Other");
+        match groups.iter_mut().find(|(name, _)| *name == label) {
+            Some((_, group)) => group.push(task),
+            None => groups.push((label, vec![task])),
+        }
+    }
+    // Labels alphabetically, with unlabeled work last
+    groups.sort_by_key(|(name, _)| (*name == "Other", name.to_lowercase()));
+    for (label, group) in groups {
+        out.push_str(&format!("### {}\n\n", label));
+        for task in group {
+            out.push_str(&bullet(task));
+        }
+        out.push('\n');
+    }
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out
+}
+
+/// Parse `--since` as a date (YYYY-MM-DD, midnight UTC) or an age like 12h, 3d, or 2w
+fn parse_since(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap().and_utc());
+    }
+    
+    parse_age(value)
+        .and_then(|age| now.checked_sub_signed(age))
+        .ok_or_else(|| anyhow::anyhow!("Invalid --since '{}': use YYYY-MM-DD, <n>h, <n>d, or <n>w", value))
+}
+
+/// `<n>h`, `<n>d`, or `<n>w` as a duration; `None` when it doesn't fit one or
+/// `n` isn't positive, since an age in the future would match everything
+fn parse_age(value: &str) -> Option<Duration> {
+    let unit = value.chars().last()?;
+    let amount: i64 = value[..value.len() - unit.len_utf8()].parse().ok().filter(|&n| n > 0)?;
+    match unit {
+        'h' => Duration::try_hours(amount),
+        'd' => Duration::try_days(amount),
+        'w' => Duration::try_weeks(amount),
+        _ => None,
+    }
+}
+
+/// Done tasks `auto_archive_done_after` archives per load, so a board that was
+/// never cleaned up catches up over a few runs rather than all at once
+const AUTO_ARCHIVE_BATCH: i64 = 50;
+
+/// Archive Done tasks untouched for longer than `auto_archive_done_after`,
+/// oldest first, returning how many were archived
+pub(crate) async fn auto_archive_done(db: &Database) -> Result<usize> {
+    let config = get_config()?;
+    let Some(after) = &config.auto_archive_done_after else {
+        return Ok(0);
+    };
+    let updated_before = parse_age(after)
+        .filter(|age| *age >= Duration::days(1))
+        .and_then(|age| Utc::now().checked_sub_signed(age))
+        .ok_or_else(|| anyhow::anyhow!("Invalid auto_archive_done_after '{}': use <n>d or <n>w, at least 1d", after))?;
+    let Some(done_column) = db.get_column_by_role(ColumnRole::Done).await? else {
+        return Ok(0);
+    };
+    
+    let mut tasks = db.query_tasks(&TaskQuery {
+        column_id: Some(done_column.id),
+        limit: Some(AUTO_ARCHIVE_BATCH),
+        oldest_first: true,
+        updated_before: Some(updated_before),
+        ..TaskQuery::default()
+    }).await?;
+    for task in &mut tasks {
+        db.archive_task(task).await?;
+        db.log_task_activity(
+            "task_auto_archived",
+            task.id,
+            None,
+            Some(format!("Task #{}: done and untouched for over {}", task.id, after))
+        ).await?;
+    }
+    
+    Ok(tasks.len())
+}
+
+pub async fn stats_command(by_assignee: bool, json: bool) -> Result<()> {
+    let db = get_database().await?;
+    
+    if by_assignee {
+        let stats = db.get_assignee_stats().await?;
+        if json {
+            println!("{}", serde_json::to_string_pretty(&stats)?);
+        } else {
+            print!("{}", format_assignee_stats(&stats));
+        }
+        return Ok(());
+    }
+    
+    let columns = db.get_columns_with_limits().await?;
+    let ideas = db.get_ideas().await?;
+    let stats = board_stats(&columns, ideas.len(), &db.get_logged_time().await?);
+    
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+    
+    let total: i64 = columns.iter().map(|c| c.task_count).sum();
+    println!("{} {} tasks, {} ideas", glyphs::icon("📊"), total, ideas.len());
+    for column in &columns {
+        println!("  {}", format_column_load(column));
+    }
+    
+    let over: Vec<_> = columns.iter().filter(|c| c.is_over_limit()).collect();
+    if !over.is_empty() {
+        println!();
+        println!("{}  {} column(s) over their WIP limit:", glyphs::icon("⚠️"), over.len());
+        for column in over {
+            println!("   {}", column.column.name);
+        }
+    }
+    
+    if stats.logged_minutes > 0 {
+        println!();
+        println!("{}  {} logged", glyphs::icon("⏱️"), format_minutes(stats.logged_minutes));
+        for column in stats.columns.iter().filter(|c| c.logged_minutes > 0) {
+            println!("  {}: {}", column.name, format_minutes(column.logged_minutes));
+        }
+        for total in &stats.logged_by_assignee {
+            let assignee = total.assignee.as_deref().map_or("(unassigned)".to_string(), |a| format!("@{}", a));
+            println!("  {}: {}", assignee, format_minutes(total.minutes));
+        }
+    }
+    
+    Ok(())
+}
+
+/// `pb stats --json`: the board totals, each column's load, and logged time
+#[derive(Debug, Serialize)]
+struct BoardStats {
+    tasks: i64,
+    ideas: usize,
+    logged_minutes: i64,
+    columns: Vec<ColumnStats>,
+    logged_by_assignee: Vec<AssigneeTime>,
+}
+
+#[derive(Debug, Serialize)]
+struct ColumnStats {
+    name: String,
+    tasks: i64,
+    wip_limit: Option<i64>,
+    over_limit: bool,
+    logged_minutes: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct AssigneeTime {
+    /// None for unassigned tasks
+    assignee: Option<String>,
+    minutes: i64,
+}
+
+fn board_stats(columns: &[ColumnWithLimit], ideas: usize, logged: &[LoggedTime]) -> BoardStats {
+    let mut by_assignee: Vec<AssigneeTime> = Vec::new();
+    for entry in logged {
+        match by_assignee.iter_mut().find(|a| a.assignee == entry.assignee) {
+            Some(total) => total.minutes += entry.minutes,
+            None => by_assignee.push(AssigneeTime { assignee: entry.assignee.clone(), minutes: entry.minutes }),
+        }
+    }
+    by_assignee.sort_by(|a, b| (a.assignee.is_none(), &a.assignee).cmp(&(b.assignee.is_none(), &b.assignee)));
+    
+    BoardStats {
+        tasks: columns.iter().map(|c| c.task_count).sum(),
+        ideas,
+        logged_minutes: logged.iter().map(|l| l.minutes).sum(),
+        columns: columns.iter()
+            .map(|c| ColumnStats {
+                name: c.column.name.clone(),
+                tasks: c.task_count,
+                wip_limit: c.wip_limit,
+                over_limit: c.is_over_limit(),
+                logged_minutes: logged.iter().filter(|l| l.column_id == c.column.id).map(|l| l.minutes).sum(),
+            })
+            .collect(),
+        logged_by_assignee: by_assignee,
+    }
+}
+
+fn format_assignee_stats(stats: &[AssigneeStats]) -> String {
+    let mut out = format!("{} Open work by assignee\n", glyphs::icon("👤"));
+    if stats.is_empty() {
+        out.push_str("  (no open tasks)\n");
+        return out;
+    }
+    
+    let name = |s: &AssigneeStats| s.assignee.clone().unwrap_or_else(|| "(unassigned)".to_string());
+    let width = stats.iter().map(|s| name(s).chars().count()).max().unwrap_or(0).max("Assignee".len());
+    let _ = writeln!(out, "  {:<width$}  {:>4}  {:>11}", "Assignee", "Open", "In progress");
+    for s in stats {
+        let _ = writeln!(out, "  {:<width$}  {:>4}  {:>11}", name(s), s.open, s.in_progress);
+    }
+    out
+}
+
+/// How a `pb doctor` check came out; only problems make doctor fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum DoctorStatus {
+    Ok,
+    Warning,
+    Problem,
+}
+
+/// One `pb doctor` check, printed as a line or as a `--json` object
+#[derive(Debug, Serialize)]
+struct DoctorCheck {
+    name: &'static str,
+    status: DoctorStatus,
+    message: String,
+    /// What to run or change to fix it
+    hint: Option<String>,
+}
+
+impl DoctorCheck {
+    fn new(name: &'static str, status: DoctorStatus, message: impl Into<String>) -> Self {
+        DoctorCheck { name, status, message: message.into(), hint: None }
+    }
+
+    fn hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+async fn doctor_checks(db: &Database) -> Result<Vec<DoctorCheck>> {
+    let mut checks = Vec::new();
+    
+    checks.push(match get_config() {
+        Ok(_) => DoctorCheck::new("config", DoctorStatus::Ok, "Config loaded"),
+        Err(e) => DoctorCheck::new("config", DoctorStatus::Problem, format!("Config: {:#}", e))
+            .hint("fix .projectboard/config.toml"),
+    });
+    
+    let repo_path = std::env::current_dir()?;
+    checks.push(match GitRepo::open(&repo_path) {
+        Ok(_) => DoctorCheck::new("git_repository", DoctorStatus::Ok, "Git repository found"),
+        Err(e) => DoctorCheck::new("git_repository", DoctorStatus::Problem, format!("Git repository: {}", e)),
+    });
+    
+    // add, start, submit, and done find their columns by role
+    for (role, name) in [
+        (ColumnRole::Backlog, "backlog_role"),
+        (ColumnRole::Doing, "doing_role"),
+        (ColumnRole::Review, "review_role"),
+        (ColumnRole::Done, "done_role"),
+    ] {
+        checks.push(match db.get_column_by_role(role).await? {
+            Some(column) => DoctorCheck::new(name, DoctorStatus::Ok, format!("{} has the {} role", column.name, role)),
+            None => DoctorCheck::new(name, DoctorStatus::Problem, format!("No column has the {} role", role))
+                .hint(format!("run 'pb column set-role <name> {}'", role)),
+        });
+    }
+    
+    let columns = db.get_columns_with_limits().await?;
+    let over: Vec<_> = columns.iter().filter(|c| c.is_over_limit()).map(format_column_load).collect();
+    checks.push(if over.is_empty() {
+        DoctorCheck::new("wip_limits", DoctorStatus::Ok, "All columns within WIP limits")
+    } else {
+        DoctorCheck::new("wip_limits", DoctorStatus::Warning, over.join("; "))
+    });
+    
+    checks.push(match get_config().ok().and_then(|config| resolve_token(&config)) {
+        Some(_) => DoctorCheck::new("github_token", DoctorStatus::Ok, "GitHub token found"),
+        None => DoctorCheck::new("github_token", DoctorStatus::Warning, "No GitHub token found; 'pb submit' will not create PRs")
+            .hint("set GITHUB_TOKEN, add github_token to the config, or run 'gh auth login'"),
+    });
+    
+    Ok(checks)
+}
+
+pub async fn doctor_command(json: bool) -> Result<()> {
+    let db = get_database().await?;
+    let checks = doctor_checks(&db).await?;
+    
+    if json {
+        println!("{}", serde_json::to_string_pretty(&checks)?);
+    } else {
+        for check in &checks {
+            // Passing role checks are noise; the rest read as before
+            if check.status == DoctorStatus::Ok && check.name.ends_with("_role") {
+                continue;
+            }
+            let icon = match check.status {
+                DoctorStatus::Ok => glyphs::icon("✅").to_string(),
+                DoctorStatus::Warning => format!("{} ", glyphs::icon("⚠️")),
+                DoctorStatus::Problem => glyphs::icon("❌").to_string(),
+            };
+            match &check.hint {
+                Some(hint) => println!("{} {} ({})", icon, check.message, hint),
+                None => println!("{} {}", icon, check.message),
+            }
+        }
+    }
+    
+    // Warnings don't count as problems
+    let problems = checks.iter().filter(|c| c.status == DoctorStatus::Problem).count();
+    if problems > 0 {
+        bail!("{} problem(s) found", problems);
+    }
+    if !json {
+        println!("{} No problems found", glyphs::icon("🩺"));
+    }
+    
+    Ok(())
+}
+
+/// One inconsistency `pb check` found, and how to fix it
+#[derive(Debug, PartialEq)]
+struct BoardIssue {
+    task_id: i64,
+    problem: String,
+    fix: String,
+}
+
+pub async fn check_command() -> Result<()> {
+    let db = get_database().await?;
+    let config = get_config()?;
+    let git_repo = GitRepo::open(std::env::current_dir()?)?;
+    
+    let tasks = db.get_tasks(None).await?;
+    let doing = db.get_column_by_role(ColumnRole::Doing).await?;
+    let review = db.get_column_by_role(ColumnRole::Review).await?;
+    let done = db.get_column_by_role(ColumnRole::Done).await?;
+    
+    // `pb start --no-branch` leaves a Doing task branchless on purpose
+    let mut started_branchless = std::collections::HashSet::new();
+    if let Some(doing) = &doing {
+        for task in tasks.iter().filter(|t| t.column_id == doing.id && t.branch_name.is_none()) {
+            let last_start = db.query_activity(&ActivityFilter {
+                limit: Some(1),
+                event: Some("task_started".to_string()),
+                task_id: Some(task.id),
+                ..Default::default()
+            }).await?;
+            if last_start.first().and_then(|log| log.metadata.as_deref()).is_some_and(|m| m.ends_with(STARTED_WITHOUT_BRANCH)) {
+                started_branchless.insert(task.id);
+            }
+        }
+    }
+    
+    // Without branch creation, Doing tasks have no branch by design
+    let mut issues = column_issues(&tasks, doing.as_ref().filter(|_| config.create_branches), review.as_ref(), &started_branchless);
+    
+    // A CI checkout often only has the remote-tracking branch
+    let local_branches = git_repo.list_local_branches()?;
+    let branch_exists = |branch: &str| {
+        local_branches.iter().any(|b| b == branch)
+            || git_repo.remote_branch_exists(&config.remote, branch).unwrap_or(false)
+    };
+    issues.extend(missing_branch_issues(&tasks, done.as_ref().map(|c| c.id), branch_exists));
+    
+    for cycle in dependency_cycles(&db.get_dependencies().await?) {
+        let path: Vec<String> = cycle.iter().chain(cycle.first()).map(|&id| task_ref(id)).collect();
+        issues.push(BoardIssue {
+            task_id: cycle[0],
+            problem: format!("Dependency cycle: {}", path.join(" → ")),
+            fix: format!("run 'pb dep remove {} {}' to break it", cycle[cycle.len() - 1], cycle[0]),
+        });
+    }
+    
+    if issues.is_empty() {
+        println!("{} No board issues found", glyphs::icon("✅"));
+        return Ok(());
+    }
+    
+    issues.sort_by_key(|issue| issue.task_id);
+    for issue in &issues {
+        println!("{} {}", glyphs::icon("❌"), issue.problem);
+        println!("   {} {}", glyphs::icon("👉"), issue.fix);
+    }
+    bail!("{} issue(s) found", issues.len());
+}
+
+/// Tasks in Doing without a branch that weren't started branchless, and
+/// branch tasks in Review without a PR; a branchless submit never opens one
+fn column_issues(tasks: &[Task], doing: Option<&Column>, review: Option<&Column>, started_branchless: &std::collections::HashSet<i64>) -> Vec<BoardIssue> {
+    let mut issues = Vec::new();
+    for task in tasks {
+        if let Some(doing) = doing.filter(|c| c.id == task.column_id && task.branch_name.is_none() && !started_branchless.contains(&task.id)) {
+            issues.push(BoardIssue {
+                task_id: task.id,
+                problem: format!("Task {} is in {} but has no branch", task_ref(task.id), doing.name),
+                fix: format!("run 'pb start {}' to create one, or 'pb move {} --role backlog'", task.id, task.id),
+            });
+        }
+        if let Some(review) = review.filter(|c| c.id == task.column_id && task.branch_name.is_some() && task.pr_url.is_none()) {
+            issues.push(BoardIssue {
+                task_id: task.id,
+                problem: format!("Task {} is in {} but has no PR", task_ref(task.id), review.name),
+                fix: format!("run 'pb submit {}' to open one, or 'pb move {} --role doing'", task.id, task.id),
+            });
+        }
+    }
+    issues
+}
+
+/// Unfinished tasks whose branch is gone; Done tasks' branches are expected to be deleted
+fn missing_branch_issues(tasks: &[Task], done_column: Option<i64>, branch_exists: impl Fn(&str) -> bool) -> Vec<BoardIssue> {
+    tasks.iter()
+        .filter(|task| Some(task.column_id) != done_column)
+        .filter_map(|task| {
+            let branch = task.branch_name.as_deref()?;
+            (!branch_exists(branch)).then(|| BoardIssue {
+                task_id: task.id,
+                problem: format!("Task {} refers to branch '{}', which does not exist", task_ref(task.id), branch),
+                fix: format!("fetch it from the remote, or run 'pb start {}' to create it again", task.id),
+            })
+        })
+        .collect()
+}
+
+/// Each dependency cycle once, as the tasks in waiting order starting from the lowest ID.
+/// `dep add` refuses to create cycles, so these only come from edits made outside pb.
+fn dependency_cycles(dependencies: &[TaskDependency]) -> Vec<Vec<i64>> {
+    fn visit(task: i64, dependencies: &[TaskDependency], path: &mut Vec<i64>, finished: &mut std::collections::HashSet<i64>, cycles: &mut Vec<Vec<i64>>) {
+        if let Some(start) = path.iter().position(|&t| t == task) {
+            let mut cycle = path[start..].to_vec();
+            let lowest = cycle.iter().enumerate().min_by_key(|(_, id)| **id).map_or(0, |(i, _)| i);
+            cycle.rotate_left(lowest);
+            if !cycles.contains(&cycle) {
+                cycles.push(cycle);
+            }
+            return;
+        }
+        if finished.contains(&task) {
+            return;
+        }
+        path.push(task);
+        for dependency in dependencies.iter().filter(|d| d.task_id == task) {
+            visit(dependency.blocked_by, dependencies, path, finished, cycles);
+        }
+        path.pop();
+        finished.insert(task);
+    }
+    
+    let mut tasks: Vec<i64> = dependencies.iter().map(|d| d.task_id).collect();
+    tasks.sort_unstable();
+    tasks.dedup();
+    
+    let mut cycles = Vec::new();
+    let mut finished = std::collections::HashSet::new();
+    for task in tasks {
+        visit(task, dependencies, &mut Vec::new(), &mut finished, &mut cycles);
+    }
+    cycles
+}
+
+pub async fn board_command(column: Option<String>, snapshot: Option<PathBuf>) -> Result<()> {
+    use crate::tui::{run_board_interface, write_board_snapshot};
+    match snapshot {
+        Some(path) => {
+            write_board_snapshot(column, &path).await?;
+            say!("{} Saved board snapshot to {}", glyphs::icon("💾"), path.display());
+            Ok(())
+        }
+        None => run_board_interface(column).await,
+    }
+}
+
+pub async fn export_command(
+    format: ExportFormat,
+    output: Option<PathBuf>,
+    summary: bool,
+    include: Vec<ExportInclude>,
+    column_names: Vec<String>,
+    anonymize: bool,
+    redact: Vec<ExportRedact>,
+) -> Result<()> {
+    let db = get_database().await?;
+    // Every format sees the same tasks: active ones plus whatever --include adds,
+    // from the columns --column names
+    let include_archived = include.contains(&ExportInclude::Archived);
+    let include_deleted = include.contains(&ExportInclude::Deleted);
+    let mut columns = db.get_columns().await?;
+    let scope = column_ids_named(&columns, &column_names)?;
+    let in_scope = |column_id: i64| scope.as_ref().is_none_or(|ids| ids.contains(&column_id));
+    let mut anonymizer = anonymize.then(|| Anonymizer::new(redact));
+    if let ExportFormat::Ndjson = format {
+        return export_ndjson(&db, output.as_deref(), summary, include_archived, include_deleted, &in_scope, anonymizer.as_mut()).await;
+    }
+    if summary {
+        bail!("--summary only applies to --format ndjson");
+    }
+    
+    let mut tasks = db.query_tasks(&TaskQuery {
+        include_archived,
+        include_deleted,
+        ..TaskQuery::default()
+    }).await?;
+    let mut limits = db.get_columns_with_limits().await?;
+    tasks.retain(|t| in_scope(t.column_id));
+    columns.retain(|c| in_scope(c.id));
+    limits.retain(|c| in_scope(c.column.id));
+    if let Some(anonymizer) = &mut anonymizer {
+        tasks.iter_mut().for_each(|task| anonymizer.apply(task));
+    }
+    
+    let mut out = String::new();
+    match format {
+        ExportFormat::Csv => {
+            writeln!(out, "ID,Title,Description,Column,Created,Updated,Branch,PR")?;
+            for task in tasks {
+                let column_name = columns.iter()
+                    .find(|c| c.id == task.column_id)
+                    .map(|c| c.name.as_str())
+                    .unwrap_or("Unknown");
+                
+                writeln!(out, "{},{},{},{},{},{},{},{}",
+                    task_ref(task.id).trim_start_matches('#'),
+                    escape_csv(&task.title),
+                    escape_csv(&task.description.unwrap_or_default()),
+                    column_name,
+                    task.created_at.format("%Y-%m-%d %H:%M:%S"),
+                    task.updated_at.format("%Y-%m-%d %H:%M:%S"),
+                    task.branch_name.unwrap_or_default(),
+                    task.pr_url.unwrap_or_default()
+                )?;
+            }
+        }
+        ExportFormat::JiraCsv => {
+            let task_labels = db.get_all_task_labels().await?;
+            writeln!(out, "Summary,Description,Status,Assignee,Labels")?;
+            for task in tasks {
+                let status = columns.iter()
+                    .find(|c| c.id == task.column_id)
+                    .map(|c| c.name.as_str())
+                    .unwrap_or("Unknown");
+                // Jira labels can't contain spaces, and a space separates labels in one cell
+                let labels: Vec<_> = task_labels.iter()
+                    .filter(|tl| tl.task_id == task.id)
+                    .map(|tl| tl.name.split_whitespace().collect::<Vec<_>>().join("_"))
+                    .collect();
+                
+                writeln!(out, "{},{},{},{},{}",
+                    escape_csv(&task.title),
+                    escape_csv(&task.description.unwrap_or_default()),
+                    escape_csv(status),
+                    escape_csv(&task.assignee.unwrap_or_default()),
+                    escape_csv(&labels.join(" "))
+                )?;
+            }
+        }
+        ExportFormat::Markdown => {
+            writeln!(out, "# ProjectBoard Export\n")?;
+            for column in columns {
+                let column_tasks: Vec<_> = tasks.iter()
+                    .filter(|t| t.column_id == column.id)
+                    .collect();
+                
+                match limits.iter().find(|c| c.column.id == column.id) {
+                    Some(load) if load.wip_limit.is_some() => {
+                        writeln!(out, "## {}\n", format_column_load(load))?;
+                    }
+                    _ => writeln!(out, "## {} ({})\n", column.name, column_tasks.len())?,
+                }
+                for task in column_tasks {
+                    writeln!(out, "- **{}**: {}", task_ref(task.id), task.title)?;
+                    if let Some(desc) = &task.description {
+                        writeln!(out, "  - {}", desc)?;
+                    }
+                    if let Some(branch) = &task.branch_name {
+                        writeln!(out, "  - Branch: `{}`", branch)?;
+                    }
+                    if let Some(pr) = &task.pr_url {
+                        writeln!(out, "  - PR: {}", pr)?;
+                    }
+                    writeln!(out)?;
+                }
+            }
+        }
+        ExportFormat::Json => {
+            let columns: Vec<_> = limits.iter()
+                .map(|c| serde_json::json!({
+                    "id": c.column.id,
+                    "name": c.column.name,
+                    "order": c.column.order,
+                    "task_count": c.task_count,
+                    "wip_limit": c.wip_limit,
+                    "over_limit": c.is_over_limit(),
+                }))
+                .collect();
+            let export = serde_json::json!({ "columns": columns, "tasks": tasks });
+            writeln!(out, "{}", serde_json::to_string_pretty(&export)?)?;
+        }
+        ExportFormat::Dot => {
+            let dependencies = db.get_dependencies().await?;
+            out = format_dot(&columns, &tasks, &dependencies);
+        }
+        ExportFormat::GithubProjects => {
+            let export = format_github_projects(&columns, &tasks);
+            writeln!(out, "{}", serde_json::to_string_pretty(&export)?)?;
+        }
+        ExportFormat::Ndjson => unreachable!("ndjson is streamed by export_ndjson"),
+    }
+    
+    write_export(&out, output.as_deref())
+}
+
+async fn export_ndjson(
+    db: &Database,
+    output: Option<&Path>,
+    summary: bool,
+    include_archived: bool,
+    include_deleted: bool,
+    in_scope: &dyn Fn(i64) -> bool,
+    mut anonymizer: Option<&mut Anonymizer>,
+) -> Result<()> {
+    use futures::TryStreamExt;
+    
+    let columns = db.get_columns().await?;
+    let mut writer: Box<dyn std::io::Write> = match output {
+        Some(path) => {
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                fs::create_dir_all(parent)
+                    .context(format!("Failed to create directory {}", parent.display()))?;
+            }
+            let file = fs::File::create(path)
+                .context(format!("Failed to write {}", path.display()))?;
+            Box::new(std::io::BufWriter::new(file))
+        }
+        None => Box::new(std::io::BufWriter::new(std::io::stdout().lock())),
+    };
+    
+    // One task per line, written as it is read so memory stays flat
+    let mut count = 0;
+    let mut tasks = db.stream_tasks(include_archived, include_deleted);
+    while let Some(mut task) = tasks.try_next().await? {
+        if !in_scope(task.column_id) {
+            continue;
+        }
+        if let Some(anonymizer) = anonymizer.as_deref_mut() {
+            anonymizer.apply(&mut task);
+        }
+        let mut line = serde_json::to_value(&task)?;
+        line["column"] = columns.iter()
+            .find(|c| c.id == task.column_id)
+            .map(|c| c.name.clone())
+            .into();
+        serde_json::to_writer(&mut writer, &line)?;
+        writer.write_all(b"\n")?;
+        count += 1;
+    }
+    
+    if summary {
+        serde_json::to_writer(&mut writer, &serde_json::json!({ "summary": { "tasks": count } }))?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+    
+    if let Some(path) = output {
+        println!("{} Wrote {} ({} tasks)", glyphs::icon("📄"), path.display(), count);
+    }
+    
+    Ok(())
+}
+
+/// Takes people and git details out of exported tasks for `export --anonymize`.
+/// Assignees become user-1, user-2, … in order of appearance, so tasks that
+/// shared an assignee still do; branches, PRs, and worktrees are dropped.
+struct Anonymizer {
+    redact: Vec<ExportRedact>,
+    pseudonyms: std::collections::HashMap<String, String>,
+}
+
+impl Anonymizer {
+    fn new(redact: Vec<ExportRedact>) -> Self {
+        Anonymizer { redact, pseudonyms: std::collections::HashMap::new() }
+    }
+    
+    fn apply(&mut self, task: &mut Task) {
+        if let Some(assignee) = task.assignee.take() {
+            let next = self.pseudonyms.len() + 1;
+            let pseudonym = self.pseudonyms.entry(assignee).or_insert_with(|| format!("user-{}", next));
+            task.assignee = Some(pseudonym.clone());
+        }
+        task.branch_name = None;
+        task.pr_url = None;
+        task.pr_number = None;
+        task.worktree_path = None;
+        if self.redact.contains(&ExportRedact::Titles) {
+            task.title = format!("Task #{}", task.id);
+        }
+        if self.redact.contains(&ExportRedact::Descriptions) {
+            task.description = None;
+        }
+    }
+}
+
+/// The IDs of the columns `names` picks out, ignoring case, or None when no
+/// names are given and every column counts
+fn column_ids_named(columns: &[Column], names: &[String]) -> Result<Option<Vec<i64>>> {
+    if names.is_empty() {
+        return Ok(None);
+    }
+    names.iter()
+        .map(|name| {
+            columns.iter()
+                .find(|c| c.name.to_lowercase() == name.trim().to_lowercase())
+                .map(|c| c.id)
+                .ok_or_else(|| anyhow::anyhow!("Column '{}' not found", name))
+        })
+        .collect::<Result<Vec<_>>>()
+        .map(Some)
+}
+
+pub async fn import_trello_command(path: PathBuf) -> Result<()> {
+    let db = get_database().await?;
+    
+    let contents = fs::read_to_string(&path).context(format!("Failed to read {}", path.display()))?;
+    let board: TrelloBoard = serde_json::from_str(&contents)
+        .context(format!("{} is not a Trello board export", path.display()))?;
+    let done = db.get_column_by_role(ColumnRole::Done).await?;
+    let (columns, tasks) = trello_tasks(&board, done.as_ref().map(|c| c.name.as_str()));
+    let summary = db.import_tasks(&columns, &tasks).await?;
+    
+    // Log activity
+    db.log_activity("trello_imported", Some(format!("{}: {} tasks", board.name, summary.tasks))).await?;
+    
+    say!("{} Imported {} tasks from Trello board '{}'", glyphs::icon("📥"), summary.tasks, board.name);
+    if summary.archived > 0 {
+        say!("   {}  {} archived", glyphs::icon("🗄️"), summary.archived);
+    }
+    say!("   {} {} new columns", glyphs::icon("📋"), summary.columns_created);
+    say!("   {}  {} new labels", glyphs::icon("🏷️"), summary.labels_created);
+    
+    Ok(())
+}
+
+/// The parts of a Trello board's JSON export that `import trello` reads
+#[derive(Debug, serde::Deserialize)]
+struct TrelloBoard {
+    name: String,
+    #[serde(default)]
+    lists: Vec<TrelloList>,
+    #[serde(default)]
+    cards: Vec<TrelloCard>,
+    #[serde(default)]
+    checklists: Vec<TrelloChecklist>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct TrelloList {
+    id: String,
+    name: String,
+    #[serde(default)]
+    closed: bool,
+    #[serde(default)]
+    pos: f64,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TrelloCard {
+    name: String,
+    #[serde(default)]
+    desc: String,
+    id_list: String,
+    #[serde(default)]
+    closed: bool,
+    #[serde(default)]
+    pos: f64,
+    #[serde(default)]
+    labels: Vec<TrelloLabel>,
+    #[serde(default)]
+    id_checklists: Vec<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct TrelloLabel {
+    #[serde(default)]
+    name: String,
+    color: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TrelloChecklist {
+    id: String,
+    name: String,
+    #[serde(default)]
+    check_items: Vec<TrelloCheckItem>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct TrelloCheckItem {
+    name: String,
+    state: String,
+    #[serde(default)]
+    pos: f64,
+}
+
+/// The columns and tasks a Trello board becomes, in board order. Open lists
+/// become columns; an archived list doesn't, and its cards come in archived in
+/// `archive_column` (or the last open list). Checklists have nowhere else to go,
+/// so they're appended to the description as Markdown task lists. Unnamed labels
+/// go by their color.
+fn trello_tasks(board: &TrelloBoard, archive_column: Option<&str>) -> (Vec<String>, Vec<ImportedTask>) {
+    let mut lists: Vec<&TrelloList> = board.lists.iter().collect();
+    lists.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+    let mut columns: Vec<String> = Vec::new();
+    for list in lists.iter().filter(|l| !l.closed) {
+        if !columns.contains(&list.name) {
+            columns.push(list.name.clone());
+        }
+    }
+    let archive_column = archive_column.map(str::to_string).or_else(|| columns.last().cloned());
+    
+    let mut cards: Vec<(usize, &TrelloCard)> = board.cards.iter()
+        .filter_map(|card| Some((lists.iter().position(|l| l.id == card.id_list)?, card)))
+        .collect();
+    cards.sort_by(|(a_list, a), (b_list, b)| a_list.cmp(b_list).then(a.pos.total_cmp(&b.pos)));
+    
+    let tasks = cards.into_iter()
+        .map(|(list, card)| {
+            let list = lists[list];
+            let column = match (&archive_column, list.closed) {
+                (Some(column), true) => column.clone(),
+                _ => list.name.clone(),
+            };
+            if !columns.contains(&column) {
+                // Only archived lists, and nowhere to put their cards
+                columns.push(column.clone());
+            }
+            
+            let mut description = card.desc.trim().to_string();
+            for checklist in card.id_checklists.iter()
+                .filter_map(|id| board.checklists.iter().find(|c| &c.id == id))
+            {
+                let mut items: Vec<&TrelloCheckItem> = checklist.check_items.iter().collect();
+                items.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+                if !description.is_empty() {
+                    description.push_str("\n\n");
+                }
+                let _ = write!(description, "### {}", checklist.name);
+                for item in items {
+                    let mark = if item.state == "complete" { "x" } else { " " };
+                    let _ = write!(description, "\n- [{}] {}", mark, item.name);
+                }
+            }
+            
+            let mut labels: Vec<String> = Vec::new();
+            for label in &card.labels {
+                let name = match (label.name.trim(), &label.color) {
+                    ("", Some(color)) => color.clone(),
+                    ("", None) => continue,
+                    (name, _) => name.to_string(),
+                };
+                if !labels.contains(&name) {
+                    labels.push(name);
+                }
+            }
+            
+            ImportedTask {
+                title: card.name.clone(),
+                description: (!description.is_empty()).then_some(description),
+                column,
+                labels,
+                archived: card.closed || list.closed,
+            }
+        })
+        .collect();
+    
+    (columns, tasks)
+}
+
+// Helper functions
+async fn get_database() -> Result<Database> {
+    let repo_path = std::env::current_dir()?;
+    let db_path = repo_path.join(".projectboard").join("board.sqlite");
+    
+    if !db_path.exists() {
+        bail!("ProjectBoard not initialized. Run 'pb init' first.");
+    }
+    
+    Database::new(&db_path).await
+}
+
+async fn resolve_task(db: &Database, selector: &TaskSelector) -> Result<Task> {
+    match selector {
+        TaskSelector::Id(id) => db.get_task(*id).await?
+            .ok_or_else(|| anyhow::anyhow!("Task {} not found", task_ref(*id))),
+        TaskSelector::Query(query) => {
+            let mut tasks = db.search_tasks(query).await?;
+            match tasks.len() {
+                0 => bail!("No task matches '@{}'", query),
+                1 => Ok(tasks.remove(0)),
+                n => {
+                    println!("{} '@{}' matches {} tasks:", glyphs::icon("🔍"), query, n);
+                    for task in &tasks {
+                        println!("  {}: {}", task_ref(task.id), task.title);
+                    }
+                    bail!("'@{}' is ambiguous; use a task ID or a more specific query", query)
+                }
+            }
+        }
+    }
+}
+
+/// The column playing `role`, whatever it is called now
+async fn column_for_role(db: &Database, role: ColumnRole) -> Result<Column> {
+    db.get_column_by_role(role).await?
+        .ok_or_else(|| anyhow::anyhow!("No column has the {} role (set one with 'pb column set-role <name> {}')", role, role))
+}
+
+async fn column_name(db: &Database, column_id: i64) -> Result<String> {
+    let name = db.get_columns().await?
+        .into_iter()
+        .find(|c| c.id == column_id)
+        .map(|c| c.name)
+        .unwrap_or_else(|| "Unknown".to_string());
+    
+    Ok(name)
+}
+
+fn get_config() -> Result<Config> {
+    let repo_path = std::env::current_dir()?;
+    Config::load(&repo_path.join(".projectboard"))
+}
+
+fn format_column_load(column: &ColumnWithLimit) -> String {
+    match column.wip_limit {
+        Some(limit) if column.is_over_limit() => format!(
+            "{} ({}/{} WIP, over limit)", column.column.name, column.task_count, limit
+        ),
+        Some(limit) => format!("{} ({}/{} WIP)", column.column.name, column.task_count, limit),
+        None => format!("{} ({})", column.column.name, column.task_count),
+    }
+}
+
+/// `column` is shown after the title when tasks aren't already grouped by column
+fn print_task_line(task: &Task, column: Option<&str>) {
+    match column {
+        Some(column) => println!("  {}: {} [{}]", paint(&task_ref(task.id), "33"), task.title, column),
+        None => println!("  {}: {}", paint(&task_ref(task.id), "33"), task.title),
+    }
+    if let Some(desc) = &task.description {
+        println!("      {}", desc);
+    }
+    if let Some(branch) = &task.branch_name {
+        println!("      {} Branch: {}", glyphs::icon("🌿"), branch);
+    }
+    if let Some(pr) = &task.pr_url {
+        println!("      {} PR: {}", glyphs::icon("🔗"), pr);
+    }
+}
+
+fn confirm(prompt: &str) -> Result<bool> {
+    print!("{} [y/N] ", prompt);
+    std::io::stdout().flush()?;
+    
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Open `$EDITOR` (or vi/nano) on a temp file and return what was written.
+/// `instructions` is shown as a `#` comment header and stripped afterwards.
+fn edit_in_editor(instructions: &str, initial: &str) -> Result<String> {
+    let header: Vec<String> = instructions.lines()
+        .chain(["Lines starting with '#' above the text are ignored."])
+        .map(|line| format!("# {}", line))
+        .collect();
+    
+    let path = std::env::temp_dir().join(format!("pb-edit-{}.md", uuid::Uuid::new_v4()));
+    fs::write(&path, format!("{}\n\n{}", header.join("\n"), initial))
+        .context(format!("Failed to write {}", path.display()))?;
+    
+    let status = run_editor(&path);
+    let contents = fs::read_to_string(&path);
+    let _ = fs::remove_file(&path);
+    
+    let status = status?;
+    if !status.success() {
+        bail!("Editor exited with {}", status);
+    }
+    
+    Ok(strip_editor_header(&contents?, &header))
+}
+
+fn run_editor(path: &Path) -> Result<std::process::ExitStatus> {
+    use std::process::Command;
+    
+    // Go through the shell so values like "code --wait" work
+    if let Some(editor) = std::env::var("EDITOR").ok().filter(|e| !e.trim().is_empty()) {
+        return Command::new("sh")
+            .arg("-c")
+            .arg(format!("{} \"$1\"", editor))
+            .arg("sh")
+            .arg(path)
+            .status()
+            .context(format!("Failed to run editor '{}'", editor));
+    }
+    
+    for editor in ["vi", "nano"] {
+        match Command::new(editor).arg(path).status() {
+            Ok(status) => return Ok(status),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e).context(format!("Failed to run editor '{}'", editor)),
+        }
+    }
+    
+    bail!("No editor found. Set $EDITOR or install vi or nano.")
+}
+
+fn strip_editor_header(contents: &str, header: &[String]) -> String {
+    // Only our own header is dropped, so markdown headings in the text survive
+    contents.lines()
+        .skip_while(|line| header.iter().any(|h| h == line))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// Byte ranges of `@user` mentions in a comment, `@` included. Email
+/// addresses and anything inside backtick code spans or blocks are skipped.
+fn mention_ranges(text: &str) -> Vec<std::ops::Range<usize>> {
+    let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+    let mut ranges = Vec::new();
+    let mut in_code = false;
+    let mut prev: Option<char> = None;
+    let mut chars = text.char_indices().peekable();
+    
+    while let Some((start, c)) = chars.next() {
+        if c == '`' {
+            // A run of backticks opens or closes a span; ``` fences work the same way
+            while chars.next_if(|&(_, c)| c == '`').is_some() {}
+            in_code = !in_code;
+            prev = Some('`');
+            continue;
+        }
+        
+        let after_word = prev.is_some_and(|p| p.is_alphanumeric() || matches!(p, '.' | '_' | '-' | '+'));
+        if c == '@' && !in_code && !after_word {
+            let mut end = start + 1;
+            while let Some(&(i, c)) = chars.peek() {
+                if !is_name_char(c) {
+                    break;
+                }
+                end = i + c.len_utf8();
+                chars.next();
+            }
+            if end > start + 1 {
+                ranges.push(start..end);
+            }
+            prev = text[..end].chars().last();
+            continue;
+        }
+        prev = Some(c);
+    }
+    
+    ranges
+}
+
+fn parse_mentions(text: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for range in mention_ranges(text) {
+        let name = &text[range.start + 1..range.end];
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+fn highlight_mentions(text: &str) -> String {
+    let mut out = String::new();
+    let mut last = 0;
+    for range in mention_ranges(text) {
+        out.push_str(&text[last..range.start]);
+        out.push_str(&format!("\x1b[1;36m{}\x1b[0m", &text[range.clone()]));
+        last = range.end;
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+fn split_idea_content(content: &str) -> (String, Option<String>) {
+    match content.split_once("::") {
+        Some((title, description)) if !title.trim().is_empty() => {
+            let description = description.trim();
+            let description = (!description.is_empty()).then(|| description.to_string());
+            (title.trim().to_string(), description)
+        }
+        _ => (content.to_string(), None),
+    }
+}
+
+/// The PR template (if any) with the task description appended below it
+fn pr_body(template: Option<&str>, description: Option<&str>) -> String {
+    let parts: Vec<&str> = [template, description]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect();
+    parts.join("\n\n")
+}
+
+/// Append the task footer unless the body already carries it
+fn with_pr_footer(body: &str, footer: &str) -> String {
+    let footer = footer.trim();
+    if footer.is_empty() || body.contains(footer) {
+        return body.to_string();
+    }
+    if body.trim().is_empty() {
+        return footer.to_string();
+    }
+    format!("{}\n\n---\n{}", body.trim_end(), footer)
+}
+
+fn check_commit_branch(git_repo: &GitRepo, base_branch: &str, force: bool) -> Result<()> {
+    // Refuse to auto-commit straight onto the base branch unless forced
+    if force {
+        return Ok(());
+    }
+    if git_repo.get_current_branch()?.as_deref() == Some(base_branch) {
+        bail!(
+            "Refusing to commit on the base branch '{}'. Run 'pb start <id>' first, or pass --force to commit anyway.",
+            base_branch
+        );
+    }
+    
+    Ok(())
+}
+
+fn branch_name_for(task_id: i64, title: &str) -> String {
+    let slug = title
+        .to_lowercase()
+        .replace(' ', "-")
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == '-')
+        .collect::<String>();
+    format!("feature/{}-{}", task_id, slug)
+}
+
+#[derive(Debug, Clone)]
+struct GitIdentity {
+    name: Option<String>,
+    email: Option<String>,
+}
+
+// Resolved once per process so repeated lookups don't reshell git
+static GIT_IDENTITY: OnceLock<GitIdentity> = OnceLock::new();
+
+fn git_identity() -> &'static GitIdentity {
+    GIT_IDENTITY.get_or_init(|| GitIdentity {
+        name: git_config_value("user.name"),
+        email: git_config_value("user.email"),
+    })
+}
+
+#[cfg(feature = "clipboard")]
+pub(crate) fn copy_to_clipboard(text: &str) -> Result<()> {
+    let mut clipboard = arboard::Clipboard::new()
+        .context("Failed to open the system clipboard")?;
+    clipboard.set_text(text)
+        .context("Failed to copy to the system clipboard")?;
+    Ok(())
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub(crate) fn copy_to_clipboard(_text: &str) -> Result<()> {
+    bail!("pb was built without clipboard support (enable the 'clipboard' feature)")
+}
+
+fn current_user() -> Result<String> {
+    get_git_user().ok_or_else(|| anyhow::anyhow!("No git user.name configured. Set it with: git config user.name \"Your Name\""))
+}
+
+/// What an assignee argument selects: `@me` is your git user.name, `@unassigned`
+/// tasks nobody has picked up, and anything else that user (a leading `@` is optional)
+pub fn resolve_assignee_token(token: &str) -> Result<AssigneeFilter> {
+    resolve_assignee(token, current_user)
+}
+
+fn resolve_assignee(token: &str, current_user: impl FnOnce() -> Result<String>) -> Result<AssigneeFilter> {
+    let token = token.trim();
+    if token.eq_ignore_ascii_case("@me") {
+        Ok(AssigneeFilter::User(current_user()?))
+    } else if token.eq_ignore_ascii_case("@unassigned") {
+        Ok(AssigneeFilter::Unassigned)
+    } else {
+        let user = token.strip_prefix('@').unwrap_or(token);
+        if user.is_empty() {
+            bail!("Assignee cannot be empty");
+        }
+        Ok(AssigneeFilter::User(user.to_string()))
+    }
+}
+
+fn archived_marker(task: &Task) -> &'static str {
+    if task.archived_at.is_some() { " (archived)" } else { "" }
+}
+
+pub(crate) fn get_git_user() -> Option<String> {
+    git_identity().name.clone()
+}
+
+fn git_config_value(key: &str) -> Option<String> {
+    std::process::Command::new("git")
+        .args(["config", key])
+        .output()
+        .ok()
+        .and_then(|output| {
+            if output.status.success() {
+                String::from_utf8(output.stdout).ok()
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+            } else {
+                None
+            }
+        })
+}
+
+fn validate_url(url: &str) -> Result<()> {
+    let parsed = reqwest::Url::parse(url)
+        .context(format!("Invalid URL '{}'", url))?;
+    
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        bail!("Invalid URL '{}': expected an http(s) URL with a host", url);
+    }
+    
+    Ok(())
+}
+
+fn write_export(contents: &str, output: Option<&Path>) -> Result<()> {
+    let Some(path) = output else {
+        print!("{}", contents);
+        return Ok(());
+    };
+    
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .context(format!("Failed to create directory {}", parent.display()))?;
+    }
+    fs::write(path, contents)
+        .context(format!("Failed to write {}", path.display()))?;
+    
+    say!("{} Wrote {} ({} bytes)", glyphs::icon("📄"), path.display(), contents.len());
+    
+    Ok(())
+}
+
+// Node fill colors, assigned to columns in board order
+const DOT_COLORS: [&str; 6] = ["lightgrey", "lightblue", "khaki", "plum", "palegreen", "lightsalmon"];
+
+/// The shape GitHub Projects (v2) is built from over GraphQL: field definitions
+/// for `createProjectV2Field`, then draft items for `addProjectV2DraftIssue`
+/// with the values `updateProjectV2ItemFieldValue` sets. Columns become the
+/// options of a Status single-select, in board order.
+fn format_github_projects(columns: &[Column], tasks: &[Task]) -> serde_json::Value {
+    let options: Vec<_> = columns.iter()
+        .map(|c| serde_json::json!({ "name": c.name }))
+        .collect();
+    let items: Vec<_> = tasks.iter()
+        .map(|task| {
+            let status = columns.iter()
+                .find(|c| c.id == task.column_id)
+                .map(|c| c.name.as_str())
+                .unwrap_or("Unknown");
+            serde_json::json!({
+                "title": task.title,
+                "body": task.description.as_deref().unwrap_or_default(),
+                "fieldValues": [{ "field": "Status", "value": status }],
+            })
+        })
+        .collect();
+    
+    serde_json::json!({
+        "fields": [{ "name": "Status", "dataType": "SINGLE_SELECT", "options": options }],
+        "items": items,
+    })
+}
+
+/// A Graphviz digraph with one node per task and an edge from each blocker to
+/// the task it blocks. Dependencies on tasks not in `tasks` are left out.
+fn format_dot(columns: &[Column], tasks: &[Task], dependencies: &[TaskDependency]) -> String {
+    let mut out = String::from("digraph board {\n    rankdir=LR;\n    node [shape=box, style=\"rounded,filled\"];\n");
+    for task in tasks {
+        let (column, color) = columns.iter()
+            .position(|c| c.id == task.column_id)
+            .map(|i| (columns[i].name.as_str(), DOT_COLORS[i % DOT_COLORS.len()]))
+            .unwrap_or(("Unknown", "white"));
+        let label = format!("{}: {}\\n({})", task_ref(task.id), escape_dot(&task.title), escape_dot(column));
+        out.push_str(&format!("    t{} [label=\"{}\", fillcolor=\"{}\"];\n", task.id, label, color));
+    }
+    let shown = |id: i64| tasks.iter().any(|t| t.id == id);
+    for dependency in dependencies.iter().filter(|d| shown(d.task_id) && shown(d.blocked_by)) {
+        out.push_str(&format!("    t{} -> t{};\n", dependency.blocked_by, dependency.task_id));
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_csv(text: &str) -> String {
+    if text.contains(',') || text.contains('"') || text.contains('\n') {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// A plain task to build test tasks from with struct update syntax
+    fn task(id: i64, column_id: i64) -> Task {
+        let now = Utc::now();
+        Task {
+            id,
+            title: format!("Task {}", id),
+            description: None,
+            column_id,
+            assignee: None,
+            created_at: now,
+            updated_at: now,
+            branch_name: None,
+            pr_url: None,
+            pr_number: None,
+            worktree_path: None,
+            recurrence: None,
+            priority: None,
+            archived_at: None,
+            deleted_at: None,
+            version: 1,
+        }
+    }
+
+    fn repo_on_main(dir: &TempDir) -> GitRepo {
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        repo.commit(Some("refs/heads/main"), &signature, &signature, "Initial commit", &tree, &[]).unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+
+        GitRepo::open(dir.path()).unwrap()
+    }
+
+    #[test]
+    fn test_split_idea_content() {
+        assert_eq!(
+            split_idea_content("Refactor auth :: it's gotten messy"),
+            ("Refactor auth".to_string(), Some("it's gotten messy".to_string()))
+        );
+        assert_eq!(split_idea_content("Add dark mode"), ("Add dark mode".to_string(), None));
+        assert_eq!(split_idea_content("Trailing ::"), ("Trailing".to_string(), None));
+    }
+
+    #[test]
+    fn test_parse_mentions() {
+        assert_eq!(parse_mentions("@alice can you check this, @bob-smith?"), ["alice", "bob-smith"]);
+        assert_eq!(parse_mentions("(@carol) and @carol again"), ["carol"]);
+        assert!(parse_mentions("mail dave@example.com or first.last@example.com").is_empty());
+        assert!(parse_mentions("a lone @ sign").is_empty());
+    }
+
+    #[test]
+    fn test_parse_mentions_skips_code() {
+        assert_eq!(parse_mentions("run `npm i @types/node` then ping @erin"), ["erin"]);
+        assert_eq!(parse_mentions("```\n@decorator\n```\n@frank"), ["frank"]);
+        assert_eq!(highlight_mentions("hi @gina!"), "hi \x1b[1;36m@gina\x1b[0m!");
+    }
+
+    #[test]
+    fn test_pr_body_appends_description_to_template() {
+        let template = "## Summary\n\n## Testing\n";
+        assert_eq!(pr_body(Some(template), Some("Fixes login")), "## Summary\n\n## Testing\n\nFixes login");
+        assert_eq!(pr_body(Some(template), None), "## Summary\n\n## Testing");
+        assert_eq!(pr_body(None, Some("Fixes login")), "Fixes login");
+        assert_eq!(pr_body(Some("  \n"), None), "");
+    }
+
+    #[test]
+    fn test_pull_request_template_is_read_from_repo_root() {
+        let dir = TempDir::new().unwrap();
+        let git_repo = repo_on_main(&dir);
+        assert_eq!(git_repo.pull_request_template().unwrap(), None);
+
+        fs::create_dir(dir.path().join(".github")).unwrap();
+        fs::write(dir.path().join(".github/pull_request_template.md"), "## Summary\n").unwrap();
+        assert_eq!(git_repo.pull_request_template().unwrap().as_deref(), Some("## Summary\n"));
+    }
+
+    #[test]
+    fn test_pr_footer_is_added_once() {
+        let footer = "Tracked by ProjectBoard task #7";
+        let body = with_pr_footer("Fixes login", footer);
+        assert_eq!(body, "Fixes login\n\n---\nTracked by ProjectBoard task #7");
+        assert_eq!(with_pr_footer(&body, footer), body);
+        assert_eq!(with_pr_footer("", footer), footer);
+        assert_eq!(with_pr_footer("Fixes login", ""), "Fixes login");
+    }
+
+    #[test]
+    fn test_undo_and_back_move_sources() {
+        let entry = |event: &str, from: Option<&str>, to: Option<&str>| ActivityLog {
+            id: 1,
+            event: event.to_string(),
+            metadata: None,
+            created_at: Utc::now(),
+            task_id: Some(7),
+            column_from: from.map(String::from),
+            column_to: to.map(String::from),
+            column_to_id: None,
+        };
+        let moved = entry("task_moved", Some("To Do"), Some("Doing"));
+        assert_eq!(undo_move_source(7, Some(&moved), "Doing").unwrap(), "To Do");
+        assert!(undo_move_source(7, Some(&moved), "Review").is_err());
+        assert!(undo_move_source(7, Some(&entry("task_assigned", None, None)), "Doing").is_err());
+        assert!(undo_move_source(7, Some(&entry("task_moved", None, None)), "Doing").is_err());
+        assert!(undo_move_source(7, None, "Doing").is_err());
+
+        // `back` ignores whatever happened since, but not a pointless round trip
+        assert_eq!(back_move_source(7, Some(&moved), "Review").unwrap(), "To Do");
+        assert!(back_move_source(7, Some(&moved), "To Do").is_err());
+        assert!(back_move_source(7, Some(&entry("task_moved", None, None)), "Doing").is_err());
+        assert!(back_move_source(7, None, "Doing").is_err());
+    }
+
+    #[test]
+    fn test_recurrence_schedules_from_completion_and_rejects_overflow() {
+        let done = "2026-03-01T09:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let entry = |to: Option<(&str, i64)>, created_at: DateTime<Utc>| ActivityLog {
+            id: 1,
+            event: "task_moved".to_string(),
+            metadata: None,
+            created_at,
+            task_id: Some(7),
+            column_from: None,
+            column_to: to.map(|(name, _)| name.to_string()),
+            column_to_id: to.map(|(_, id)| id),
+        };
+        // Newest first: an edit after completion doesn't push the schedule back
+        let activity = [entry(None, done + Duration::days(1)), entry(Some(("Done", 3)), done), entry(Some(("Done", 3)), done - Duration::days(9))];
+        assert_eq!(completed_at(&activity, 3), Some(done));
+        assert_eq!(completed_at(&activity, 4), None);
+
+        // Renaming the Done column doesn't lose the moves made under its old name
+        let activity = [entry(Some(("Shipped", 3)), done + Duration::days(2)), entry(Some(("Done", 3)), done)];
+        assert_eq!(completed_at(&activity, 3), Some(done + Duration::days(2)));
+        assert_eq!(completed_at(&activity[1..], 3), Some(done));
+
+        assert_eq!("2w".parse::<Recurrence>().unwrap().next_after(done).unwrap(), done + Duration::days(14));
+        assert!("4294967295w".parse::<Recurrence>().is_err());
+        assert!("4294967295d".parse::<Recurrence>().unwrap().next_after(done).is_err());
+        assert!(Recurrence::Monthly.next_after(DateTime::<Utc>::MAX_UTC).is_err());
+    }
+
+    #[test]
+    fn test_title_from_branch() {
+        assert_eq!(title_from_branch("feature/12-fix-login-page").as_deref(), Some("Fix login page"));
+        assert_eq!(title_from_branch(&branch_name_for(3, "Add dark mode")).as_deref(), Some("Add dark mode"));
+        assert_eq!(title_from_branch("feature/x-fix"), None);
+        assert_eq!(title_from_branch("feature/12-"), None);
+        assert_eq!(title_from_branch("main"), None);
+    }
+
+    #[test]
+    fn test_changelog_groups_by_label() {
+        let since = "2026-03-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let task = |id: i64, title: &str, pr_number: Option<i64>| Task {
+            title: title.to_string(),
+            pr_url: pr_number.map(|n| format!("https://github.com/o/r/pull/{}", n)),
+            pr_number,
+            ..task(id, 5)
+        };
+        let tasks = [task(1, "Fix login", Some(12)), task(2, "Tidy docs", None), task(3, "Dark mode", Some(14))];
+
+        assert_eq!(
+            format_changelog(since, &tasks, &[]),
+            "## Changes since 2026-03-01\n\n\
+             - Fix login ([#12](https://github.com/o/r/pull/12))\n\
+             - Tidy docs\n\
+             - Dark mode ([#14](https://github.com/o/r/pull/14))\n"
+        );
+
+        let labels = [
+            TaskLabel { task_id: 1, name: "bug".to_string() },
+            TaskLabel { task_id: 1, name: "ui".to_string() },
+            TaskLabel { task_id: 3, name: "ui".to_string() },
+        ];
+        assert_eq!(
+            format_changelog(since, &tasks, &labels),
+            "## Changes since 2026-03-01\n\n\
+             ### bug\n\n- Fix login ([#12](https://github.com/o/r/pull/12))\n\n\
+             ### ui\n\n- Dark mode ([#14](https://github.com/o/r/pull/14))\n\n\
+             ### Other\n\n- Tidy docs\n"
+        );
+    }
+
+    #[test]
+    fn test_dot_export_skips_hidden_blockers() {
+        let now = Utc::now();
+        let column = |id: i64, name: &str| Column { id, name: name.to_string(), order: id as i32, role: None };
+        let task = |id: i64, column_id: i64, title: &str| Task {
+            title: title.to_string(),
+            ..task(id, column_id)
+        };
+        let dependency = |task_id: i64, blocked_by: i64| TaskDependency { task_id, blocked_by, created_at: now };
+
+        let dot = format_dot(
+            &[column(1, "To Do"), column(2, "Doing")],
+            &[task(1, 1, "Say \"hi\""), task(2, 2, "API")],
+            &[dependency(1, 2), dependency(1, 9)],
+        );
+        assert_eq!(
+            dot,
+            "digraph board {\n    rankdir=LR;\n    node [shape=box, style=\"rounded,filled\"];\n\
+             \x20   t1 [label=\"#1: Say \\\"hi\\\"\\n(To Do)\", fillcolor=\"lightgrey\"];\n\
+             \x20   t2 [label=\"#2: API\\n(Doing)\", fillcolor=\"lightblue\"];\n\
+             \x20   t2 -> t1;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_github_projects_export_maps_columns_to_status() {
+        let column = |id: i64, name: &str| Column { id, name: name.to_string(), order: id as i32, role: None };
+        let task = |id: i64, column_id: i64, description: Option<&str>| Task {
+            description: description.map(str::to_string),
+            ..task(id, column_id)
+        };
+
+        let export = format_github_projects(
+            &[column(1, "To Do"), column(2, "Doing")],
+            &[task(1, 2, Some("Details")), task(2, 1, None)],
+        );
+        assert_eq!(export, serde_json::json!({
+            "fields": [{ "name": "Status", "dataType": "SINGLE_SELECT", "options": [{ "name": "To Do" }, { "name": "Doing" }] }],
+            "items": [
+                { "title": "Task 1", "body": "Details", "fieldValues": [{ "field": "Status", "value": "Doing" }] },
+                { "title": "Task 2", "body": "", "fieldValues": [{ "field": "Status", "value": "To Do" }] },
+            ],
+        }));
+    }
+
+    #[test]
+    fn test_export_columns_are_named_case_insensitively() {
+        let columns: Vec<_> = ["Backlog", "In Review", "Done"].iter().enumerate()
+            .map(|(i, name)| Column { id: i as i64 + 1, name: name.to_string(), order: i as i32, role: None })
+            .collect();
+        let names = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(column_ids_named(&columns, &[]).unwrap(), None);
+        assert_eq!(column_ids_named(&columns, &names(&["done", "in review"])).unwrap(), Some(vec![3, 2]));
+        let err = column_ids_named(&columns, &names(&["Done", "Shipped"])).unwrap_err();
+        assert_eq!(err.to_string(), "Column 'Shipped' not found");
+    }
+
+    #[test]
+    fn test_anonymizer_keeps_structure_and_pseudonyms_stable() {
+        let task = |id: i64, assignee: Option<&str>| Task {
+            title: format!("Internal project {}", id),
+            description: Some("Ask alice@corp".to_string()),
+            assignee: assignee.map(str::to_string),
+            branch_name: Some(format!("feature/{}-internal", id)),
+            pr_url: Some(format!("https://github.com/corp/secret/pull/{}", id)),
+            pr_number: Some(id),
+            worktree_path: Some("/home/alice/wt".to_string()),
+            ..task(id, 1)
+        };
+        let mut tasks = [task(1, Some("alice")), task(2, Some("bob")), task(3, Some("alice")), task(4, None)];
+
+        let mut anonymizer = Anonymizer::new(Vec::new());
+        tasks.iter_mut().for_each(|t| anonymizer.apply(t));
+        let assignees: Vec<_> = tasks.iter().map(|t| t.assignee.as_deref()).collect();
+        assert_eq!(assignees, [Some("user-1"), Some("user-2"), Some("user-1"), None]);
+        assert!(tasks.iter().all(|t| t.branch_name.is_none() && t.pr_url.is_none() && t.worktree_path.is_none()));
+        assert_eq!(tasks[0].title, "Internal project 1");
+
+        let mut anonymizer = Anonymizer::new(vec![ExportRedact::Titles, ExportRedact::Descriptions]);
+        let mut redacted = task(7, None);
+        anonymizer.apply(&mut redacted);
+        assert_eq!((redacted.title.as_str(), redacted.description), ("Task #7", None));
+    }
+
+    #[test]
+    fn test_stats_and_doctor_json_schemas() {
+        let load = |id: i64, name: &str, wip_limit: Option<i64>, task_count: i64| ColumnWithLimit {
+            column: Column { id, name: name.to_string(), order: id as i32, role: None },
+            wip_limit,
+            task_count,
+        };
+        let logged = |column_id: i64, assignee: Option<&str>, minutes: i64| LoggedTime {
+            column_id,
+            assignee: assignee.map(str::to_string),
+            minutes,
+        };
+        let stats = board_stats(
+            &[load(1, "To Do", None, 3), load(2, "Doing", Some(1), 2)],
+            4,
+            &[logged(1, Some("bo"), 30), logged(1, None, 15), logged(2, Some("al"), 60), logged(2, Some("bo"), 90)],
+        );
+        assert_eq!(serde_json::to_value(&stats).unwrap(), serde_json::json!({
+            "tasks": 5,
+            "ideas": 4,
+            "logged_minutes": 195,
+            "columns": [
+                { "name": "To Do", "tasks": 3, "wip_limit": null, "over_limit": false, "logged_minutes": 45 },
+                { "name": "Doing", "tasks": 2, "wip_limit": 1, "over_limit": true, "logged_minutes": 150 },
+            ],
+            "logged_by_assignee": [
+                { "assignee": "al", "minutes": 60 },
+                { "assignee": "bo", "minutes": 120 },
+                { "assignee": null, "minutes": 15 },
+            ],
+        }));
+
+        let check = DoctorCheck::new("review_role", DoctorStatus::Problem, "No column has the review role")
+            .hint("run 'pb column set-role <name> review'");
+        assert_eq!(serde_json::to_value(&check).unwrap(), serde_json::json!({
+            "name": "review_role",
+            "status": "problem",
+            "message": "No column has the review role",
+            "hint": "run 'pb column set-role <name> review'",
+        }));
+    }
+
+    #[test]
+    fn test_work_durations() {
+        assert_eq!(parse_work_duration("2h").unwrap(), 120);
+        assert_eq!(parse_work_duration("30m").unwrap(), 30);
+        assert_eq!(parse_work_duration("1h30m").unwrap(), 90);
+        for invalid in ["", "2", "h", "1.5h", "2d", "2é", "-1h", "0m"] {
+            assert!(parse_work_duration(invalid).is_err(), "{:?} should be rejected", invalid);
+        }
+        assert_eq!(format_minutes(150), "2h 30m");
+        assert_eq!(format_minutes(45), "45m");
+        assert_eq!(format_minutes(120), "2h");
+
+        let start = Utc::now();
+        assert_eq!(elapsed_minutes(start, start + Duration::seconds(29)), 0);
+        assert_eq!(elapsed_minutes(start, start + Duration::seconds(90)), 2);
+        assert_eq!(elapsed_minutes(start, start + Duration::hours(3)), 180);
+        assert_eq!(elapsed_minutes(start, start - Duration::minutes(5)), 0);
+    }
+
+    #[test]
+    fn test_rewrite_repo_url() {
+        let rewrite = |url: &str| rewrite_repo_url(url, "old-org/app", "new-org/app");
+        assert_eq!(rewrite("https://github.com/old-org/app/pull/12").as_deref(), Some("https://github.com/new-org/app/pull/12"));
+        assert_eq!(rewrite("https://github.com/Old-Org/App/issues/3#top").as_deref(), Some("https://github.com/new-org/app/issues/3#top"));
+        assert_eq!(rewrite("https://github.com/old-org/app").as_deref(), Some("https://github.com/new-org/app"));
+        assert_eq!(rewrite("https://github.com/old-org/app-docs/pull/1"), None);
+        assert_eq!(rewrite("https://github.com/other/app/pull/1"), None);
+        assert_eq!(rewrite("https://example.com/github.com/old-org/app"), None);
+        assert_eq!(rewrite("https://github.com/old"), None);
+
+        assert!(is_owner_repo("owner/repo"));
+        assert!(!is_owner_repo("owner"));
+        assert!(!is_owner_repo("owner/repo/pull"));
+        assert!(!is_owner_repo("/repo"));
+    }
+
+    #[test]
+    fn test_resolve_assignee_tokens() {
+        let me = || Ok("alice".to_string());
+        assert_eq!(resolve_assignee("@me", me).unwrap(), AssigneeFilter::User("alice".to_string()));
+        assert_eq!(resolve_assignee("@Unassigned", me).unwrap(), AssigneeFilter::Unassigned);
+        assert_eq!(resolve_assignee(" @bob ", me).unwrap(), AssigneeFilter::User("bob".to_string()));
+        assert_eq!(resolve_assignee("bob", me).unwrap(), AssigneeFilter::User("bob".to_string()));
+        assert!(resolve_assignee("@", me).is_err());
+        assert!(resolve_assignee("@me", || Err(anyhow::anyhow!("no git user"))).is_err());
+    }
+
+    #[test]
+    fn test_dependency_cycles_are_reported_once() {
+        let now = Utc::now();
+        let dependency = |task_id: i64, blocked_by: i64| TaskDependency { task_id, blocked_by, created_at: now };
+
+        assert!(dependency_cycles(&[dependency(1, 2), dependency(2, 3), dependency(1, 3)]).is_empty());
+        assert_eq!(
+            dependency_cycles(&[dependency(3, 1), dependency(1, 2), dependency(2, 3), dependency(4, 4), dependency(5, 1)]),
+            vec![vec![1, 2, 3], vec![4]]
+        );
+    }
+
+    #[test]
+    fn test_board_issues_for_columns_and_branches() {
+        let task = |id: i64, column_id: i64, branch_name: Option<&str>, pr_url: Option<&str>| Task {
+            branch_name: branch_name.map(str::to_string),
+            pr_url: pr_url.map(str::to_string),
+            ..task(id, column_id)
+        };
+        let doing = Column { id: 2, name: "Doing".to_string(), order: 2, role: Some(ColumnRole::Doing.to_string()) };
+        let review = Column { id: 3, name: "Review".to_string(), order: 3, role: Some(ColumnRole::Review.to_string()) };
+        let tasks = [
+            task(1, 2, None, None),
+            task(2, 2, Some("task-2"), None),
+            task(3, 3, Some("task-3"), None),
+            task(4, 3, Some("gone"), Some("https://github.com/o/r/pull/4")),
+            task(5, 4, Some("gone"), None),
+            task(6, 2, None, None),
+            task(7, 3, None, None),
+        ];
+
+        let ids = |issues: Vec<BoardIssue>| issues.iter().map(|i| i.task_id).collect::<Vec<_>>();
+        let started_branchless = std::collections::HashSet::from([6]);
+        assert_eq!(ids(column_issues(&tasks, Some(&doing), Some(&review), &started_branchless)), [1, 3]);
+        assert_eq!(ids(column_issues(&tasks, None, Some(&review), &started_branchless)), [3]);
+        assert_eq!(ids(column_issues(&tasks, Some(&doing), Some(&review), &std::collections::HashSet::new())), [1, 3, 6]);
+        assert_eq!(ids(missing_branch_issues(&tasks, Some(4), |branch| branch != "gone")), [4]);
+    }
+
+    #[test]
+    fn test_group_tasks_by_label_and_assignee() {
+        let task = |id: i64, assignee: Option<&str>| Task {
+            assignee: assignee.map(str::to_string),
+            ..task(id, 1)
+        };
+        let tasks = [task(1, Some("sam")), task(2, None), task(3, Some("alex")), task(4, Some("sam"))];
+        let labels = [
+            TaskLabel { task_id: 1, name: "bug".to_string() },
+            TaskLabel { task_id: 1, name: "api".to_string() },
+            TaskLabel { task_id: 4, name: "bug".to_string() },
+        ];
+        let ids = |grouping, labels: &[TaskLabel]| group_tasks(&tasks, grouping, labels).into_iter()
+            .map(|(name, tasks)| (name, tasks.iter().map(|t| t.id).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(ids(ListGrouping::Assignee, &[]), [
+            ("alex".to_string(), vec![3]),
+            ("sam".to_string(), vec![1, 4]),
+            ("(unassigned)".to_string(), vec![2]),
+        ]);
+        assert_eq!(ids(ListGrouping::Label, &labels), [
+            ("api".to_string(), vec![1]),
+            ("bug".to_string(), vec![1, 4]),
+            ("(no label)".to_string(), vec![2, 3]),
+        ]);
+    }
+
+    #[test]
+    fn test_dependency_tree_nests_blocked_tasks_and_marks_cycles() {
+        let now = Utc::now();
+        let blocked = |task_id: i64, blocked_by: i64| TaskDependency { task_id, blocked_by, created_at: now };
+        let columns = [Column { id: 1, name: "Todo".to_string(), order: 1, role: None }];
+        let tasks: Vec<Task> = (1..=7).map(|id| task(id, 1)).collect();
+        // 8 is not shown, so its edge is dropped
+        let dependencies = [
+            blocked(2, 1), blocked(3, 2), blocked(4, 2), blocked(4, 1),
+            blocked(7, 6), blocked(6, 7), blocked(5, 8),
+        ];
+
+        assert_eq!(format_dependency_tree(&tasks, &dependencies, &columns), [
+            "  #1: Task 1 [Todo]",
+            "  ├── #2: Task 2 [Todo]",
+            "  │   ├── #3: Task 3 [Todo]",
+            "  │   └── #4: Task 4 [Todo]",
+            "  └── #4: Task 4 [Todo]",
+            "  #5: Task 5 [Todo]",
+            "  #6: Task 6 [Todo]",
+            "  └── #7: Task 7 [Todo]",
+            "      └── ↻ #6 (cycle)",
+            "",
+        ].join("\n"));
+    }
+
+    #[test]
+    fn test_trello_export_maps_lists_cards_and_checklists() {
+        let board: TrelloBoard = serde_json::from_value(serde_json::json!({
+            "name": "Launch",
+            "lists": [
+                {"id": "l2", "name": "Doing", "closed": false, "pos": 2.0},
+                {"id": "l1", "name": "Ideas", "closed": false, "pos": 1.0},
+                {"id": "l3", "name": "Old", "closed": true, "pos": 3.0}
+            ],
+            "cards": [
+                {"name": "Ship", "desc": "Soon", "idList": "l2", "closed": false, "pos": 2.0,
+                 "labels": [{"name": "api", "color": "red"}, {"name": "", "color": "blue"}],
+                 "idChecklists": ["c1"]},
+                {"name": "Draft", "desc": "", "idList": "l2", "closed": false, "pos": 1.0},
+                {"name": "Gone", "desc": "", "idList": "l1", "closed": true, "pos": 1.0},
+                {"name": "Shelved", "desc": "", "idList": "l3", "closed": false, "pos": 1.0}
+            ],
+            "checklists": [
+                {"id": "c1", "name": "Steps", "checkItems": [
+                    {"name": "Deploy", "state": "incomplete", "pos": 2.0},
+                    {"name": "Build", "state": "complete", "pos": 1.0}
+                ]}
+            ]
+        })).unwrap();
+
+        let (columns, tasks) = trello_tasks(&board, Some("Done"));
+        assert_eq!(columns, ["Ideas", "Doing", "Done"]);
+        let summary: Vec<_> = tasks.iter()
+            .map(|t| (t.title.as_str(), t.column.as_str(), t.archived))
+            .collect();
+        assert_eq!(summary, [
+            ("Gone", "Ideas", true),
+            ("Draft", "Doing", false),
+            ("Ship", "Doing", false),
+            ("Shelved", "Done", true),
+        ]);
+        assert_eq!(tasks[2].description.as_deref(), Some("Soon\n\n### Steps\n- [x] Build\n- [ ] Deploy"));
+        assert_eq!(tasks[2].labels, ["api", "blue"]);
+        assert_eq!(tasks[1].description, None);
+
+        // Without a done column, archived lists' cards land in the last open list
+        let (columns, tasks) = trello_tasks(&board, None);
+        assert_eq!(columns, ["Ideas", "Doing"]);
+        assert_eq!(tasks[3].column, "Doing");
+    }
+
+    #[test]
+    fn test_label_names_are_trimmed_and_checked() {
+        let names = |names: &[&str]| label_names(&names.iter().map(|n| n.to_string()).collect::<Vec<_>>());
+        assert_eq!(names(&[" bug ", "urgent", "bug"]).unwrap(), ["bug", "urgent"]);
+        assert!(names(&["bug,urgent"]).is_err());
+        assert!(names(&["  "]).is_err());
+    }
+
+    #[test]
+    fn test_prune_candidates_spare_current_and_base() {
+        let task = |id: i64, branch: Option<&str>| Task {
+            branch_name: branch.map(str::to_string),
+            ..task(id, 5)
+        };
+        let tasks = [
+            task(1, Some("task-1-login")),
+            task(2, Some("task-2-docs")),
+            task(3, Some("main")),
+            task(4, Some("task-4-gone")),
+            task(5, None),
+        ];
+        let local = ["main", "task-1-login", "task-2-docs"].map(String::from);
+
+        let ids = |current| prune_candidates(&tasks, &local, current, "main").iter().map(|t| t.id).collect::<Vec<_>>();
+        assert_eq!(ids(Some("task-2-docs")), [1]);
+        assert_eq!(ids(None), [1, 2]);
+    }
+
+    #[test]
+    fn test_regex_hits_cover_descriptions_and_comments() {
+        let now = Utc::now();
+        let task = |id: i64, title: &str, description: Option<&str>| Task {
+            title: title.to_string(),
+            description: description.map(str::to_string),
+            ..task(id, 1)
+        };
+        let tasks = [
+            task(1, "Fix ERR-42 crash", None),
+            task(2, "Tidy docs", Some("Intro\n  see ERR-7 here")),
+            task(3, "Dark mode", None),
+            task(4, "Login", None),
+        ];
+        let comments = [Comment { id: 9, task_id: 4, author: "bob".to_string(), text: "reproduces as ERR-100".to_string(), created_at: now, resolved: false }];
+        let re = regex::Regex::new(r"ERR-\d+").unwrap();
+
+        let hits: Vec<_> = regex_hits(&re, &tasks, &comments).into_iter().map(|(t, lines)| (t.id, lines)).collect();
+        assert_eq!(hits, [
+            (1, vec![]),
+            (2, vec!["description: see ERR-7 here".to_string()]),
+            (4, vec!["comment #9: reproduces as ERR-100".to_string()]),
+        ]);
+    }
+
+    #[test]
+    fn test_check_column_specs() {
+        let specs = |list: &[&str]| list.iter().map(|s| s.parse::<ColumnSpec>().unwrap()).collect::<Vec<_>>();
+        assert!(check_column_specs(&specs(&["Inbox:backlog", "Doing", "Shipped:done"])).is_ok());
+        assert!(check_column_specs(&specs(&["Doing", "Doing"])).is_err());
+        assert!(check_column_specs(&specs(&["Done", "Shipped:done"])).is_err());
+        assert!(check_column_specs(&[]).is_err());
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let now = "2026-03-10T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(parse_since("2026-03-01", now).unwrap().to_rfc3339(), "2026-03-01T00:00:00+00:00");
+        assert_eq!(parse_since("6h", now).unwrap(), now - Duration::hours(6));
+        assert_eq!(parse_since("2w", now).unwrap(), now - Duration::days(14));
+        assert!(parse_since("3x", now).is_err());
+        assert!(parse_since("d", now).is_err());
+        assert!(parse_since("", now).is_err());
+        assert!(parse_since("2é", now).is_err());
+        assert!(parse_since("-3d", now).is_err());
+        assert!(parse_since("0d", now).is_err());
+        assert!(parse_since("9223372036854775807h", now).is_err());
+        assert!(parse_since("99999999w", now).is_err());
+    }
+
+    #[test]
+    fn test_resolve_color() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string())
+        };
+        assert!(resolve_color(ColorChoice::Auto, env(&[]), true));
+        assert!(!resolve_color(ColorChoice::Auto, env(&[]), false));
+        assert!(!resolve_color(ColorChoice::Auto, env(&[("NO_COLOR", "1")]), true));
+        assert!(!resolve_color(ColorChoice::Auto, env(&[("CLICOLOR", "0")]), true));
+        assert!(resolve_color(ColorChoice::Auto, env(&[("CLICOLOR_FORCE", "1")]), false));
+        assert!(resolve_color(ColorChoice::Always, env(&[("NO_COLOR", "1")]), false));
+        assert!(!resolve_color(ColorChoice::Never, env(&[]), true));
+    }
+
+    #[test]
+    fn test_strip_editor_header_keeps_markdown_headings() {
+        let header = vec!["# Comment on task #1".to_string(), "# Ignored".to_string()];
+        let contents = "# Comment on task #1\n# Ignored\n\n# Summary\nLooks good\n";
+        assert_eq!(strip_editor_header(contents, &header), "# Summary\nLooks good");
+        assert_eq!(strip_editor_header("# Comment on task #1\n\n", &header), "");
+    }
+
+    #[test]
+    fn test_done_is_blocked_on_base_branch() {
+        let dir = TempDir::new().unwrap();
+        let git_repo = repo_on_main(&dir);
+
+        assert!(check_commit_branch(&git_repo, "main", false).is_err());
+        assert!(check_commit_branch(&git_repo, "main", true).is_ok());
+    }
+
+    #[test]
+    fn test_done_is_allowed_on_task_branch() {
+        let dir = TempDir::new().unwrap();
+        let git_repo = repo_on_main(&dir);
+        git_repo.create_branch("feature/1-task", None).unwrap();
+        git_repo.checkout_branch("feature/1-task").unwrap();
+
+        assert!(check_commit_branch(&git_repo, "main", false).is_ok());
+    }
+
+    #[test]
+    fn test_create_branch_from_tag() {
+        let dir = TempDir::new().unwrap();
+        let git_repo = repo_on_main(&dir);
+        let repo = git2::Repository::open(dir.path()).unwrap();
+        let initial = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.tag_lightweight("v1.0", initial.as_object(), false).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree = initial.tree().unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "Later", &tree, &[&initial]).unwrap();
+
+        git_repo.create_branch("hotfix/2-crash", Some("v1.0")).unwrap();
+        let branch = repo.find_branch("hotfix/2-crash", git2::BranchType::Local).unwrap();
+        assert_eq!(branch.get().peel_to_commit().unwrap().id(), initial.id());
+
+        let err = git_repo.create_branch("hotfix/3-typo", Some("v9.9")).unwrap_err();
+        assert!(err.to_string().contains("'v9.9' is not a branch, tag, or commit"));
     }
 }
@@ -0,0 +1,288 @@
+use anyhow::{Result, Context};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::db::ColumnRole;
+use crate::github::MergeMethod;
+
+/// Repo-specific settings stored in `.projectboard/config.toml`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Create a git branch per task in `pb start`
+    pub create_branches: bool,
+    /// Sign commits made by `pb done` using the signing setup from git config
+    pub sign_commits: bool,
+    /// Branch that task branches are created from and PRs are opened against
+    pub base_branch: String,
+    /// Remote that task branches are pushed to
+    pub remote: String,
+    /// Remote whose repository pull requests are opened against
+    pub pr_remote: String,
+    /// GitHub token, used when GITHUB_TOKEN is not set
+    pub github_token: Option<String>,
+    /// Footer `pb submit` adds to PR bodies; `{id}` becomes the task ID as shown
+    /// (`#12`, or `PROJ-12` under `task_prefix`), empty disables it
+    pub pr_footer: String,
+    /// How `pb merge` merges pull requests
+    pub merge_method: MergeMethod,
+    /// Days without an update before `pb board` marks a task as stale; 0 turns it off
+    pub stale_days: u32,
+    /// Print ASCII markers instead of emoji, for terminals whose fonts lack them
+    pub ascii_icons: bool,
+    /// Time tasks from `pb start` to `pb done` and add it to the worklog
+    pub track_time: bool,
+    /// Archive Done tasks untouched for this long (`<n>d` or `<n>w`) when
+    /// `pb list` or `pb board` loads; unset leaves them alone
+    pub auto_archive_done_after: Option<String>,
+    /// Make `pb move` into Doing create the task branch like `pb start`, and
+    /// into Done commit and push like `pb done`
+    pub move_triggers_git: bool,
+    /// Show task IDs as `<prefix>-<id>` (e.g. `PROJ-12`) instead of `#<id>`
+    pub task_prefix: Option<String>,
+    /// Settings for `pb board`, under `[tui]`
+    pub tui: TuiConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            create_branches: true,
+            sign_commits: false,
+            base_branch: "main".to_string(),
+            remote: "origin".to_string(),
+            pr_remote: "origin".to_string(),
+            github_token: None,
+            pr_footer: "Tracked by ProjectBoard task {id}".to_string(),
+            merge_method: MergeMethod::Merge,
+            stale_days: 14,
+            ascii_icons: false,
+            track_time: false,
+            auto_archive_done_after: None,
+            move_triggers_git: false,
+            task_prefix: None,
+            tui: TuiConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TuiConfig {
+    /// Fields shown on each card under its ID and title, in this order
+    pub card_fields: Vec<CardField>,
+}
+
+/// A task field `pb board` can show on cards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CardField {
+    Assignee,
+    Labels,
+    Priority,
+    Branch,
+}
+
+impl Config {
+    /// Written by `pb config edit` when the repo has no config yet; every key
+    /// is commented out at its default value
+    pub const TEMPLATE: &'static str = r#"# ProjectBoard settings for this repository.
+# Uncomment a line to change the setting; commented keys use the default shown.
+
+# Create a git branch per task in `pb start`
+# create_branches = true
+
+# Sign commits made by `pb done` using the signing setup from git config
+# sign_commits = false
+
+# Branch that task branches are created from and PRs are opened against
+# base_branch = "main"
+
+# Remote that task branches are pushed to
+# remote = "origin"
+
+# Remote whose repository pull requests are opened against
+# pr_remote = "origin"
+
+# GitHub token, used when GITHUB_TOKEN is not set
+# github_token = "ghp_..."
+
+# Footer `pb submit` adds to PR bodies; {id} becomes the task ID (#12, or PROJ-12
+# under task_prefix), "" disables it
+# pr_footer = "Tracked by ProjectBoard task {id}"
+
+# How `pb merge` merges pull requests: "merge", "squash", or "rebase"
+# merge_method = "merge"
+
+# Days without an update before `pb board` marks a task as stale; 0 turns it off
+# stale_days = 14
+
+# Print ASCII markers like [#] instead of emoji, for terminals whose fonts lack them
+# ascii_icons = false
+
+# Time tasks from `pb start` to `pb done` and add the elapsed time to the worklog
+# track_time = false
+
+# Archive Done tasks untouched for this long (<n>d or <n>w, at least 1d) whenever
+# `pb list` or `pb board` loads; unset never archives automatically
+# auto_archive_done_after = "14d"
+
+# Make `pb move` do the git side of `pb start` when a task enters Doing (create and
+# check out its branch) and of `pb done` when it enters Done (commit and push)
+# move_triggers_git = false
+
+# Show task IDs Jira-style as <prefix>-<id> (PROJ-12) instead of #12; commands
+# accept either form, and IDs are still stored as plain numbers
+# task_prefix = "PROJ"
+
+# Settings for `pb board`; keep this table last, since keys below it belong to it
+# [tui]
+# Fields shown on each card under its title: "assignee", "labels", "priority", "branch"
+# card_fields = []
+"#;
+
+    pub fn path(pb_dir: &Path) -> PathBuf {
+        pb_dir.join("config.toml")
+    }
+
+    pub fn load(pb_dir: &Path) -> Result<Self> {
+        let path = Self::path(pb_dir);
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+
+        let contents = fs::read_to_string(&path)
+            .context(format!("Failed to read {}", path.display()))?;
+        let config = toml::from_str(&contents)
+            .context(format!("Failed to parse {}", path.display()))?;
+
+        Ok(config)
+    }
+}
+
+/// Per-user settings shared by every repository, read from
+/// `$XDG_CONFIG_HOME/projectboard/defaults.toml` (`~/.config/...` when unset)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserDefaults {
+    /// Columns `pb init` creates instead of the built-in five
+    pub columns: Option<Vec<ColumnSpec>>,
+}
+
+impl UserDefaults {
+    pub fn path() -> Option<PathBuf> {
+        config_home(std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from), dirs::home_dir())
+            .map(|dir| dir.join("projectboard").join("defaults.toml"))
+    }
+
+    pub fn load() -> Result<Self> {
+        let Some(path) = Self::path().filter(|p| p.exists()) else {
+            return Ok(UserDefaults::default());
+        };
+
+        let contents = fs::read_to_string(&path)
+            .context(format!("Failed to read {}", path.display()))?;
+        let defaults = toml::from_str(&contents)
+            .context(format!("Failed to parse {}", path.display()))?;
+
+        Ok(defaults)
+    }
+}
+
+// The XDG spec says to ignore a relative XDG_CONFIG_HOME
+fn config_home(xdg_config_home: Option<PathBuf>, home: Option<PathBuf>) -> Option<PathBuf> {
+    xdg_config_home
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| home.map(|home| home.join(".config")))
+}
+
+/// A column for a new board: `name` or `name:role` on the command line, or
+/// `{ name = "...", role = "..." }` in defaults.toml
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnSpec {
+    pub name: String,
+    /// Defaults to the role whose built-in column has this name (Backlog, To Do, ...)
+    #[serde(default)]
+    pub role: Option<ColumnRole>,
+}
+
+impl ColumnSpec {
+    pub fn role(&self) -> Option<ColumnRole> {
+        self.role.or_else(|| self.name.replace(' ', "").parse().ok())
+    }
+}
+
+impl std::str::FromStr for ColumnSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (name, role) = match s.rsplit_once(':') {
+            Some((name, role)) => (name, Some(role.parse()?)),
+            None => (s, None),
+        };
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(format!("'{}' has no column name", s));
+        }
+        Ok(ColumnSpec { name: name.to_string(), role })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_documents_every_default() {
+        let uncommented: String = Config::TEMPLATE.lines()
+            .filter_map(|line| line.strip_prefix("# "))
+            .filter(|line| (line.contains(" = ") || line.starts_with('[')) && !line.starts_with("github_token") && !line.starts_with("auto_archive") && !line.starts_with("task_prefix"))
+            .map(|line| format!("{}\n", line))
+            .collect();
+        let config: Config = toml::from_str(&uncommented).unwrap();
+        let defaults = Config::default();
+
+        assert_eq!(config.create_branches, defaults.create_branches);
+        assert_eq!(config.sign_commits, defaults.sign_commits);
+        assert_eq!(config.base_branch, defaults.base_branch);
+        assert_eq!(config.remote, defaults.remote);
+        assert_eq!(config.pr_remote, defaults.pr_remote);
+        assert_eq!(config.pr_footer, defaults.pr_footer);
+        assert_eq!(defaults.pr_footer, "Tracked by ProjectBoard task {id}");
+        assert_eq!(config.merge_method, defaults.merge_method);
+        assert_eq!(config.stale_days, defaults.stale_days);
+        assert_eq!(config.ascii_icons, defaults.ascii_icons);
+        assert_eq!(config.track_time, defaults.track_time);
+        assert_eq!(config.move_triggers_git, defaults.move_triggers_git);
+        assert_eq!(config.tui.card_fields, defaults.tui.card_fields);
+        assert_eq!(uncommented.lines().count(), 13);
+    }
+
+    #[test]
+    fn test_config_home_follows_xdg() {
+        let home = Some(PathBuf::from("/home/ada"));
+        assert_eq!(config_home(None, home.clone()), Some(PathBuf::from("/home/ada/.config")));
+        assert_eq!(config_home(Some(PathBuf::from("/xdg")), home.clone()), Some(PathBuf::from("/xdg")));
+        assert_eq!(config_home(Some(PathBuf::from("relative")), home), Some(PathBuf::from("/home/ada/.config")));
+        assert_eq!(config_home(None, None), None);
+    }
+
+    #[test]
+    fn test_column_specs() {
+        let spec: ColumnSpec = "Shipped:done".parse().unwrap();
+        assert_eq!((spec.name.as_str(), spec.role()), ("Shipped", Some(ColumnRole::Done)));
+        let spec: ColumnSpec = "To Do".parse().unwrap();
+        assert_eq!(spec.role(), Some(ColumnRole::Todo));
+        let spec: ColumnSpec = "Ideas".parse().unwrap();
+        assert_eq!(spec.role(), None);
+        assert!("Ideas:someday".parse::<ColumnSpec>().is_err());
+        assert!(":done".parse::<ColumnSpec>().is_err());
+
+        let defaults: UserDefaults = toml::from_str(
+            "columns = [{ name = \"Inbox\", role = \"backlog\" }, { name = \"Doing\" }]"
+        ).unwrap();
+        let roles: Vec<_> = defaults.columns.unwrap().iter().map(ColumnSpec::role).collect();
+        assert_eq!(roles, [Some(ColumnRole::Backlog), Some(ColumnRole::Doing)]);
+    }
+}
@@ -1,8 +1,9 @@
-use sqlx::{sqlite::SqlitePool, Sqlite, Pool};
+use sqlx::{sqlite::{SqliteConnectOptions, SqliteConnection, SqlitePool, SqliteQueryResult}, Sqlite, Pool, Transaction};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use anyhow::Result;
 use std::path::PathBuf;
+use std::sync::OnceLock;
 
 pub mod migrations;
 
@@ -18,6 +19,173 @@ pub struct Column {
     pub id: i64,
     pub name: String,
     pub order: i32,
+    pub role: Option<String>,
+}
+
+impl Column {
+    pub fn role(&self) -> Option<ColumnRole> {
+        self.role.as_deref().and_then(|r| r.parse().ok())
+    }
+}
+
+/// What a column means to commands like `start` and `done`, independent of its name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnRole {
+    Backlog,
+    Todo,
+    Doing,
+    Review,
+    Done,
+}
+
+impl std::str::FromStr for ColumnRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "backlog" => Ok(ColumnRole::Backlog),
+            "todo" => Ok(ColumnRole::Todo),
+            "doing" => Ok(ColumnRole::Doing),
+            "review" => Ok(ColumnRole::Review),
+            "done" => Ok(ColumnRole::Done),
+            other => Err(format!("'{}' is not a column role (backlog, todo, doing, review, done)", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for ColumnRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColumnRole::Backlog => write!(f, "backlog"),
+            ColumnRole::Todo => write!(f, "todo"),
+            ColumnRole::Doing => write!(f, "doing"),
+            ColumnRole::Review => write!(f, "review"),
+            ColumnRole::Done => write!(f, "done"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnLimit {
+    pub column_id: i64,
+    pub wip_limit: i64,
+}
+
+/// A column with its WIP limit (if any) and current task count
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnWithLimit {
+    pub column: Column,
+    pub wip_limit: Option<i64>,
+    pub task_count: i64,
+}
+
+impl ColumnWithLimit {
+    pub fn is_over_limit(&self) -> bool {
+        self.wip_limit.is_some_and(|limit| self.task_count > limit)
+    }
+
+    /// True when one more task would go over the limit
+    pub fn is_full(&self) -> bool {
+        self.wip_limit.is_some_and(|limit| self.task_count >= limit)
+    }
+}
+
+/// How many active tasks a column holds, from `count_tasks_by_column`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnCount {
+    pub column_id: i64,
+    pub count: i64,
+}
+
+/// One assignee's share of the open work, from `get_assignee_stats`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssigneeStats {
+    /// None for unassigned tasks
+    pub assignee: Option<String>,
+    /// Tasks outside the done-role column
+    pub open: i64,
+    /// Tasks in the doing-role column
+    pub in_progress: i64,
+}
+
+/// A task ID given by the user, checked on parse to be a valid database id
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TaskId(i64);
+
+impl TaskId {
+    pub fn get(self) -> i64 {
+        self.0
+    }
+}
+
+impl From<TaskId> for i64 {
+    fn from(id: TaskId) -> i64 {
+        id.0
+    }
+}
+
+impl std::str::FromStr for TaskId {
+    type Err = String;
+
+    /// Accepts `12`, `#12`, and `PROJ-12`; any prefix is taken, so IDs pasted
+    /// from before a `task_prefix` change still work
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let number = match trimmed.rsplit_once('-') {
+            Some((prefix, number)) if is_task_prefix(prefix) => number,
+            _ => trimmed.strip_prefix('#').unwrap_or(trimmed),
+        };
+        match number.parse::<i64>() {
+            Ok(id) if id >= 1 => Ok(TaskId(id)),
+            _ => Err(format!("'{}' is not a task ID (expected a number from 1 to {}, #<n>, or <PREFIX>-<n>)", s, i64::MAX)),
+        }
+    }
+}
+
+/// Letters, digits, and underscores, starting with a letter, like Jira project keys
+fn is_task_prefix(prefix: &str) -> bool {
+    prefix.starts_with(|c: char| c.is_ascii_alphabetic()) && prefix.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Fails on a `task_prefix` setting that IDs couldn't be parsed back from; empty means none
+pub fn check_task_prefix(prefix: &str) -> Result<()> {
+    let prefix = prefix.trim();
+    if !prefix.is_empty() && !is_task_prefix(prefix) {
+        anyhow::bail!("Invalid task_prefix '{}': use letters, digits, and underscores, starting with a letter (like PROJ)", prefix);
+    }
+    Ok(())
+}
+
+// Set once from the `task_prefix` config before the command runs
+static TASK_PREFIX: OnceLock<String> = OnceLock::new();
+
+/// Show task IDs as `<prefix>-<id>`; an empty prefix is ignored
+pub fn set_task_prefix(prefix: &str) -> Result<()> {
+    check_task_prefix(prefix)?;
+    let prefix = prefix.trim();
+    if !prefix.is_empty() {
+        let _ = TASK_PREFIX.set(prefix.to_string());
+    }
+    Ok(())
+}
+
+/// How a task ID is shown: `PROJ-12` under `task_prefix`, otherwise `#12`
+pub fn task_ref(id: impl Into<i64>) -> String {
+    format_task_ref(TASK_PREFIX.get().map(String::as_str), id.into())
+}
+
+fn format_task_ref(prefix: Option<&str>, id: i64) -> String {
+    match prefix {
+        Some(prefix) => format!("{}-{}", prefix, id),
+        None => format!("#{}", id),
+    }
+}
+
+impl std::fmt::Display for TaskId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +199,101 @@ pub struct Task {
     pub updated_at: DateTime<Utc>,
     pub branch_name: Option<String>,
     pub pr_url: Option<String>,
+    pub pr_number: Option<i64>,
+    pub worktree_path: Option<String>,
+    pub recurrence: Option<String>,
+    pub priority: Option<String>,
+    pub archived_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Bumped on every update; writes based on an older version are refused
+    pub version: i64,
+}
+
+/// A task changed between being read and being updated. Updates take the
+/// task as it was read and fail with this instead of overwriting the change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub task_id: i64,
+}
+
+impl std::fmt::Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Task {} was changed by someone else; reload it and try again", task_ref(self.task_id))
+    }
+}
+
+impl std::error::Error for Conflict {}
+
+/// Which tasks a view wants. Archived and deleted tasks are hidden unless
+/// asked for, so every view filters them the same way.
+#[derive(Debug, Clone, Default)]
+pub struct TaskQuery {
+    pub column_id: Option<i64>,
+    pub assignee: AssigneeFilter,
+    pub include_archived: bool,
+    pub include_deleted: bool,
+    pub limit: Option<i64>,
+    pub oldest_first: bool,
+    /// Only tasks last updated before this time
+    pub updated_before: Option<DateTime<Utc>>,
+}
+
+/// Whose tasks a view wants
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum AssigneeFilter {
+    #[default]
+    All,
+    Unassigned,
+    User(String),
+}
+
+impl AssigneeFilter {
+    pub fn matches(&self, assignee: Option<&str>) -> bool {
+        match self {
+            AssigneeFilter::All => true,
+            AssigneeFilter::Unassigned => assignee.is_none(),
+            AssigneeFilter::User(user) => assignee == Some(user.as_str()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl std::str::FromStr for Priority {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "low" => Ok(Priority::Low),
+            "medium" => Ok(Priority::Medium),
+            "high" => Ok(Priority::High),
+            "critical" => Ok(Priority::Critical),
+            other => Err(format!("'{}' is not a priority (low, medium, high, critical)", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Priority::Low => write!(f, "low"),
+            Priority::Medium => write!(f, "medium"),
+            Priority::High => write!(f, "high"),
+            Priority::Critical => write!(f, "critical"),
+        }
+    }
+}
+
+impl Task {
+    pub fn priority(&self) -> Option<Priority> {
+        self.priority.as_deref().and_then(|p| p.parse().ok())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,6 +303,116 @@ pub struct Comment {
     pub author: String,
     pub text: String,
     pub created_at: DateTime<Utc>,
+    /// Set with `pb comment resolve` once the thread needs no more attention
+    pub resolved: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reaction {
+    pub id: i64,
+    pub comment_id: i64,
+    pub author: String,
+    pub emoji: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Mention {
+    pub id: i64,
+    pub comment_id: i64,
+    pub username: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskLink {
+    pub id: i64,
+    pub task_id: i64,
+    pub label: String,
+    pub url: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Label {
+    pub id: i64,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A label name attached to a task, for loading the labels of many tasks at once
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskLabel {
+    pub task_id: i64,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskDependency {
+    pub task_id: i64,
+    pub blocked_by: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Time logged against a task with `pb log-time`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorklogEntry {
+    pub id: i64,
+    pub task_id: i64,
+    pub minutes: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A timer running on a task since `pb start`, with `track_time` on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Timer {
+    pub task_id: i64,
+    pub started_at: DateTime<Utc>,
+}
+
+/// Minutes logged on the tasks of one column and assignee, from `get_logged_time`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggedTime {
+    pub column_id: i64,
+    /// None for unassigned tasks
+    pub assignee: Option<String>,
+    pub minutes: i64,
+}
+
+/// Everything the detail views show for one task, from `get_task_with_details`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskDetail {
+    pub task: Task,
+    pub labels: Vec<Label>,
+    pub links: Vec<TaskLink>,
+    pub comments: Vec<Comment>,
+    pub reactions: Vec<Reaction>,
+    pub mentions: Vec<Mention>,
+    pub blocked_by: Vec<Task>,
+    /// Total minutes logged with `pb log-time`
+    pub logged_minutes: i64,
+}
+
+/// A task that is not done yet, with the IDs of tasks still blocking it
+#[derive(Debug, Clone)]
+pub struct TaskReadiness {
+    pub task: Task,
+    pub open_blockers: Vec<i64>,
+}
+
+impl TaskReadiness {
+    pub fn is_ready(&self) -> bool {
+        self.open_blockers.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Template {
+    pub id: i64,
+    pub name: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,240 +422,1219 @@ pub struct Idea {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ActivityLog {
-    pub id: i64,
-    pub event: String,
-    pub metadata: Option<String>,
-    pub created_at: DateTime<Utc>,
-}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityLog {
+    pub id: i64,
+    pub event: String,
+    pub metadata: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub task_id: Option<i64>,
+    pub column_from: Option<String>,
+    pub column_to: Option<String>,
+    pub column_to_id: Option<i64>,
+}
+
+/// Which of a task's comments to load, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct CommentFilter {
+    pub author: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// Which activity entries to load, newest first
+#[derive(Debug, Clone, Default)]
+pub struct ActivityFilter {
+    pub limit: Option<i64>,
+    pub since: Option<DateTime<Utc>>,
+    pub event: Option<String>,
+    pub task_id: Option<i64>,
+}
+
+/// A task brought in from another tool by `import_tasks`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedTask {
+    pub title: String,
+    pub description: Option<String>,
+    /// Name of the column to put it in; created if the board has none by that name
+    pub column: String,
+    /// Label names, created if the board has none by that name
+    pub labels: Vec<String>,
+    pub archived: bool,
+}
+
+/// What `import_tasks` added to the board
+#[derive(Debug, Clone, Default)]
+pub struct ImportSummary {
+    pub tasks: usize,
+    pub archived: usize,
+    pub columns_created: usize,
+    pub labels_created: usize,
+}
+
+const DEFAULT_COLUMNS: [(&str, i32, ColumnRole); 5] = [
+    ("Backlog", 0, ColumnRole::Backlog),
+    ("To Do", 1, ColumnRole::Todo),
+    ("Doing", 2, ColumnRole::Doing),
+    ("Review", 3, ColumnRole::Review),
+    ("Done", 4, ColumnRole::Done),
+];
+
+/// Statements with `RETURNING` run in a transaction. The SQLite worker hands the
+/// row back before it steps the statement to completion, so after a bare
+/// `fetch_one` the write may not yet be visible to other pooled connections;
+/// `commit` is queued behind the statement and waits for it.
+pub struct Database {
+    pool: Pool<Sqlite>,
+}
+
+impl Database {
+    pub async fn new(db_path: &PathBuf) -> Result<Self> {
+        // Options rather than a `sqlite:` URL, which would misread paths with `?` or `#`;
+        // callers check the file exists unless they mean to create it
+        let options = SqliteConnectOptions::new()
+            .filename(db_path)
+            .create_if_missing(true);
+        let pool = SqlitePool::connect_with(options).await?;
+        
+        Ok(Database { pool })
+    }
+
+    pub async fn migrate(&self) -> Result<()> {
+        sqlx::migrate!("./migrations").run(&self.pool).await?;
+        Ok(())
+    }
+
+    // Project operations
+    pub async fn create_project(&self, name: &str, repo_path: &str) -> Result<Project> {
+        let mut tx = self.pool.begin().await?;
+        let project = sqlx::query_as!(
+            Project,
+            "INSERT INTO projects (name, repo_path) VALUES (?, ?) RETURNING *",
+            name,
+            repo_path
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(project)
+    }
+
+    pub async fn get_project_by_path(&self, repo_path: &str) -> Result<Option<Project>> {
+        let project = sqlx::query_as!(
+            Project,
+            "SELECT * FROM projects WHERE repo_path = ?",
+            repo_path
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(project)
+    }
+
+    // Column operations
+    pub async fn create_default_columns(&self) -> Result<Vec<Column>> {
+        let defaults: Vec<_> = DEFAULT_COLUMNS.iter().map(|&(name, _, role)| (name, Some(role))).collect();
+        self.create_columns(&defaults).await
+    }
+
+    /// Create columns in the given order on an empty board
+    pub async fn create_columns(&self, specs: &[(&str, Option<ColumnRole>)]) -> Result<Vec<Column>> {
+        let mut tx = self.pool.begin().await?;
+        let mut columns = Vec::new();
+        for (order, &(name, role)) in specs.iter().enumerate() {
+            let order = order as i32;
+            let role = role.map(|r| r.to_string());
+            let column = sqlx::query_as!(
+                Column,
+                "INSERT INTO columns (name, \"order\", role) VALUES (?, ?, ?) RETURNING *",
+                name,
+                order,
+                role
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+            columns.push(column);
+        }
+        tx.commit().await?;
+
+        Ok(columns)
+    }
+
+    pub async fn get_columns(&self) -> Result<Vec<Column>> {
+        let columns = sqlx::query_as!(
+            Column,
+            "SELECT * FROM columns ORDER BY \"order\""
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(columns)
+    }
+
+    pub async fn get_column_by_name(&self, name: &str) -> Result<Option<Column>> {
+        let column = sqlx::query_as!(
+            Column,
+            "SELECT * FROM columns WHERE name = ?",
+            name
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(column)
+    }
+
+    pub async fn get_column_by_role(&self, role: ColumnRole) -> Result<Option<Column>> {
+        let role = role.to_string();
+        let column = sqlx::query_as!(
+            Column,
+            "SELECT * FROM columns WHERE role = ?",
+            role
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(column)
+    }
+
+    /// Give the column `role`, taking it away from whichever column had it
+    pub async fn set_column_role(&self, column_id: i64, role: ColumnRole) -> Result<()> {
+        let role = role.to_string();
+        let role = role.as_str();
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!("UPDATE columns SET role = NULL WHERE role = ?", role)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query!("UPDATE columns SET role = ? WHERE id = ?", role, column_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn rename_column(&self, column_id: i64, name: &str) -> Result<()> {
+        sqlx::query!("UPDATE columns SET name = ? WHERE id = ?", name, column_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn create_column(&self, name: &str) -> Result<Column> {
+        // New columns go to the right of the existing ones
+        let mut tx = self.pool.begin().await?;
+        let column = sqlx::query_as!(
+            Column,
+            "INSERT INTO columns (name, \"order\") 
+             VALUES (?, (SELECT COALESCE(MAX(\"order\") + 1, 0) FROM columns)) RETURNING *",
+            name
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(column)
+    }
+
+    pub async fn reorder_column(&self, column_id: i64, position: usize) -> Result<Vec<Column>> {
+        let mut columns = self.get_columns().await?;
+        let current = columns.iter()
+            .position(|c| c.id == column_id)
+            .ok_or_else(|| anyhow::anyhow!("Column #{} not found", column_id))?;
+        
+        let column = columns.remove(current);
+        let position = position.min(columns.len());
+        columns.insert(position, column);
+        
+        // Renumber every column so the order stays dense
+        let mut tx = self.pool.begin().await?;
+        for (order, column) in columns.iter_mut().enumerate() {
+            let order = order as i32;
+            sqlx::query!(
+                "UPDATE columns SET \"order\" = ? WHERE id = ?",
+                order,
+                column.id
+            )
+            .execute(&mut *tx)
+            .await?;
+            column.order = order;
+        }
+        tx.commit().await?;
+
+        Ok(columns)
+    }
+
+    pub async fn set_column_limit(&self, column_id: i64, wip_limit: Option<i64>) -> Result<()> {
+        match wip_limit {
+            Some(wip_limit) => {
+                sqlx::query!(
+                    "INSERT INTO column_limits (column_id, wip_limit) VALUES (?, ?)
+                     ON CONFLICT(column_id) DO UPDATE SET wip_limit = excluded.wip_limit",
+                    column_id,
+                    wip_limit
+                )
+                .execute(&self.pool)
+                .await?;
+            }
+            None => {
+                sqlx::query!("DELETE FROM column_limits WHERE column_id = ?", column_id)
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn get_columns_with_limits(&self) -> Result<Vec<ColumnWithLimit>> {
+        let limits = sqlx::query_as!(
+            ColumnLimit,
+            "SELECT * FROM column_limits"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut columns = Vec::new();
+        for column in self.get_columns().await? {
+            let wip_limit = limits.iter()
+                .find(|l| l.column_id == column.id)
+                .map(|l| l.wip_limit);
+            let task_count = self.count_tasks(column.id).await?;
+            columns.push(ColumnWithLimit { column, wip_limit, task_count });
+        }
+
+        Ok(columns)
+    }
+
+    /// Open and in-progress counts per assignee, unassigned last
+    pub async fn get_assignee_stats(&self) -> Result<Vec<AssigneeStats>> {
+        let stats = sqlx::query_as!(
+            AssigneeStats,
+            "SELECT t.assignee, 
+                    COUNT(*) AS \"open!: i64\", 
+                    SUM(c.role IS 'doing') AS \"in_progress!: i64\" 
+             FROM tasks t 
+             JOIN columns c ON c.id = t.column_id 
+             WHERE c.role IS NOT 'done' AND t.archived_at IS NULL AND t.deleted_at IS NULL 
+             GROUP BY t.assignee 
+             ORDER BY t.assignee IS NULL, t.assignee"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(stats)
+    }
+
+    // Task operations
+    pub async fn create_task(&self, title: &str, description: Option<String>, column_id: i64, assignee: Option<&str>) -> Result<Task> {
+        let mut tx = self.pool.begin().await?;
+        let task = insert_task(&mut tx, title, description.as_deref(), column_id, assignee, None).await?;
+        tx.commit().await?;
+
+        Ok(task)
+    }
+
+    /// Active tasks per column in one grouped query; empty columns are left out
+    pub async fn count_tasks_by_column(&self, assignee: &AssigneeFilter) -> Result<Vec<ColumnCount>> {
+        let (assignee, unassigned) = match assignee {
+            AssigneeFilter::All => (None, false),
+            AssigneeFilter::Unassigned => (None, true),
+            AssigneeFilter::User(user) => (Some(user.as_str()), false),
+        };
+        let counts = sqlx::query_as!(
+            ColumnCount,
+            "SELECT column_id, COUNT(*) AS \"count!: i64\" FROM tasks 
+             WHERE (?1 IS NULL OR assignee = ?1) 
+               AND (NOT ?2 OR assignee IS NULL) 
+               AND archived_at IS NULL AND deleted_at IS NULL 
+             GROUP BY column_id",
+            assignee,
+            unassigned
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(counts)
+    }
+
+    /// Create a task with `labels` attached in one transaction, creating any label
+    /// that doesn't exist yet. Returns the task and the labels that were created.
+    pub async fn create_task_with_labels(
+        &self,
+        title: &str,
+        description: Option<String>,
+        column_id: i64,
+        assignee: Option<&str>,
+        labels: &[String],
+    ) -> Result<(Task, Vec<Label>)> {
+        let mut tx = self.pool.begin().await?;
+        let task = insert_task(&mut tx, title, description.as_deref(), column_id, assignee, None).await?;
+
+        let mut created_labels = Vec::new();
+        for name in labels {
+            let (label, created) = find_or_create_label(&mut tx, name).await?;
+            sqlx::query!(
+                "INSERT OR IGNORE INTO task_labels (task_id, label_id) VALUES (?, ?)",
+                task.id,
+                label.id
+            )
+            .execute(&mut *tx)
+            .await?;
+            if created {
+                created_labels.push(label);
+            }
+        }
+        tx.commit().await?;
+
+        Ok((task, created_labels))
+    }
+
+    pub async fn get_task(&self, id: TaskId) -> Result<Option<Task>> {
+        let id = id.get();
+        let task = sqlx::query_as!(
+            Task,
+            "SELECT * FROM tasks WHERE id = ?",
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(task)
+    }
+
+    /// Re-read a task as it is now, such as after an update failed with `Conflict`
+    pub async fn refresh_task(&self, task: &mut Task) -> Result<()> {
+        *task = sqlx::query_as!(
+            Task,
+            "SELECT * FROM tasks WHERE id = ?",
+            task.id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The task and everything attached to it, or None if there is no such task
+    pub async fn get_task_with_details(&self, id: i64) -> Result<Option<TaskDetail>> {
+        let task = sqlx::query_as!(Task, "SELECT * FROM tasks WHERE id = ?", id)
+            .fetch_optional(&self.pool)
+            .await?;
+        let Some(task) = task else {
+            return Ok(None);
+        };
+
+        // Independent reads, so let the pool run them side by side
+        let (labels, links, comments, reactions, mentions, blocked_by, logged_minutes) = futures::try_join!(
+            self.get_task_labels(id),
+            self.get_task_links(id),
+            self.get_comments(id),
+            self.get_task_reactions(id),
+            self.get_task_mentions(id),
+            self.get_blocking_tasks(id),
+            self.get_logged_minutes(id),
+        )?;
+
+        Ok(Some(TaskDetail { task, labels, links, comments, reactions, mentions, blocked_by, logged_minutes }))
+    }
+
+    pub async fn get_tasks(&self, column_id: Option<i64>) -> Result<Vec<Task>> {
+        self.query_tasks(&TaskQuery { column_id, ..TaskQuery::default() }).await
+    }
+
+    pub async fn query_tasks(&self, query: &TaskQuery) -> Result<Vec<Task>> {
+        let (assignee, unassigned) = match &query.assignee {
+            AssigneeFilter::All => (None, false),
+            AssigneeFilter::Unassigned => (None, true),
+            AssigneeFilter::User(user) => (Some(user.as_str()), false),
+        };
+        // SQLite treats a negative LIMIT as "no limit"
+        let limit = query.limit.unwrap_or(-1);
+        let tasks = sqlx::query_as!(
+            Task,
+            "SELECT * FROM tasks 
+             WHERE (?1 IS NULL OR column_id = ?1) 
+               AND (?2 IS NULL OR assignee = ?2) 
+               AND (NOT ?8 OR assignee IS NULL) 
+               AND (?3 OR archived_at IS NULL) 
+               AND (?4 OR deleted_at IS NULL) 
+               AND (?7 IS NULL OR updated_at < ?7) 
+             ORDER BY column_id, CASE WHEN ?5 THEN created_at END ASC, created_at DESC 
+             LIMIT ?6",
+            query.column_id,
+            assignee,
+            query.include_archived,
+            query.include_deleted,
+            query.oldest_first,
+            limit,
+            query.updated_before,
+            unassigned
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tasks)
+    }
+
+    /// All visible tasks, read row by row instead of collected into memory
+    pub fn stream_tasks(&self, include_archived: bool, include_deleted: bool) -> impl futures::Stream<Item = Result<Task>> + '_ {
+        use futures::TryStreamExt;
+        sqlx::query_as!(
+            Task,
+            "SELECT * FROM tasks 
+             WHERE (?1 OR archived_at IS NULL) AND (?2 OR deleted_at IS NULL) 
+             ORDER BY column_id, created_at DESC",
+            include_archived,
+            include_deleted
+        )
+        .fetch(&self.pool)
+        .map_err(anyhow::Error::from)
+    }
+
+    pub async fn get_tasks_paged(&self, column_id: i64, limit: Option<i64>, oldest_first: bool) -> Result<Vec<Task>> {
+        self.query_tasks(&TaskQuery {
+            column_id: Some(column_id),
+            limit,
+            oldest_first,
+            ..TaskQuery::default()
+        })
+        .await
+    }
+
+    pub async fn count_tasks(&self, column_id: i64) -> Result<i64> {
+        let count = sqlx::query_scalar!(
+            "SELECT COUNT(*) as \"count: i64\" FROM tasks 
+             WHERE column_id = ? AND archived_at IS NULL AND deleted_at IS NULL",
+            column_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    pub async fn search_tasks(&self, query: &str) -> Result<Vec<Task>> {
+        // Substring match, with LIKE wildcards in the query taken literally
+        let pattern = format!(
+            "%{}%",
+            query.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+        );
+        let tasks = sqlx::query_as!(
+            Task,
+            "SELECT * FROM tasks 
+             WHERE (title LIKE ?1 ESCAPE '\\' OR description LIKE ?1 ESCAPE '\\') 
+               AND archived_at IS NULL AND deleted_at IS NULL 
+             ORDER BY id",
+            pattern
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tasks)
+    }
+
+    /// Mark the task as looked at without changing anything else
+    pub async fn touch_task(&self, task: &mut Task) -> Result<()> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            "UPDATE tasks SET updated_at = ?, version = version + 1 WHERE id = ? AND version = ?",
+            now,
+            task.id,
+            task.version
+        )
+        .execute(&self.pool)
+        .await?;
+
+        bump_version(task, result)
+    }
+
+    pub async fn archive_task(&self, task: &mut Task) -> Result<()> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            "UPDATE tasks SET archived_at = ?, updated_at = ?, version = version + 1 WHERE id = ? AND version = ?",
+            now,
+            now,
+            task.id,
+            task.version
+        )
+        .execute(&self.pool)
+        .await?;
+
+        bump_version(task, result)
+    }
+
+    /// Soft delete: the task is hidden everywhere but can be restored
+    pub async fn delete_task(&self, task: &mut Task) -> Result<()> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            "UPDATE tasks SET deleted_at = ?, updated_at = ?, version = version + 1 WHERE id = ? AND version = ?",
+            now,
+            now,
+            task.id,
+            task.version
+        )
+        .execute(&self.pool)
+        .await?;
+
+        bump_version(task, result)
+    }
+
+    /// Bring an archived or deleted task back onto the board
+    pub async fn restore_task(&self, task: &mut Task) -> Result<()> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            "UPDATE tasks SET archived_at = NULL, deleted_at = NULL, updated_at = ?, version = version + 1 WHERE id = ? AND version = ?",
+            now,
+            task.id,
+            task.version
+        )
+        .execute(&self.pool)
+        .await?;
+
+        bump_version(task, result)
+    }
+
+    pub async fn update_task_column(&self, task: &mut Task, column_id: i64) -> Result<()> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            "UPDATE tasks SET column_id = ?, updated_at = ?, version = version + 1 WHERE id = ? AND version = ?",
+            column_id,
+            now,
+            task.id,
+            task.version
+        )
+        .execute(&self.pool)
+        .await?;
+
+        bump_version(task, result)
+    }
+
+    pub async fn update_task_branch(&self, task: &mut Task, branch_name: Option<&str>) -> Result<()> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            "UPDATE tasks SET branch_name = ?, updated_at = ?, version = version + 1 WHERE id = ? AND version = ?",
+            branch_name,
+            now,
+            task.id,
+            task.version
+        )
+        .execute(&self.pool)
+        .await?;
+
+        bump_version(task, result)
+    }
+
+    pub async fn update_task_pr(&self, task: &mut Task, pr_url: &str, pr_number: Option<i64>) -> Result<()> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            "UPDATE tasks SET pr_url = ?, pr_number = ?, updated_at = ?, version = version + 1 WHERE id = ? AND version = ?",
+            pr_url,
+            pr_number,
+            now,
+            task.id,
+            task.version
+        )
+        .execute(&self.pool)
+        .await?;
+
+        bump_version(task, result)
+    }
+
+    pub async fn update_task_assignee(&self, task: &mut Task, assignee: Option<&str>) -> Result<()> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            "UPDATE tasks SET assignee = ?, updated_at = ?, version = version + 1 WHERE id = ? AND version = ?",
+            assignee,
+            now,
+            task.id,
+            task.version
+        )
+        .execute(&self.pool)
+        .await?;
+
+        bump_version(task, result)
+    }
+
+    pub async fn update_task_worktree(&self, task: &mut Task, worktree_path: Option<&str>) -> Result<()> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            "UPDATE tasks SET worktree_path = ?, updated_at = ?, version = version + 1 WHERE id = ? AND version = ?",
+            worktree_path,
+            now,
+            task.id,
+            task.version
+        )
+        .execute(&self.pool)
+        .await?;
+
+        bump_version(task, result)
+    }
+
+    pub async fn update_task_recurrence(&self, task: &mut Task, recurrence: Option<&str>) -> Result<()> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            "UPDATE tasks SET recurrence = ?, updated_at = ?, version = version + 1 WHERE id = ? AND version = ?",
+            recurrence,
+            now,
+            task.id,
+            task.version
+        )
+        .execute(&self.pool)
+        .await?;
+
+        bump_version(task, result)
+    }
+
+    pub async fn update_task_priority(&self, task: &mut Task, priority: Option<Priority>) -> Result<()> {
+        let now = Utc::now();
+        let priority = priority.map(|p| p.to_string());
+        let result = sqlx::query!(
+            "UPDATE tasks SET priority = ?, updated_at = ?, version = version + 1 WHERE id = ? AND version = ?",
+            priority,
+            now,
+            task.id,
+            task.version
+        )
+        .execute(&self.pool)
+        .await?;
+
+        bump_version(task, result)
+    }
+
+    // Comment operations
+    pub async fn create_comment(&self, task_id: i64, author: &str, text: &str) -> Result<Comment> {
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
+        let comment = sqlx::query_as!(
+            Comment,
+            "INSERT INTO comments (task_id, author, text, created_at) VALUES (?, ?, ?, ?) RETURNING *",
+            task_id,
+            author,
+            text,
+            now
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(comment)
+    }
+
+    pub async fn get_all_comments(&self) -> Result<Vec<Comment>> {
+        let comments = sqlx::query_as!(
+            Comment,
+            "SELECT * FROM comments ORDER BY task_id, created_at"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(comments)
+    }
+
+    pub async fn get_comments(&self, task_id: i64) -> Result<Vec<Comment>> {
+        let comments = sqlx::query_as!(
+            Comment,
+            "SELECT * FROM comments WHERE task_id = ? ORDER BY created_at",
+            task_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(comments)
+    }
+
+    pub async fn get_comments_filtered(&self, task_id: i64, filter: &CommentFilter) -> Result<Vec<Comment>> {
+        let author = filter.author.as_deref();
+        let comments = sqlx::query_as!(
+            Comment,
+            "SELECT * FROM comments 
+             WHERE task_id = ?1 
+               AND (?2 IS NULL OR author = ?2) 
+               AND (?3 IS NULL OR created_at >= ?3) 
+             ORDER BY created_at",
+            task_id,
+            author,
+            filter.since
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(comments)
+    }
+
+    pub async fn get_comment(&self, id: i64) -> Result<Option<Comment>> {
+        let comment = sqlx::query_as!(
+            Comment,
+            "SELECT * FROM comments WHERE id = ?",
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(comment)
+    }
+
+    /// Returns false if the comment was already in that state
+    pub async fn set_comment_resolved(&self, id: i64, resolved: bool) -> Result<bool> {
+        let result = sqlx::query!(
+            "UPDATE comments SET resolved = ?1 WHERE id = ?2 AND resolved != ?1",
+            resolved,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Returns false if the author already reacted to the comment with this emoji
+    pub async fn add_reaction(&self, comment_id: i64, author: &str, emoji: &str) -> Result<bool> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            "INSERT OR IGNORE INTO reactions (comment_id, author, emoji, created_at) VALUES (?, ?, ?, ?)",
+            comment_id,
+            author,
+            emoji,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn add_mentions(&self, comment_id: i64, usernames: &[String]) -> Result<()> {
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
+        for username in usernames {
+            sqlx::query!(
+                "INSERT OR IGNORE INTO mentions (comment_id, username, created_at) VALUES (?, ?, ?)",
+                comment_id,
+                username,
+                now
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn get_task_mentions(&self, task_id: i64) -> Result<Vec<Mention>> {
+        let mentions = sqlx::query_as!(
+            Mention,
+            "SELECT mentions.* FROM mentions 
+             JOIN comments ON comments.id = mentions.comment_id 
+             WHERE comments.task_id = ? 
+             ORDER BY mentions.id",
+            task_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(mentions)
+    }
+
+    pub async fn get_task_reactions(&self, task_id: i64) -> Result<Vec<Reaction>> {
+        let reactions = sqlx::query_as!(
+            Reaction,
+            "SELECT reactions.* FROM reactions 
+             JOIN comments ON comments.id = reactions.comment_id 
+             WHERE comments.task_id = ? 
+             ORDER BY reactions.id",
+            task_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(reactions)
+    }
+
+    // Link operations
+    pub async fn create_task_link(&self, task_id: i64, label: &str, url: &str) -> Result<TaskLink> {
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
+        let link = sqlx::query_as!(
+            TaskLink,
+            "INSERT INTO task_links (task_id, label, url, created_at) VALUES (?, ?, ?, ?) RETURNING *",
+            task_id,
+            label,
+            url,
+            now
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(link)
+    }
+
+    pub async fn get_task_links(&self, task_id: i64) -> Result<Vec<TaskLink>> {
+        let links = sqlx::query_as!(
+            TaskLink,
+            "SELECT * FROM task_links WHERE task_id = ? ORDER BY created_at",
+            task_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
 
-pub struct Database {
-    pool: Pool<Sqlite>,
-}
+        Ok(links)
+    }
 
-impl Database {
-    pub async fn new(db_path: &PathBuf) -> Result<Self> {
-        let db_url = format!("sqlite:{}", db_path.display());
-        let pool = SqlitePool::connect(&db_url).await?;
-        
-        Ok(Database { pool })
+    pub async fn get_all_links(&self) -> Result<Vec<TaskLink>> {
+        let links = sqlx::query_as!(
+            TaskLink,
+            "SELECT * FROM task_links ORDER BY task_id, created_at"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(links)
     }
 
-    pub async fn migrate(&self) -> Result<()> {
-        sqlx::migrate!("./migrations").run(&self.pool).await?;
+    /// Replace tasks' PR URLs and links' URLs, all or nothing
+    pub async fn rewrite_urls(&self, pr_urls: &[(i64, String)], link_urls: &[(i64, String)]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for (task_id, url) in pr_urls {
+            sqlx::query!("UPDATE tasks SET pr_url = ?, version = version + 1 WHERE id = ?", url, task_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        for (link_id, url) in link_urls {
+            sqlx::query!("UPDATE task_links SET url = ? WHERE id = ?", url, link_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
         Ok(())
     }
 
-    // Project operations
-    pub async fn create_project(&self, name: &str, repo_path: &str) -> Result<Project> {
-        let project = sqlx::query_as!(
-            Project,
-            "INSERT INTO projects (name, repo_path) VALUES (?, ?) RETURNING *",
+    // Label operations
+    pub async fn create_label(&self, name: &str) -> Result<Label> {
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
+        let label = sqlx::query_as!(
+            Label,
+            "INSERT INTO labels (name, created_at) VALUES (?, ?) RETURNING *",
             name,
-            repo_path
+            now
         )
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *tx)
         .await?;
+        tx.commit().await?;
 
-        Ok(project)
+        Ok(label)
     }
 
-    pub async fn get_project_by_path(&self, repo_path: &str) -> Result<Option<Project>> {
-        let project = sqlx::query_as!(
-            Project,
-            "SELECT * FROM projects WHERE repo_path = ?",
-            repo_path
+    pub async fn get_label_by_name(&self, name: &str) -> Result<Option<Label>> {
+        let label = sqlx::query_as!(Label, "SELECT * FROM labels WHERE name = ?", name)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(label)
+    }
+
+    pub async fn get_labels(&self) -> Result<Vec<Label>> {
+        let labels = sqlx::query_as!(Label, "SELECT * FROM labels ORDER BY name")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(labels)
+    }
+
+    /// Returns false if the task already had the label
+    pub async fn add_task_label(&self, task_id: i64, label_id: i64) -> Result<bool> {
+        let result = sqlx::query!(
+            "INSERT OR IGNORE INTO task_labels (task_id, label_id) VALUES (?, ?)",
+            task_id,
+            label_id
         )
-        .fetch_optional(&self.pool)
+        .execute(&self.pool)
         .await?;
 
-        Ok(project)
+        Ok(result.rows_affected() > 0)
     }
 
-    // Column operations
-    pub async fn create_default_columns(&self) -> Result<Vec<Column>> {
-        let default_columns = vec![
-            ("Backlog", 0),
-            ("To Do", 1),
-            ("Doing", 2),
-            ("Review", 3),
-            ("Done", 4),
-        ];
-
-        let mut columns = Vec::new();
-        for (name, order) in default_columns {
-            let column = sqlx::query_as!(
-                Column,
-                "INSERT INTO columns (name, \"order\") VALUES (?, ?) RETURNING *",
-                name,
-                order
-            )
-            .fetch_one(&self.pool)
-            .await?;
-            columns.push(column);
-        }
+    /// Returns false if the task didn't have the label
+    pub async fn remove_task_label(&self, task_id: i64, label_id: i64) -> Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM task_labels WHERE task_id = ? AND label_id = ?",
+            task_id,
+            label_id
+        )
+        .execute(&self.pool)
+        .await?;
 
-        Ok(columns)
+        Ok(result.rows_affected() > 0)
     }
 
-    pub async fn get_columns(&self) -> Result<Vec<Column>> {
-        let columns = sqlx::query_as!(
-            Column,
-            "SELECT * FROM columns ORDER BY \"order\""
+    pub async fn get_task_labels(&self, task_id: i64) -> Result<Vec<Label>> {
+        let labels = sqlx::query_as!(
+            Label,
+            "SELECT labels.* FROM labels 
+             JOIN task_labels tl ON tl.label_id = labels.id 
+             WHERE tl.task_id = ? 
+             ORDER BY labels.name",
+            task_id
         )
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(columns)
+        Ok(labels)
     }
 
-    pub async fn get_column_by_name(&self, name: &str) -> Result<Option<Column>> {
-        let column = sqlx::query_as!(
-            Column,
-            "SELECT * FROM columns WHERE name = ?",
-            name
+    pub async fn get_all_task_labels(&self) -> Result<Vec<TaskLabel>> {
+        let labels = sqlx::query_as!(
+            TaskLabel,
+            "SELECT tl.task_id, l.name FROM task_labels tl 
+             JOIN labels l ON l.id = tl.label_id 
+             ORDER BY tl.task_id, l.name"
         )
-        .fetch_optional(&self.pool)
+        .fetch_all(&self.pool)
         .await?;
 
-        Ok(column)
+        Ok(labels)
     }
 
-    // Task operations
-    pub async fn create_task(&self, title: &str, description: Option<String>, column_id: i64) -> Result<Task> {
+    // Dependency operations
+    /// Returns false if the dependency already existed
+    pub async fn add_dependency(&self, task_id: i64, blocked_by: i64) -> Result<bool> {
         let now = Utc::now();
-        let task = sqlx::query_as!(
-            Task,
-            "INSERT INTO tasks (title, description, column_id, created_at, updated_at) 
-             VALUES (?, ?, ?, ?, ?) RETURNING *",
-            title,
-            description,
-            column_id,
-            now,
+        let result = sqlx::query!(
+            "INSERT OR IGNORE INTO task_dependencies (task_id, blocked_by, created_at) VALUES (?, ?, ?)",
+            task_id,
+            blocked_by,
             now
         )
-        .fetch_one(&self.pool)
+        .execute(&self.pool)
         .await?;
 
-        Ok(task)
+        Ok(result.rows_affected() > 0)
     }
 
-    pub async fn get_task(&self, id: i64) -> Result<Option<Task>> {
-        let task = sqlx::query_as!(
+    /// Returns false if there was no such dependency
+    pub async fn remove_dependency(&self, task_id: i64, blocked_by: i64) -> Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM task_dependencies WHERE task_id = ? AND blocked_by = ?",
+            task_id,
+            blocked_by
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn get_dependencies(&self) -> Result<Vec<TaskDependency>> {
+        let dependencies = sqlx::query_as!(
+            TaskDependency,
+            "SELECT * FROM task_dependencies ORDER BY task_id, blocked_by"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(dependencies)
+    }
+
+    pub async fn get_blocking_tasks(&self, task_id: i64) -> Result<Vec<Task>> {
+        let tasks = sqlx::query_as!(
             Task,
-            "SELECT * FROM tasks WHERE id = ?",
-            id
+            "SELECT tasks.* FROM tasks 
+             JOIN task_dependencies d ON d.blocked_by = tasks.id 
+             WHERE d.task_id = ? 
+             ORDER BY tasks.id",
+            task_id
         )
-        .fetch_optional(&self.pool)
+        .fetch_all(&self.pool)
         .await?;
 
-        Ok(task)
+        Ok(tasks)
     }
 
-    pub async fn get_tasks(&self, column_id: Option<i64>) -> Result<Vec<Task>> {
-        let tasks = if let Some(column_id) = column_id {
-            sqlx::query_as!(
-                Task,
-                "SELECT * FROM tasks WHERE column_id = ? ORDER BY created_at DESC",
-                column_id
-            )
-            .fetch_all(&self.pool)
-            .await?
-        } else {
-            sqlx::query_as!(
-                Task,
-                "SELECT * FROM tasks ORDER BY column_id, created_at DESC"
-            )
-            .fetch_all(&self.pool)
-            .await?
-        };
+    /// Tasks that entered the column at or after `since` and are still there
+    /// (archived ones included), in the order they arrived. The activity log
+    /// records column names, so moves made before a rename are not matched.
+    pub async fn get_tasks_moved_to(&self, column: &Column, since: DateTime<Utc>) -> Result<Vec<Task>> {
+        let name = column.name.as_str();
+        let tasks = sqlx::query_as!(
+            Task,
+            "SELECT tasks.* FROM tasks 
+             JOIN (SELECT task_id, MAX(created_at) AS moved_at FROM activity_log 
+                   WHERE column_to = ?1 AND created_at >= ?2 
+                   GROUP BY task_id) m ON m.task_id = tasks.id 
+             WHERE tasks.column_id = ?3 AND tasks.deleted_at IS NULL 
+             ORDER BY m.moved_at, tasks.id",
+            name,
+            since,
+            column.id
+        )
+        .fetch_all(&self.pool)
+        .await?;
 
         Ok(tasks)
     }
 
-    pub async fn update_task_column(&self, id: i64, column_id: i64) -> Result<()> {
-        let now = Utc::now();
-        sqlx::query!(
-            "UPDATE tasks SET column_id = ?, updated_at = ? WHERE id = ?",
-            column_id,
-            now,
-            id
+    /// Every task outside Done, with the blockers that are not Done yet
+    pub async fn compute_readiness(&self) -> Result<Vec<TaskReadiness>> {
+        let open = sqlx::query_as!(
+            TaskDependency,
+            "SELECT d.* FROM task_dependencies d 
+             JOIN tasks blocker ON blocker.id = d.blocked_by 
+             JOIN columns c ON c.id = blocker.column_id 
+             WHERE c.role IS NOT 'done' 
+             ORDER BY d.blocked_by"
         )
-        .execute(&self.pool)
+        .fetch_all(&self.pool)
         .await?;
+        let done_column_id = self.get_column_by_role(ColumnRole::Done).await?.map(|c| c.id);
 
-        Ok(())
+        let readiness = self.get_tasks(None).await?
+            .into_iter()
+            .filter(|task| Some(task.column_id) != done_column_id)
+            .map(|task| {
+                let open_blockers = open.iter()
+                    .filter(|d| d.task_id == task.id)
+                    .map(|d| d.blocked_by)
+                    .collect();
+                TaskReadiness { task, open_blockers }
+            })
+            .collect();
+
+        Ok(readiness)
     }
 
-    pub async fn update_task_branch(&self, id: i64, branch_name: &str) -> Result<()> {
+    // Worklog operations
+    pub async fn log_time(&self, task_id: i64, minutes: i64) -> Result<WorklogEntry> {
         let now = Utc::now();
-        sqlx::query!(
-            "UPDATE tasks SET branch_name = ?, updated_at = ? WHERE id = ?",
-            branch_name,
-            now,
-            id
+        let mut tx = self.pool.begin().await?;
+        let entry = sqlx::query_as!(
+            WorklogEntry,
+            "INSERT INTO worklog (task_id, minutes, created_at) VALUES (?, ?, ?) RETURNING *",
+            task_id,
+            minutes,
+            now
         )
-        .execute(&self.pool)
+        .fetch_one(&mut *tx)
         .await?;
+        tx.commit().await?;
 
-        Ok(())
+        Ok(entry)
     }
 
-    pub async fn update_task_pr(&self, id: i64, pr_url: &str) -> Result<()> {
-        let now = Utc::now();
-        sqlx::query!(
-            "UPDATE tasks SET pr_url = ?, updated_at = ? WHERE id = ?",
-            pr_url,
-            now,
-            id
+    pub async fn get_logged_minutes(&self, task_id: i64) -> Result<i64> {
+        let minutes = sqlx::query_scalar!(
+            "SELECT COALESCE(SUM(minutes), 0) AS \"minutes!: i64\" FROM worklog WHERE task_id = ?",
+            task_id
         )
-        .execute(&self.pool)
+        .fetch_one(&self.pool)
         .await?;
 
-        Ok(())
+        Ok(minutes)
     }
 
-    // Comment operations
-    pub async fn create_comment(&self, task_id: i64, author: &str, text: &str) -> Result<Comment> {
+    /// Logged minutes per column and assignee, leaving out deleted tasks
+    pub async fn get_logged_time(&self) -> Result<Vec<LoggedTime>> {
+        let logged = sqlx::query_as!(
+            LoggedTime,
+            "SELECT t.column_id, t.assignee, SUM(w.minutes) AS \"minutes!: i64\" 
+             FROM worklog w 
+             JOIN tasks t ON t.id = w.task_id 
+             WHERE t.deleted_at IS NULL 
+             GROUP BY t.column_id, t.assignee 
+             ORDER BY t.column_id, t.assignee IS NULL, t.assignee"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(logged)
+    }
+
+    /// Returns false if the task's timer was already running
+    pub async fn start_timer(&self, task_id: i64) -> Result<bool> {
         let now = Utc::now();
-        let comment = sqlx::query_as!(
-            Comment,
-            "INSERT INTO comments (task_id, author, text, created_at) VALUES (?, ?, ?, ?) RETURNING *",
+        let result = sqlx::query!(
+            "INSERT OR IGNORE INTO timers (task_id, started_at) VALUES (?, ?)",
             task_id,
-            author,
-            text,
             now
         )
-        .fetch_one(&self.pool)
+        .execute(&self.pool)
         .await?;
 
-        Ok(comment)
+        Ok(result.rows_affected() > 0)
     }
 
-    pub async fn get_comments(&self, task_id: i64) -> Result<Vec<Comment>> {
-        let comments = sqlx::query_as!(
-            Comment,
-            "SELECT * FROM comments WHERE task_id = ? ORDER BY created_at",
+    pub async fn get_timers(&self) -> Result<Vec<Timer>> {
+        let timers = sqlx::query_as!(Timer, "SELECT * FROM timers ORDER BY started_at")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(timers)
+    }
+
+    /// Removes the task's timer, returning it if one was running
+    pub async fn stop_timer(&self, task_id: i64) -> Result<Option<Timer>> {
+        let mut tx = self.pool.begin().await?;
+        let timer = sqlx::query_as!(
+            Timer,
+            "DELETE FROM timers WHERE task_id = ? RETURNING *",
             task_id
         )
-        .fetch_all(&self.pool)
+        .fetch_optional(&mut *tx)
         .await?;
+        tx.commit().await?;
 
-        Ok(comments)
+        Ok(timer)
+    }
+
+    // Template operations
+    pub async fn save_template(&self, name: &str, title: &str, description: Option<String>) -> Result<Template> {
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
+        let template = sqlx::query_as!(
+            Template,
+            "INSERT INTO templates (name, title, description, created_at) VALUES (?, ?, ?, ?)
+             ON CONFLICT(name) DO UPDATE SET title = excluded.title, description = excluded.description
+             RETURNING *",
+            name,
+            title,
+            description,
+            now
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(template)
+    }
+
+    pub async fn get_template(&self, name: &str) -> Result<Option<Template>> {
+        let template = sqlx::query_as!(
+            Template,
+            "SELECT * FROM templates WHERE name = ?",
+            name
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(template)
     }
 
     // Idea operations
     pub async fn create_idea(&self, content: &str) -> Result<Idea> {
         let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
         let idea = sqlx::query_as!(
             Idea,
             "INSERT INTO ideas (content, created_at) VALUES (?, ?) RETURNING *",
             content,
             now
         )
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *tx)
         .await?;
+        tx.commit().await?;
 
         Ok(idea)
     }
@@ -310,6 +1662,18 @@ impl Database {
         Ok(ideas)
     }
 
+    pub async fn update_idea(&self, id: i64, content: &str) -> Result<()> {
+        sqlx::query!(
+            "UPDATE ideas SET content = ? WHERE id = ?",
+            content,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn delete_idea(&self, id: i64) -> Result<()> {
         sqlx::query!("DELETE FROM ideas WHERE id = ?", id)
             .execute(&self.pool)
@@ -318,14 +1682,166 @@ impl Database {
         Ok(())
     }
 
+    /// Turn an idea into a task: the task is created and the idea deleted together
+    pub async fn promote_idea(&self, idea_id: i64, title: &str, description: Option<String>, column_id: i64) -> Result<Task> {
+        let mut tx = self.pool.begin().await?;
+        let task = insert_task(&mut tx, title, description.as_deref(), column_id, None, None).await?;
+        sqlx::query!("DELETE FROM ideas WHERE id = ?", idea_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(task)
+    }
+
+    /// Add `tasks` in one transaction, so a failed import leaves the board as it was.
+    /// `columns` are matched to existing columns by name, and the missing ones are
+    /// created to the right in the order given.
+    pub async fn import_tasks(&self, columns: &[String], tasks: &[ImportedTask]) -> Result<ImportSummary> {
+        let mut tx = self.pool.begin().await?;
+        let now = Utc::now();
+        let mut summary = ImportSummary::default();
+
+        let mut column_ids = std::collections::HashMap::new();
+        for name in columns {
+            let existing = sqlx::query_as!(Column, "SELECT * FROM columns WHERE name = ?", name)
+                .fetch_optional(&mut *tx)
+                .await?;
+            let column = match existing {
+                Some(column) => column,
+                None => {
+                    summary.columns_created += 1;
+                    sqlx::query_as!(
+                        Column,
+                        "INSERT INTO columns (name, \"order\") 
+                         VALUES (?, (SELECT COALESCE(MAX(\"order\") + 1, 0) FROM columns)) RETURNING *",
+                        name
+                    )
+                    .fetch_one(&mut *tx)
+                    .await?
+                }
+            };
+            column_ids.insert(name.as_str(), column.id);
+        }
+
+        let mut label_ids = std::collections::HashMap::new();
+        for task in tasks {
+            let column_id = *column_ids.get(task.column.as_str())
+                .ok_or_else(|| anyhow::anyhow!("Column '{}' is not part of the import", task.column))?;
+            let archived_at = task.archived.then_some(now);
+            let created = insert_task(&mut tx, &task.title, task.description.as_deref(), column_id, None, archived_at).await?;
+            summary.tasks += 1;
+            if task.archived {
+                summary.archived += 1;
+            }
+
+            for name in &task.labels {
+                let label_id = match label_ids.get(name.as_str()) {
+                    Some(&id) => id,
+                    None => {
+                        let (label, created) = find_or_create_label(&mut tx, name).await?;
+                        if created {
+                            summary.labels_created += 1;
+                        }
+                        label_ids.insert(name.as_str(), label.id);
+                        label.id
+                    }
+                };
+                sqlx::query!(
+                    "INSERT OR IGNORE INTO task_labels (task_id, label_id) VALUES (?, ?)",
+                    created.id,
+                    label_id
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+        tx.commit().await?;
+
+        Ok(summary)
+    }
+
+    // Board maintenance
+    pub async fn reset_board(&self, hard: bool) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!("DELETE FROM task_labels").execute(&mut *tx).await?;
+        sqlx::query!("DELETE FROM task_dependencies").execute(&mut *tx).await?;
+        sqlx::query!("DELETE FROM task_links").execute(&mut *tx).await?;
+        sqlx::query!("DELETE FROM mentions").execute(&mut *tx).await?;
+        sqlx::query!("DELETE FROM reactions").execute(&mut *tx).await?;
+        sqlx::query!("DELETE FROM comments").execute(&mut *tx).await?;
+        sqlx::query!("DELETE FROM tasks").execute(&mut *tx).await?;
+        sqlx::query!("DELETE FROM ideas").execute(&mut *tx).await?;
+        sqlx::query!("DELETE FROM activity_log").execute(&mut *tx).await?;
+
+        if hard {
+            sqlx::query!("DELETE FROM labels").execute(&mut *tx).await?;
+            sqlx::query!("DELETE FROM column_limits").execute(&mut *tx).await?;
+            sqlx::query!("DELETE FROM columns").execute(&mut *tx).await?;
+            for (name, order, role) in DEFAULT_COLUMNS {
+                let role = role.to_string();
+                sqlx::query!(
+                    "INSERT INTO columns (name, \"order\", role) VALUES (?, ?, ?)",
+                    name,
+                    order,
+                    role
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        // Start IDs from 1 again for the wiped tables
+        sqlx::query!(
+            "DELETE FROM sqlite_sequence 
+             WHERE name IN ('task_links', 'mentions', 'reactions', 'comments', 'tasks', 'ideas', 'activity_log')"
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
     // Activity log operations
     pub async fn log_activity(&self, event: &str, metadata: Option<String>) -> Result<()> {
+        self.insert_activity(event, None, None, metadata).await
+    }
+
+    /// Log an event about one task; `columns` is where it moved from and to, when it moved
+    pub async fn log_task_activity(
+        &self,
+        event: &str,
+        task_id: i64,
+        columns: Option<(&str, &Column)>,
+        metadata: Option<String>,
+    ) -> Result<()> {
+        self.insert_activity(event, Some(task_id), columns, metadata).await
+    }
+
+    async fn insert_activity(
+        &self,
+        event: &str,
+        task_id: Option<i64>,
+        columns: Option<(&str, &Column)>,
+        metadata: Option<String>,
+    ) -> Result<()> {
         let now = Utc::now();
+        let (column_from, column_to) = columns.unzip();
+        let column_to_id = column_to.map(|column| column.id);
+        let column_to = column_to.map(|column| column.name.as_str());
         sqlx::query!(
-            "INSERT INTO activity_log (event, metadata, created_at) VALUES (?, ?, ?)",
+            "INSERT INTO activity_log (event, metadata, created_at, task_id, column_from, column_to, column_to_id) 
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
             event,
             metadata,
-            now
+            now,
+            task_id,
+            column_from,
+            column_to,
+            column_to_id
         )
         .execute(&self.pool)
         .await?;
@@ -333,11 +1849,22 @@ impl Database {
         Ok(())
     }
 
-    pub async fn get_activity_log(&self, limit: Option<i64>) -> Result<Vec<ActivityLog>> {
-        let limit = limit.unwrap_or(50);
+    pub async fn query_activity(&self, filter: &ActivityFilter) -> Result<Vec<ActivityLog>> {
+        let limit = filter.limit.unwrap_or(50);
+        let event = filter.event.as_deref();
         let logs = sqlx::query_as!(
             ActivityLog,
-            "SELECT * FROM activity_log ORDER BY created_at DESC LIMIT ?",
+            "SELECT * FROM activity_log 
+             WHERE (? IS NULL OR created_at >= ?) AND (? IS NULL OR event = ?) 
+               AND (? IS NULL OR task_id = ?) 
+             ORDER BY created_at DESC, id DESC 
+             LIMIT ?",
+            filter.since,
+            filter.since,
+            event,
+            event,
+            filter.task_id,
+            filter.task_id,
             limit
         )
         .fetch_all(&self.pool)
@@ -346,3 +1873,62 @@ impl Database {
         Ok(logs)
     }
 }
+
+/// Fails with `Conflict` when the update matched no row at the task's version,
+/// and otherwise moves the task on to the version it now has
+fn bump_version(task: &mut Task, result: SqliteQueryResult) -> Result<()> {
+    if result.rows_affected() == 0 {
+        return Err(Conflict { task_id: task.id }.into());
+    }
+    task.version += 1;
+    Ok(())
+}
+
+/// Insert a task row; every way of creating a task goes through here
+async fn insert_task(
+    conn: &mut SqliteConnection,
+    title: &str,
+    description: Option<&str>,
+    column_id: i64,
+    assignee: Option<&str>,
+    archived_at: Option<DateTime<Utc>>,
+) -> Result<Task> {
+    let now = Utc::now();
+    let task = sqlx::query_as!(
+        Task,
+        "INSERT INTO tasks (title, description, column_id, assignee, created_at, updated_at, archived_at) 
+         VALUES (?, ?, ?, ?, ?, ?, ?) RETURNING *",
+        title,
+        description,
+        column_id,
+        assignee,
+        now,
+        now,
+        archived_at
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(task)
+}
+
+/// The label called `name`, created if there is none; true when it was created
+async fn find_or_create_label(tx: &mut Transaction<'_, Sqlite>, name: &str) -> Result<(Label, bool)> {
+    let existing = sqlx::query_as!(Label, "SELECT * FROM labels WHERE name = ?", name)
+        .fetch_optional(&mut **tx)
+        .await?;
+    if let Some(label) = existing {
+        return Ok((label, false));
+    }
+    let now = Utc::now();
+    let label = sqlx::query_as!(
+        Label,
+        "INSERT INTO labels (name, created_at) VALUES (?, ?) RETURNING *",
+        name,
+        now
+    )
+    .fetch_one(&mut **tx)
+    .await?;
+
+    Ok((label, true))
+}
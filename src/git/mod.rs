@@ -1,11 +1,21 @@
-use git2::{Repository, Branch, BranchType, ObjectType, Signature, StatusOptions};
+use git2::{Repository, Branch, BranchType, DiffFormat, ObjectType, Signature, StatusOptions, WorktreeAddOptions, WorktreePruneOptions};
 use anyhow::{Result, Context, bail};
-use std::path::Path;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
 pub struct GitRepo {
     repo: Repository,
 }
 
+#[derive(Debug, Clone)]
+pub struct DiffSummary {
+    pub files: Vec<String>,
+    pub insertions: usize,
+    pub deletions: usize,
+    pub patch: Option<String>,
+}
+
 impl GitRepo {
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
         let repo = Repository::discover(path)
@@ -14,10 +24,15 @@ impl GitRepo {
         Ok(GitRepo { repo })
     }
 
-    pub fn create_branch(&self, branch_name: &str) -> Result<()> {
-        // Get the current HEAD commit
-        let head = self.repo.head()?;
-        let target_commit = head.peel_to_commit()?;
+    /// Create a branch at `base` (anything `git rev-parse` takes: a branch, tag,
+    /// or commit), or at HEAD without one
+    pub fn create_branch(&self, branch_name: &str, base: Option<&str>) -> Result<()> {
+        let target_commit = match base {
+            Some(base) => self.repo.revparse_single(base)
+                .and_then(|object| object.peel_to_commit())
+                .context(format!("'{}' is not a branch, tag, or commit in this repository", base))?,
+            None => self.repo.head()?.peel_to_commit()?,
+        };
         
         // Create the new branch
         self.repo.branch(branch_name, &target_commit, false)
@@ -44,6 +59,71 @@ impl GitRepo {
         Ok(())
     }
 
+    pub fn rename_branch(&self, old_name: &str, new_name: &str) -> Result<()> {
+        let mut branch = self.repo.find_branch(old_name, BranchType::Local)
+            .context(format!("Branch '{}' not found", old_name))?;
+        
+        // HEAD follows the rename if the branch is checked out
+        branch.rename(new_name, false)
+            .context(format!("Failed to rename branch '{}' to '{}'", old_name, new_name))?;
+        
+        Ok(())
+    }
+
+    pub fn delete_branch(&self, branch_name: &str) -> Result<()> {
+        let mut branch = self.repo.find_branch(branch_name, BranchType::Local)
+            .context(format!("Branch '{}' not found", branch_name))?;
+        
+        // Unmerged work is deleted too, like `git branch -D`
+        branch.delete()
+            .context(format!("Failed to delete branch '{}'", branch_name))?;
+        
+        Ok(())
+    }
+
+    pub fn create_worktree(&self, branch_name: &str) -> Result<PathBuf> {
+        // Worktrees live in a sibling directory named after the branch
+        let workdir = self.repo.workdir()
+            .ok_or_else(|| anyhow::anyhow!("Cannot create a worktree from a bare repository"))?;
+        let parent = workdir.parent()
+            .ok_or_else(|| anyhow::anyhow!("Repository has no parent directory for worktrees"))?;
+        let worktree_name = branch_name.replace('/', "-");
+        let worktree_path = parent.join(&worktree_name);
+
+        if worktree_path.exists() {
+            bail!("Worktree path '{}' already exists", worktree_path.display());
+        }
+
+        // Check the existing branch out in the new worktree
+        let branch = self.repo.find_branch(branch_name, BranchType::Local)
+            .context(format!("Branch '{}' not found", branch_name))?;
+        let reference = branch.into_reference();
+        let mut opts = WorktreeAddOptions::new();
+        opts.reference(Some(&reference));
+
+        self.repo.worktree(&worktree_name, &worktree_path, Some(&opts))
+            .context(format!("Failed to create worktree at '{}'", worktree_path.display()))?;
+
+        Ok(worktree_path)
+    }
+
+    pub fn prune_worktree(&self, worktree_path: &Path) -> Result<()> {
+        let worktree_name = worktree_path.file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| anyhow::anyhow!("Invalid worktree path '{}'", worktree_path.display()))?;
+
+        let worktree = self.repo.find_worktree(worktree_name)
+            .context(format!("Worktree '{}' not found", worktree_name))?;
+
+        // Remove the working directory along with the worktree metadata
+        let mut opts = WorktreePruneOptions::new();
+        opts.valid(true).working_tree(true);
+        worktree.prune(Some(&mut opts))
+            .context(format!("Failed to prune worktree '{}'", worktree_name))?;
+
+        Ok(())
+    }
+
     pub fn has_staged_changes(&self) -> Result<bool> {
         let mut status_opts = StatusOptions::new();
         status_opts.include_ignored(false);
@@ -60,7 +140,7 @@ impl GitRepo {
         Ok(false)
     }
 
-    pub fn commit(&self, message: &str) -> Result<()> {
+    pub fn commit(&self, message: &str, sign: bool) -> Result<()> {
         // Get the current index
         let mut index = self.repo.index()?;
         let tree_id = index.write_tree()?;
@@ -73,32 +153,218 @@ impl GitRepo {
         // Get signature
         let signature = self.get_signature()?;
         
-        // Create the commit
-        self.repo.commit(
-            Some("HEAD"),
+        if !sign {
+            // Create the commit
+            self.repo.commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &[&parent_commit],
+            )?;
+            
+            return Ok(());
+        }
+        
+        // Build the commit object, sign it, then advance the branch by hand
+        let buffer = self.repo.commit_create_buffer(
             &signature,
             &signature,
             message,
             &tree,
             &[&parent_commit],
         )?;
+        let content = buffer.as_str()
+            .ok_or_else(|| anyhow::anyhow!("Commit buffer is not valid UTF-8"))?;
+        let commit_signature = self.sign_buffer(content)?;
+        
+        let commit_id = self.repo.commit_signed(content, &commit_signature, None)?;
+        let mut head = self.repo.head()?;
+        head.set_target(commit_id, &format!("commit (signed): {}", message))?;
         
         Ok(())
     }
 
-    pub fn push_branch(&self, branch_name: &str) -> Result<()> {
+    fn sign_buffer(&self, content: &str) -> Result<String> {
+        let config = self.repo.config()?;
+        let format = config.get_string("gpg.format")
+            .unwrap_or_else(|_| "openpgp".to_string());
+        let signing_key = config.get_string("user.signingkey").ok();
+        
+        let mut command = match format.as_str() {
+            "ssh" => {
+                let key = signing_key
+                    .ok_or_else(|| anyhow::anyhow!("SSH signing requires 'user.signingkey' in git config"))?;
+                let program = config.get_string("gpg.ssh.program")
+                    .unwrap_or_else(|_| "ssh-keygen".to_string());
+                let mut command = Command::new(program);
+                command.args(["-Y", "sign", "-n", "git", "-f", &key]);
+                command
+            }
+            "openpgp" => {
+                let program = config.get_string("gpg.program")
+                    .unwrap_or_else(|_| "gpg".to_string());
+                let mut command = Command::new(program);
+                command.args(["--status-fd=2", "-bsa"]);
+                if let Some(key) = &signing_key {
+                    command.args(["-u", key]);
+                }
+                command
+            }
+            other => bail!("Unsupported gpg.format '{}' for signed commits", other),
+        };
+        
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to start the commit signing program")?;
+        child.stdin.take()
+            .ok_or_else(|| anyhow::anyhow!("Failed to open stdin of the signing program"))?
+            .write_all(content.as_bytes())?;
+        
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            bail!(
+                "Commit signing failed, no commit was written: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        
+        let commit_signature = String::from_utf8(output.stdout)
+            .context("Signing program produced a non UTF-8 signature")?;
+        if commit_signature.trim().is_empty() {
+            bail!("Commit signing produced an empty signature, no commit was written");
+        }
+        
+        Ok(commit_signature)
+    }
+
+    pub fn push_branch(&self, remote_name: &str, branch_name: &str) -> Result<()> {
+        // Make sure the remote exists before claiming to push to it
+        self.repo.find_remote(remote_name)
+            .context(format!("Remote '{}' not found", remote_name))?;
+        
         // For now, just print that we would push
         // In a real implementation, we'd need to handle authentication
-        println!("🔄 Pushing branch '{}' (git push simulation)", branch_name);
+        println!("{} Pushing branch '{}' to '{}' (git push simulation)", crate::glyphs::icon("🔄"), branch_name, remote_name);
         
         // In a real implementation:
-        // let mut remote = self.repo.find_remote("origin")?;
+        // let mut remote = self.repo.find_remote(remote_name)?;
         // let refspec = format!("refs/heads/{}:refs/heads/{}", branch_name, branch_name);
         // remote.push(&[&refspec], None)?;
         
         Ok(())
     }
 
+    pub fn rename_remote_branch(&self, remote_name: &str, old_name: &str, new_name: &str) -> Result<()> {
+        self.repo.find_remote(remote_name)
+            .context(format!("Remote '{}' not found", remote_name))?;
+        
+        // Same simulation caveats as push_branch
+        println!("{} Renaming '{}' to '{}' on '{}' (git push simulation)", crate::glyphs::icon("🔄"), old_name, new_name, remote_name);
+        
+        // In a real implementation:
+        // let mut remote = self.repo.find_remote(remote_name)?;
+        // let push = format!("refs/heads/{}:refs/heads/{}", new_name, new_name);
+        // let delete = format!(":refs/heads/{}", old_name);
+        // remote.push(&[&push, &delete], None)?;
+        
+        Ok(())
+    }
+
+    pub fn diff_against_base(&self, branch_name: &str, base_branch: &str, full: bool) -> Result<Option<DiffSummary>> {
+        let branch_commit = self.repo.find_branch(branch_name, BranchType::Local)
+            .context(format!("Branch '{}' not found", branch_name))?
+            .get()
+            .peel_to_commit()?;
+        let base_commit = self.repo.find_branch(base_branch, BranchType::Local)
+            .context(format!("Base branch '{}' not found", base_branch))?
+            .get()
+            .peel_to_commit()?;
+
+        // Diff from the point the branch forked off the base
+        let merge_base = self.repo.merge_base(branch_commit.id(), base_commit.id())
+            .context(format!("Branch '{}' shares no history with '{}'", branch_name, base_branch))?;
+        if merge_base == branch_commit.id() {
+            return Ok(None);
+        }
+
+        let base_tree = self.repo.find_commit(merge_base)?.tree()?;
+        let branch_tree = branch_commit.tree()?;
+        let diff = self.repo.diff_tree_to_tree(Some(&base_tree), Some(&branch_tree), None)?;
+
+        let files = diff.deltas()
+            .filter_map(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
+            .map(|path| path.display().to_string())
+            .collect();
+        let stats = diff.stats()?;
+
+        let patch = if full {
+            let mut patch = String::new();
+            diff.print(DiffFormat::Patch, |_delta, _hunk, line| {
+                if matches!(line.origin(), '+' | '-' | ' ') {
+                    patch.push(line.origin());
+                }
+                patch.push_str(&String::from_utf8_lossy(line.content()));
+                true
+            })?;
+            Some(patch)
+        } else {
+            None
+        };
+
+        Ok(Some(DiffSummary {
+            files,
+            insertions: stats.insertions(),
+            deletions: stats.deletions(),
+            patch,
+        }))
+    }
+
+    pub fn has_conflicts_with(&self, branch_name: &str, base_branch: &str) -> Result<Vec<String>> {
+        let branch_commit = self.repo.find_branch(branch_name, BranchType::Local)
+            .context(format!("Branch '{}' not found", branch_name))?
+            .get()
+            .peel_to_commit()?;
+        let base_commit = self.repo.find_branch(base_branch, BranchType::Local)
+            .context(format!("Base branch '{}' not found", base_branch))?
+            .get()
+            .peel_to_commit()?;
+
+        // Merge in memory only; the working directory is left untouched
+        let index = self.repo.merge_commits(&base_commit, &branch_commit, None)?;
+        if !index.has_conflicts() {
+            return Ok(Vec::new());
+        }
+
+        let mut files = Vec::new();
+        for conflict in index.conflicts()? {
+            let conflict = conflict?;
+            let entry = conflict.our.or(conflict.their).or(conflict.ancestor);
+            if let Some(entry) = entry {
+                files.push(String::from_utf8_lossy(&entry.path).to_string());
+            }
+        }
+
+        Ok(files)
+    }
+
+    pub fn list_local_branches(&self) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        for branch in self.repo.branches(Some(BranchType::Local))? {
+            let (branch, _) = branch?;
+            if let Some(name) = branch.name()? {
+                names.push(name.to_string());
+            }
+        }
+        names.sort();
+        
+        Ok(names)
+    }
+
     pub fn get_current_branch(&self) -> Result<Option<String>> {
         let head = self.repo.head()?;
         
@@ -111,8 +377,17 @@ impl GitRepo {
         }
     }
 
-    pub fn get_remote_url(&self) -> Result<Option<String>> {
-        let remote = self.repo.find_remote("origin");
+    pub fn remote_branch_exists(&self, remote_name: &str, branch_name: &str) -> Result<bool> {
+        self.repo.find_remote(remote_name)
+            .context(format!("Remote '{}' not found", remote_name))?;
+        
+        // Checks the remote-tracking ref, so it is only as fresh as the last fetch
+        let tracking = format!("{}/{}", remote_name, branch_name);
+        Ok(self.repo.find_branch(&tracking, BranchType::Remote).is_ok())
+    }
+
+    pub fn get_remote_url(&self, remote_name: &str) -> Result<Option<String>> {
+        let remote = self.repo.find_remote(remote_name);
         match remote {
             Ok(remote) => {
                 let url = remote.url()
@@ -123,6 +398,24 @@ impl GitRepo {
         }
     }
 
+    /// The repo's GitHub pull request template, read from the working tree root
+    pub fn pull_request_template(&self) -> Result<Option<String>> {
+        let Some(workdir) = self.repo.workdir() else {
+            return Ok(None);
+        };
+        
+        for name in ["pull_request_template.md", "PULL_REQUEST_TEMPLATE.md"] {
+            let path = workdir.join(".github").join(name);
+            if path.is_file() {
+                let template = std::fs::read_to_string(&path)
+                    .context(format!("Failed to read {}", path.display()))?;
+                return Ok(Some(template));
+            }
+        }
+        
+        Ok(None)
+    }
+
     pub fn is_clean_working_directory(&self) -> Result<bool> {
         let mut status_opts = StatusOptions::new();
         status_opts.include_ignored(false);
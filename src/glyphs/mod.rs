@@ -0,0 +1,130 @@
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+/// Every emoji pb prints, with the ASCII marker `ascii_icons` swaps in
+const GLYPHS: &[(&str, &str)] = &[
+    ("📋", "[#]"),
+    ("🌿", ">"),
+    ("✅", "[ok]"),
+    ("❌", "[x]"),
+    ("⚠️", "[!]"),
+    ("⛔", "[blocked]"),
+    ("📦", "[>]"),
+    ("🔗", "[link]"),
+    ("🔍", "[?]"),
+    ("🔁", "[~]"),
+    ("🔄", "[~]"),
+    ("♻️", "[~]"),
+    ("🔀", "[merge]"),
+    ("↩️", "[<]"),
+    ("🏷️", "[label]"),
+    ("📝", "[*]"),
+    ("👤", "[@]"),
+    ("🗑️", "[-]"),
+    ("🗄️", "[archive]"),
+    ("🧹", "[-]"),
+    ("🚀", "[^]"),
+    ("📤", "[^]"),
+    ("📥", "[v]"),
+    ("🚩", "[!]"),
+    ("🚧", "[wip]"),
+    ("🚦", "[wip]"),
+    ("💬", "[\"]"),
+    ("📣", "[@]"),
+    ("💡", "[i]"),
+    ("📊", "[=]"),
+    ("📐", "[=]"),
+    ("🔑", "[key]"),
+    ("🔏", "[signed]"),
+    ("⚙️", "[cfg]"),
+    ("📄", "[file]"),
+    ("📜", "[log]"),
+    ("💾", "[saved]"),
+    ("🗓️", "[date]"),
+    ("💤", "[zz]"),
+    ("🕸", "[old]"),
+    ("👆", "[.]"),
+    ("🎭", "[role]"),
+    ("👉", ">"),
+    ("🩺", "[ok]"),
+    ("ℹ️", "[i]"),
+    ("⏳", "[..]"),
+    ("⏱️", "[time]"),
+    ("⏭️", "[next]"),
+    ("◀", "<"),
+    ("▶", ">"),
+];
+
+// Set once from the `ascii_icons` config before the command runs
+static ASCII: OnceLock<bool> = OnceLock::new();
+
+pub fn set_ascii(ascii: bool) {
+    let _ = ASCII.set(ascii);
+}
+
+fn ascii() -> bool {
+    ASCII.get().copied().unwrap_or(false)
+}
+
+/// The glyph to show for `emoji`: itself, or its ASCII marker under `ascii_icons`
+pub fn icon(emoji: &'static str) -> &'static str {
+    match GLYPHS.iter().find(|(e, _)| *e == emoji) {
+        Some((_, marker)) if ascii() => marker,
+        _ => emoji,
+    }
+}
+
+/// `text` with the glyph leading each line swapped for its ASCII marker under
+/// `ascii_icons`. Only line-leading glyphs are ours; emoji further along are
+/// task titles, comments, and other user text, and are left alone.
+pub fn render(text: &str) -> Cow<'_, str> {
+    if ascii() { to_ascii(text) } else { Cow::Borrowed(text) }
+}
+
+fn to_ascii(text: &str) -> Cow<'_, str> {
+    let mut swapped = false;
+    let lines: Vec<Cow<str>> = text.split('\n')
+        .map(|line| {
+            let rest = line.trim_start_matches(' ');
+            let indent = &line[..line.len() - rest.len()];
+            // The table spells emoji with their variation selector; output sometimes doesn't
+            let found = GLYPHS.iter().find_map(|(emoji, marker)| {
+                rest.strip_prefix(emoji)
+                    .or_else(|| rest.strip_prefix(emoji.trim_end_matches('\u{fe0f}')))
+                    .map(|after| (marker, after))
+            });
+            match found {
+                Some((marker, after)) => {
+                    swapped = true;
+                    // Emoji often carry an extra space to make up for their width
+                    let after = after.trim_start_matches(' ');
+                    let gap = if after.is_empty() { "" } else { " " };
+                    Cow::Owned(format!("{}{}{}{}", indent, marker, gap, after))
+                }
+                None => Cow::Borrowed(line),
+            }
+        })
+        .collect();
+
+    if swapped { Cow::Owned(lines.join("\n")) } else { Cow::Borrowed(text) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_ascii_swaps_leading_glyphs() {
+        assert_eq!(to_ascii("📋 Created task #1: Fix login"), "[#] Created task #1: Fix login");
+        assert_eq!(to_ascii("\n📋 Doing (2)"), "\n[#] Doing (2)");
+        assert_eq!(to_ascii("   🗄️  Archived: 2024-01-02"), "   [archive] Archived: 2024-01-02");
+        assert_eq!(to_ascii("⚠  No token"), "[!] No token");
+        assert_eq!(to_ascii("🌿 main → feature"), "> main → feature");
+    }
+
+    #[test]
+    fn test_to_ascii_leaves_user_text_alone() {
+        assert!(matches!(to_ascii("#3: Ship 📋 export"), Cow::Borrowed(_)));
+        assert_eq!(to_ascii("📝 Updated task #3: 🚀 launch"), "[*] Updated task #3: 🚀 launch");
+    }
+}
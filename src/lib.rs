@@ -1,7 +1,9 @@
 pub mod commands;
+pub mod config;
 pub mod db;
 pub mod git;
 pub mod github;
+pub mod glyphs;
 pub mod tui;
 
 pub use commands::*;
@@ -1,19 +1,29 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use anyhow::Result;
 
 mod commands;
+mod config;
 mod db;
 mod git;
 mod github;
+mod glyphs;
 mod tui;
 
 use commands::*;
+use config::ColumnSpec;
+use db::{ColumnRole, TaskId};
 
 #[derive(Parser)]
 #[command(name = "pb")]
 #[command(about = "A terminal-first project board tool that wraps around git workflows")]
 #[command(version = "0.1.0")]
 struct Cli {
+    /// Only print errors and the output a command exists to produce
+    #[arg(short, long, global = true)]
+    quiet: bool,
+    /// When to color output: auto (default; honors NO_COLOR and CLICOLOR), always, or never
+    #[arg(long, value_name = "WHEN", default_value = "auto", global = true)]
+    color: ColorChoice,
     #[command(subcommand)]
     command: Commands,
 }
@@ -21,100 +31,789 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Initialize a new project board in the current repository
-    Init,
+    Init {
+        /// Create Doing tasks for existing feature/<id>-<slug> branches (safe to re-run)
+        #[arg(long, alias = "existing")]
+        adopt: bool,
+        /// Columns to create, in order, as name or name:role (e.g. "Inbox:backlog,Doing,Shipped:done");
+        /// overrides the columns in ~/.config/projectboard/defaults.toml
+        #[arg(long, value_delimiter = ',')]
+        columns: Option<Vec<ColumnSpec>>,
+    },
     /// Add a new task to the backlog
     Add {
         /// Task title
-        title: String,
+        #[arg(required_unless_present_any = ["interactive", "from_idea"])]
+        title: Option<String>,
         /// Optional task description
         #[arg(short, long)]
         description: Option<String>,
+        /// Assign the task to this user, or @me for your git user.name
+        #[arg(short, long)]
+        assignee: Option<String>,
+        /// Write the description in $EDITOR
+        #[arg(short, long, conflicts_with = "description")]
+        edit: bool,
+        /// Attach this label, creating it if it doesn't exist; repeat for several
+        #[arg(short, long = "label", value_name = "NAME")]
+        labels: Vec<String>,
+        /// Promote this idea instead, like `pb promote`
+        #[arg(long, value_name = "IDEA_ID", conflicts_with_all = ["title", "assignee", "edit", "labels"])]
+        from_idea: Option<u32>,
+        /// Prompt for title, description, priority, labels, and assignee
+        #[arg(short, long, conflicts_with_all = ["title", "description", "assignee", "edit", "labels", "from_idea"])]
+        interactive: bool,
+    },
+    /// Duplicate a task into the backlog
+    Clone {
+        /// Task ID
+        id: TaskId,
+    },
+    /// Manage reusable task templates
+    Template {
+        #[command(subcommand)]
+        action: TemplateAction,
+    },
+    /// Set a task's priority
+    Priority {
+        /// Task ID
+        id: TaskId,
+        /// low, medium, high, critical, or none to clear
+        level: String,
+    },
+    /// Make a task recur once it is done
+    Recur {
+        /// Task ID
+        id: TaskId,
+        /// daily, weekly, monthly, <n>d, <n>w, or none to stop recurring
+        rule: String,
     },
+    /// Create the next occurrence of recurring tasks that are due
+    Tick,
     /// List tasks, optionally filtered by column
     List {
         /// Column name to filter by
         column: Option<String>,
+        /// Maximum number of tasks to show per column
+        #[arg(short, long)]
+        limit: Option<u32>,
+        /// Show oldest tasks first
+        #[arg(long, conflicts_with = "newest")]
+        oldest: bool,
+        /// Show newest tasks first (default)
+        #[arg(long)]
+        newest: bool,
+        /// Only tasks that are not done and have no open blockers
+        #[arg(long, conflicts_with_all = ["blocked", "limit", "oldest", "newest"])]
+        ready: bool,
+        /// Only tasks waiting on open blockers, with the blocking task IDs
+        #[arg(long, conflicts_with_all = ["limit", "oldest", "newest"])]
+        blocked: bool,
+        /// Only tasks not updated in this long (<n>h, <n>d, <n>w) or since a date (YYYY-MM-DD);
+        /// the done column is skipped unless named
+        #[arg(long, value_name = "AGE", conflicts_with_all = ["ready", "blocked"])]
+        stale: Option<String>,
+        /// Group tasks by label or assignee instead of column, showing each task's column
+        #[arg(long, value_enum, value_name = "FIELD", conflicts_with_all = ["ready", "blocked"])]
+        group_by: Option<ListGrouping>,
+        /// Only tasks assigned to this user; @me is your git user.name, @unassigned
+        /// tasks with no assignee
+        #[arg(long, value_name = "USER")]
+        assignee: Option<String>,
+        /// Draw tasks as a dependency tree, each blocker above the tasks it blocks
+        #[arg(long, conflicts_with_all = ["ready", "blocked", "stale", "group_by", "limit", "oldest", "newest"])]
+        tree: bool,
+        /// Only print how many tasks each column holds, and the total
+        #[arg(long, conflicts_with_all = ["ready", "blocked", "stale", "group_by", "limit", "oldest", "newest", "tree"])]
+        count: bool,
+        /// With --count, print the counts as JSON
+        #[arg(long, requires = "count")]
+        json: bool,
+    },
+    /// Show a task's details, links, and comments
+    Show {
+        /// Task ID or @<title query>
+        id: TaskSelector,
+        /// Print the task, its labels, links, comments, and blockers as JSON
+        #[arg(long)]
+        json: bool,
+        /// Only show comments by this author
+        #[arg(long, value_name = "USER")]
+        author: Option<String>,
+        /// Only show comments since a date (YYYY-MM-DD) or age (<n>h, <n>d, <n>w)
+        #[arg(long)]
+        since: Option<String>,
+    },
+    /// Find tasks whose title or description contains the query
+    Find {
+        /// Text to search for
+        query: String,
+        /// Treat the query as a regular expression, also matched against comments
+        #[arg(long)]
+        regex: bool,
+    },
+    /// Show tasks assigned to you (your git user.name)
+    Mine {
+        /// Also show tasks that were archived
+        #[arg(long)]
+        include_archived: bool,
+    },
+    /// Summarize your recent and current work for a standup
+    Standup {
+        /// Also count tasks that were archived
+        #[arg(long)]
+        include_archived: bool,
+    },
+    /// Hide a task from the board without deleting it
+    Archive {
+        /// Task ID
+        id: TaskId,
+    },
+    /// Delete a task (it can be brought back with `pb restore`)
+    Delete {
+        /// Task ID
+        id: TaskId,
+    },
+    /// Bring an archived or deleted task back onto the board
+    Restore {
+        /// Task ID
+        id: TaskId,
+    },
+    /// Mark a task as still active today without moving it
+    Touch {
+        /// Task ID or @<title query>
+        id: TaskSelector,
+    },
+    /// Log time spent on a task
+    LogTime {
+        /// Task ID or @<title query>
+        id: TaskSelector,
+        /// Time spent: <n>h, <n>m, or both, e.g. 1h30m
+        duration: String,
     },
     /// Move a task to a different column
+    #[command(group(clap::ArgGroup::new("target").required(true).args(["column", "role", "next", "prev", "undo"])))]
     Move {
-        /// Task ID
-        id: u32,
-        /// Target column name
-        column: String,
+        /// Task ID or @<title query>
+        id: TaskSelector,
+        /// Target column name, or `back` for the column the task's last move came from
+        column: Option<String>,
+        /// Move to the column with this role, whatever it is named
+        #[arg(long, value_name = "ROLE")]
+        role: Option<ColumnRole>,
+        /// Move to the next column in board order
+        #[arg(long)]
+        next: bool,
+        /// Move to the previous column in board order
+        #[arg(long)]
+        prev: bool,
+        /// Move back to the column the task's last move came from
+        #[arg(long)]
+        undo: bool,
+        /// Move even if the target column is at its WIP limit
+        #[arg(long)]
+        force: bool,
+    },
+    /// Manage board columns
+    Column {
+        #[command(subcommand)]
+        action: ColumnAction,
     },
     /// Add a comment to a task
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
     Comment {
-        /// Task ID
-        id: u32,
-        /// Comment text
-        text: String,
+        #[command(subcommand)]
+        action: Option<CommentAction>,
+        #[command(flatten)]
+        comment: Option<NewComment>,
+    },
+    /// Manage external links attached to a task
+    Link {
+        #[command(subcommand)]
+        action: LinkAction,
+    },
+    /// Manage which tasks block which
+    Dep {
+        #[command(subcommand)]
+        action: DepAction,
+    },
+    /// Manage this repository's ProjectBoard settings
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Manage labels and attach them to tasks
+    Label {
+        #[command(subcommand)]
+        action: LabelAction,
     },
     /// Add a brainstorm idea
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
     Idea {
+        #[command(subcommand)]
+        action: Option<IdeaAction>,
         /// Idea content
-        content: String,
+        #[arg(required = true)]
+        content: Option<String>,
+        /// Promote the idea to a backlog task right away
+        #[arg(long)]
+        task: bool,
     },
     /// Promote an idea to a task in the backlog
     Promote {
         /// Idea ID
         id: u32,
+        /// Task description (overrides any "title :: description" split in the idea)
+        #[arg(short, long)]
+        description: Option<String>,
     },
     /// Start working on a task (creates branch, moves to Doing)
     Start {
+        /// Task ID or @<title query>
+        #[arg(required_unless_present = "idea")]
+        id: Option<TaskSelector>,
+        /// Promote this idea to a task and start it right away
+        #[arg(long, value_name = "IDEA_ID", conflicts_with = "id")]
+        idea: Option<u32>,
+        /// Check the branch out in a new sibling worktree instead of switching branches
+        #[arg(short, long, conflicts_with = "no_branch")]
+        worktree: bool,
+        /// Only move the task to Doing and assign it, without creating a branch
+        #[arg(long)]
+        no_branch: bool,
+        /// Branch off this branch, tag, or commit instead of HEAD (e.g. a release tag for a hotfix)
+        #[arg(long, value_name = "REF", conflicts_with = "no_branch")]
+        from: Option<String>,
+    },
+    /// Drop a started task: leave its branch and move it back to the backlog
+    Abandon {
+        /// Task ID or @<title query>
+        id: TaskSelector,
+        /// Also delete the task's local branch
+        #[arg(long)]
+        delete_branch: bool,
+        /// Column to move the task to (default: the backlog column)
+        #[arg(long)]
+        column: Option<String>,
+        /// Skip the confirmation prompt
+        #[arg(long)]
+        force: bool,
+    },
+    /// Rename a task's branch to match its current title
+    RenameBranch {
         /// Task ID
-        id: u32,
+        id: TaskId,
+        /// Also rename the branch on the configured remote
+        #[arg(long)]
+        remote: bool,
     },
     /// Mark a task as done (commits, pushes, moves to Done)
     Done {
         /// Task ID
-        id: u32,
+        id: TaskId,
         /// Optional commit message
         #[arg(short, long)]
         message: Option<String>,
+        /// Remove the task's worktree after committing
+        #[arg(long)]
+        prune_worktree: bool,
+        /// Commit even when the current branch is the base branch
+        #[arg(long)]
+        force: bool,
     },
     /// Submit a task for review (push branch, create PR, move to Review)
     Submit {
         /// Task ID
-        id: u32,
+        id: TaskId,
+        /// Abort instead of warning if the branch conflicts with the base
+        #[arg(long)]
+        check_conflicts: bool,
+        /// Remote to push the branch to (defaults to the `remote` config key)
+        #[arg(long)]
+        remote: Option<String>,
+        /// Branch the PR should target (defaults to the `base_branch` config key)
+        #[arg(long)]
+        base: Option<String>,
+        /// Use only the task description as the PR body, ignoring .github/pull_request_template.md
+        #[arg(long)]
+        no_template: bool,
     },
     /// Check PR status and update task accordingly
     Review {
         /// Task ID
-        id: u32,
+        #[arg(required_unless_present = "all")]
+        id: Option<TaskId>,
+        /// Check every task in the Review column and move merged ones to Done
+        #[arg(long, conflicts_with = "id")]
+        all: bool,
+        /// Exit with an error if any PR's checks are failing
+        #[arg(long)]
+        fail_fast: bool,
+    },
+    /// Merge a task's PR on GitHub and move the task to Done
+    Merge {
+        /// Task ID
+        id: TaskId,
+        /// Merge method (defaults to the `merge_method` config key)
+        #[arg(long, value_enum)]
+        method: Option<github::MergeMethod>,
+        /// Delete the task's branch locally and on GitHub after merging
+        #[arg(long)]
+        delete_branch: bool,
+    },
+    /// List local branches of Done tasks whose PRs are merged
+    PruneBranches {
+        /// Delete the listed branches instead of only showing them
+        #[arg(long)]
+        delete: bool,
     },
+    /// Point stored PR and link URLs at a GitHub repository's new name
+    MigrateRemote {
+        /// Old repository, as owner/repo
+        #[arg(long)]
+        from: String,
+        /// New repository, as owner/repo
+        #[arg(long)]
+        to: String,
+        /// List the URLs that would change without changing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Print a task's branch name
+    Branch {
+        /// Task ID or @<title query>
+        id: TaskSelector,
+        /// Also copy the branch name to the system clipboard
+        #[arg(short, long)]
+        copy: bool,
+    },
+    /// Show a diff summary of a task's branch against the base branch
+    Diff {
+        /// Task ID
+        id: TaskId,
+        /// Show the full patch instead of just the summary
+        #[arg(long)]
+        full: bool,
+    },
+    /// Show the git identity and GitHub token source pb will use
+    Whoami {
+        /// Also ask GitHub who the token belongs to and which scopes it has
+        #[arg(long)]
+        github: bool,
+    },
+    /// Delete all tasks, comments, ideas, and activity while keeping config
+    Reset {
+        /// Skip the confirmation prompt
+        #[arg(long)]
+        force: bool,
+        /// Also drop labels and custom columns, and recreate the default columns
+        #[arg(long)]
+        hard: bool,
+    },
+    /// Show task counts per column and WIP limit status
+    Stats {
+        /// Show open and in-progress task counts per assignee instead
+        #[arg(long)]
+        assignee_stats: bool,
+        /// Print as JSON: {tasks, ideas, columns}, or with --assignee-stats
+        /// an array of {assignee, open, in_progress} objects
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show recent board activity, newest first
+    Log {
+        /// Maximum number of entries to show
+        #[arg(short, long, default_value_t = 50)]
+        limit: u32,
+        /// Only entries from this date (YYYY-MM-DD) or this long ago (<n>h, <n>d, <n>w)
+        #[arg(long)]
+        since: Option<String>,
+        /// Only entries for this event (e.g. task_moved)
+        #[arg(short, long)]
+        event: Option<String>,
+        /// Print a JSON array of entries with the fields id, event, task_id,
+        /// column_from, column_to, metadata, and created_at (RFC 3339)
+        #[arg(long)]
+        json: bool,
+    },
+    /// Markdown release notes for tasks moved to Done since a date
+    Changelog {
+        /// Tasks done since this date (YYYY-MM-DD) or this long ago (<n>h, <n>d, <n>w)
+        #[arg(long)]
+        since: String,
+        /// Write the changelog to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Check the board, config, and repository for problems
+    Doctor {
+        /// Print each check as a JSON {name, status, message, hint} object
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check the board for inconsistencies between tasks, git, and dependencies
+    Check,
     /// Open interactive board view
-    Board,
+    Board {
+        /// Start focused on this column, shown full width
+        #[arg(short, long)]
+        column: Option<String>,
+        /// Write the board to this file as plain text instead of opening it
+        #[arg(long, value_name = "PATH")]
+        snapshot: Option<std::path::PathBuf>,
+    },
     /// Export tasks
     Export {
         /// Export format
         #[arg(short, long, value_enum)]
         format: ExportFormat,
+        /// Write the export to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+        /// End an ndjson export with a summary line
+        #[arg(long)]
+        summary: bool,
+        /// Also export hidden tasks: archived, deleted, or both (comma-separated)
+        #[arg(long, value_enum, value_delimiter = ',', value_name = "STATES")]
+        include: Vec<ExportInclude>,
+        /// Only export tasks in this column (case-insensitive); repeat for several
+        #[arg(short, long = "column", value_name = "NAME")]
+        columns: Vec<String>,
+        /// Replace assignees with pseudonyms and drop branches and PR links
+        #[arg(long)]
+        anonymize: bool,
+        /// With --anonymize, also blank out titles, descriptions, or both (comma-separated)
+        #[arg(long, value_enum, value_delimiter = ',', value_name = "FIELDS", requires = "anonymize")]
+        redact: Vec<ExportRedact>,
+    },
+    /// Import tasks from another tool
+    Import {
+        #[command(subcommand)]
+        action: ImportAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum ColumnAction {
+    /// Add a column at the end of the board
+    Add {
+        /// Column name
+        name: String,
+    },
+    /// Move a column before or after another column
+    Move {
+        /// Column name
+        name: String,
+        /// Place the column before this one
+        #[arg(long, conflicts_with = "after", required_unless_present = "after")]
+        before: Option<String>,
+        /// Place the column after this one
+        #[arg(long)]
+        after: Option<String>,
+    },
+    /// Rename a column; start, submit, and done find it by role, not name
+    Rename {
+        /// Current column name
+        name: String,
+        /// New column name
+        new_name: String,
+    },
+    /// Make a column the one start, submit, done, and add use for a role
+    SetRole {
+        /// Column name
+        name: String,
+        /// backlog, todo, doing, review, or done
+        role: ColumnRole,
+    },
+    /// Set or clear a column's work-in-progress limit
+    Limit {
+        /// Column name
+        name: String,
+        /// Maximum number of tasks, or none to remove the limit
+        limit: String,
+    },
+}
+
+/// `pb comment <id> [text]`, when no comment subcommand is given
+#[derive(Args)]
+struct NewComment {
+    /// Task ID
+    id: TaskId,
+    /// Comment text
+    #[arg(required_unless_present = "edit")]
+    text: Option<String>,
+    /// Write the comment in $EDITOR
+    #[arg(short, long, conflicts_with = "text")]
+    edit: bool,
+}
+
+#[derive(Subcommand)]
+enum CommentAction {
+    /// React to a comment with an emoji
+    React {
+        /// Comment ID (shown by 'pb show')
+        comment_id: u32,
+        /// Emoji, e.g. 👍
+        emoji: String,
+    },
+    /// Mark a comment thread as resolved
+    Resolve {
+        /// Comment ID (shown by 'pb show')
+        comment_id: u32,
+    },
+    /// Mark a resolved comment thread as open again
+    Unresolve {
+        /// Comment ID (shown by 'pb show')
+        comment_id: u32,
+    },
+}
+
+#[derive(Subcommand)]
+enum IdeaAction {
+    /// Change the content of an existing idea
+    Edit {
+        /// Idea ID
+        id: u32,
+        /// New idea content (opens $EDITOR with the current content when omitted)
+        content: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum LinkAction {
+    /// Attach a labeled URL to a task
+    Add {
+        /// Task ID
+        id: TaskId,
+        /// Link label (e.g. "Design doc")
+        label: String,
+        /// Link URL
+        url: String,
+    },
+    /// List a task's links
+    List {
+        /// Task ID
+        id: TaskId,
+    },
+}
+
+#[derive(Subcommand)]
+enum DepAction {
+    /// Mark a task as blocked by another task
+    Add {
+        /// Task ID
+        id: TaskId,
+        /// Task that has to be done first
+        blocked_by: TaskId,
+    },
+    /// Remove a dependency between two tasks
+    Remove {
+        /// Task ID
+        id: TaskId,
+        /// Task that no longer blocks it
+        blocked_by: TaskId,
+    },
+}
+
+#[derive(Subcommand)]
+enum ImportAction {
+    /// Import a Trello board's JSON export: lists become columns and cards tasks,
+    /// with their labels and checklists
+    Trello {
+        /// Path to the exported JSON file
+        path: std::path::PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Open .projectboard/config.toml in $EDITOR, starting from a commented template
+    Edit,
+}
+
+#[derive(Subcommand)]
+enum LabelAction {
+    /// Create a label
+    Create {
+        /// Label name
+        name: String,
+    },
+    /// Attach an existing label to a task
+    Add {
+        /// Task ID
+        id: TaskId,
+        /// Label name
+        name: String,
+    },
+    /// Detach a label from a task
+    Remove {
+        /// Task ID
+        id: TaskId,
+        /// Label name
+        name: String,
+    },
+    /// List all labels
+    List,
+}
+
+#[derive(Subcommand)]
+enum TemplateAction {
+    /// Save a task's title and description as a template
+    Save {
+        /// Template name
+        name: String,
+        /// Task ID to copy from
+        #[arg(long = "from")]
+        from_task: TaskId,
+    },
+    /// Create a backlog task from a template ({date} in the title becomes today's date)
+    Apply {
+        /// Template name
+        name: String,
     },
 }
 
 #[derive(clap::ValueEnum, Clone)]
 enum ExportFormat {
     Csv,
+    /// Jira import CSV: Summary (title), Description, Status (column), Assignee,
+    /// and Labels (space-separated, with spaces inside a label turned into underscores)
+    JiraCsv,
     Markdown,
+    Json,
+    /// One JSON object per task per line, streamed
+    Ndjson,
+    /// Graphviz graph of tasks colored by column, with an edge from each
+    /// blocker to the task it blocks (render with `dot -Tpng`)
+    Dot,
+    /// GitHub Projects (v2) fields and draft items: a Status single-select
+    /// with one option per column, and each task's title, body, and Status
+    GithubProjects,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
+    set_verbosity(if cli.quiet { Verbosity::Quiet } else { Verbosity::Normal });
+    set_color(cli.color);
+    // A config.toml that doesn't parse is reported by the commands that need it
+    if let Ok(config) = config::Config::load(&std::env::current_dir()?.join(".projectboard")) {
+        glyphs::set_ascii(config.ascii_icons);
+        // `pb config edit` still runs, so a bad prefix can be fixed
+        match config.task_prefix.as_deref().map(db::set_task_prefix) {
+            Some(Err(e)) if !matches!(cli.command, Commands::Config { .. }) => return Err(e),
+            _ => {}
+        }
+    }
 
     match cli.command {
-        Commands::Init => init_command().await,
-        Commands::Add { title, description } => add_command(title, description).await,
-        Commands::List { column } => list_command(column).await,
-        Commands::Move { id, column } => move_command(id, column).await,
-        Commands::Comment { id, text } => comment_command(id, text).await,
-        Commands::Idea { content } => idea_command(content).await,
-        Commands::Promote { id } => promote_command(id).await,
-        Commands::Start { id } => start_command(id).await,
-        Commands::Done { id, message } => done_command(id, message).await,
-        Commands::Submit { id } => submit_command(id).await,
-        Commands::Review { id } => review_command(id).await,
-        Commands::Board => board_command().await,
-        Commands::Export { format } => export_command(format).await,
+        Commands::Init { adopt, columns } => init_command(adopt, columns).await,
+        Commands::Add { from_idea: Some(idea_id), description, .. } => promote_command(idea_id, description).await,
+        Commands::Add { interactive: true, .. } => add_interactive_command().await,
+        Commands::Add { title: Some(title), description, assignee, edit, labels, .. } => {
+            add_command(title, description, assignee, edit, labels).await
+        }
+        Commands::Add { title: None, .. } => unreachable!("clap requires a title unless --interactive or --from-idea"),
+        Commands::Clone { id } => clone_command(id).await,
+        Commands::Template { action } => match action {
+            TemplateAction::Save { name, from_task } => template_save_command(name, from_task).await,
+            TemplateAction::Apply { name } => template_apply_command(name).await,
+        },
+        Commands::Priority { id, level } => priority_command(id, level).await,
+        Commands::Recur { id, rule } => recur_command(id, rule).await,
+        Commands::Tick => tick_command().await,
+        Commands::List { column, limit, oldest, newest: _, ready, blocked, stale, group_by, assignee, tree, count, json } => {
+            let readiness = match (ready, blocked) {
+                (true, _) => Some(ReadinessFilter::Ready),
+                (_, true) => Some(ReadinessFilter::Blocked),
+                _ => None,
+            };
+            list_command(column, limit, oldest, readiness, stale, group_by, assignee, tree, count, json).await
+        }
+        Commands::Show { id, json, author, since } => show_command(id, json, author, since).await,
+        Commands::Find { query, regex } => find_command(query, regex).await,
+        Commands::Mine { include_archived } => mine_command(include_archived).await,
+        Commands::Standup { include_archived } => standup_command(include_archived).await,
+        Commands::Archive { id } => archive_command(id).await,
+        Commands::Delete { id } => delete_command(id).await,
+        Commands::Restore { id } => restore_command(id).await,
+        Commands::Touch { id } => touch_command(id).await,
+        Commands::LogTime { id, duration } => log_time_command(id, duration).await,
+        Commands::Move { id, column, role, next, prev, undo, force } => {
+            let target = match (column, role) {
+                (Some(column), _) if column == "back" => MoveTarget::Back,
+                (Some(column), _) => MoveTarget::Column(column),
+                (None, Some(role)) => MoveTarget::Role(role),
+                (None, None) if next => MoveTarget::Next,
+                (None, None) if prev => MoveTarget::Prev,
+                (None, None) if undo => MoveTarget::Undo,
+                (None, None) => unreachable!("clap requires a move target"),
+            };
+            move_command(id, target, force).await
+        }
+        Commands::Column { action } => match action {
+            ColumnAction::Add { name } => column_add_command(name).await,
+            ColumnAction::Move { name, before, after } => column_move_command(name, before, after).await,
+            ColumnAction::Rename { name, new_name } => column_rename_command(name, new_name).await,
+            ColumnAction::SetRole { name, role } => column_set_role_command(name, role).await,
+            ColumnAction::Limit { name, limit } => column_limit_command(name, limit).await,
+        },
+        Commands::Comment { action: Some(CommentAction::React { comment_id, emoji }), .. } => comment_react_command(comment_id, emoji).await,
+        Commands::Comment { action: Some(CommentAction::Resolve { comment_id }), .. } => comment_resolve_command(comment_id, true).await,
+        Commands::Comment { action: Some(CommentAction::Unresolve { comment_id }), .. } => comment_resolve_command(comment_id, false).await,
+        Commands::Comment { action: None, comment: Some(NewComment { id, text, edit }) } => comment_command(id, text, edit).await,
+        Commands::Comment { action: None, comment: None } => unreachable!("clap requires a task ID"),
+        Commands::Link { action } => match action {
+            LinkAction::Add { id, label, url } => link_add_command(id, label, url).await,
+            LinkAction::List { id } => link_list_command(id).await,
+        },
+        Commands::Dep { action } => match action {
+            DepAction::Add { id, blocked_by } => dep_add_command(id, blocked_by).await,
+            DepAction::Remove { id, blocked_by } => dep_remove_command(id, blocked_by).await,
+        },
+        Commands::Config { action } => match action {
+            ConfigAction::Edit => config_edit_command().await,
+        },
+        Commands::Label { action } => match action {
+            LabelAction::Create { name } => label_create_command(name).await,
+            LabelAction::Add { id, name } => label_add_command(id, name).await,
+            LabelAction::Remove { id, name } => label_remove_command(id, name).await,
+            LabelAction::List => label_list_command().await,
+        },
+        Commands::Idea { action: Some(IdeaAction::Edit { id, content }), .. } => idea_edit_command(id, content).await,
+        Commands::Idea { action: None, content, task } => idea_command(content.unwrap_or_default(), task).await,
+        Commands::Promote { id, description } => promote_command(id, description).await,
+        Commands::Start { idea: Some(idea_id), worktree, no_branch, from, .. } => {
+            start_idea_command(idea_id, worktree, no_branch, from).await
+        }
+        Commands::Start { id: Some(id), worktree, no_branch, from, .. } => start_command(id, worktree, no_branch, from).await,
+        Commands::Start { id: None, idea: None, .. } => unreachable!("clap requires an id or --idea"),
+        Commands::Abandon { id, delete_branch, column, force } => abandon_command(id, delete_branch, column, force).await,
+        Commands::RenameBranch { id, remote } => rename_branch_command(id, remote).await,
+        Commands::Done { id, message, prune_worktree, force } => done_command(id, message, prune_worktree, force).await,
+        Commands::Submit { id, check_conflicts, remote, base, no_template } => {
+            submit_command(id, check_conflicts, remote, base, no_template).await
+        }
+        Commands::Review { id: Some(id), fail_fast, .. } => review_command(id, fail_fast).await,
+        Commands::Review { id: None, fail_fast, .. } => review_all_command(fail_fast).await,
+        Commands::Merge { id, method, delete_branch } => merge_command(id, method, delete_branch).await,
+        Commands::PruneBranches { delete } => prune_branches_command(delete).await,
+        Commands::MigrateRemote { from, to, dry_run } => migrate_remote_command(from, to, dry_run).await,
+        Commands::Branch { id, copy } => branch_command(id, copy).await,
+        Commands::Diff { id, full } => diff_command(id, full).await,
+        Commands::Whoami { github } => whoami_command(github).await,
+        Commands::Reset { force, hard } => reset_command(force, hard).await,
+        Commands::Stats { assignee_stats, json } => stats_command(assignee_stats, json).await,
+        Commands::Log { limit, since, event, json } => log_command(limit, since, event, json).await,
+        Commands::Changelog { since, output } => changelog_command(since, output).await,
+        Commands::Doctor { json } => doctor_command(json).await,
+        Commands::Check => check_command().await,
+        Commands::Board { column, snapshot } => board_command(column, snapshot).await,
+        Commands::Export { format, output, summary, include, columns, anonymize, redact } => {
+            export_command(format, output, summary, include, columns, anonymize, redact).await
+        }
+        Commands::Import { action } => match action {
+            ImportAction::Trello { path } => import_trello_command(path).await,
+        },
     }
 }
@@ -0,0 +1,161 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+};
+
+/// Convert the markdown subset used in task descriptions into styled text:
+/// headings, lists, quotes, fenced code, bold, italic, inline code, and links.
+/// Anything that doesn't parse cleanly (an unclosed `**`, say) is shown as typed.
+pub fn markdown_to_text(source: &str) -> Text<'static> {
+    let mut lines = Vec::new();
+    let mut in_code_block = false;
+
+    for raw in source.lines() {
+        if raw.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            lines.push(Line::from(Span::styled(format!("  {}", raw), code_style())));
+            continue;
+        }
+
+        let trimmed = raw.trim_start();
+        let indent = &raw[..raw.len() - trimmed.len()];
+
+        if let Some((level, heading)) = parse_heading(trimmed) {
+            let style = if level == 1 {
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+            } else {
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+            };
+            lines.push(Line::from(parse_inline(heading, style)));
+        } else if let Some(item) = ["- ", "* ", "+ "].iter().find_map(|m| trimmed.strip_prefix(m)) {
+            let mut spans = vec![Span::raw(format!("{}• ", indent))];
+            spans.extend(parse_inline(item, Style::default()));
+            lines.push(Line::from(spans));
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            let style = Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC);
+            let mut spans = vec![Span::styled("│ ", style)];
+            spans.extend(parse_inline(quote.trim_start(), style));
+            lines.push(Line::from(spans));
+        } else {
+            let mut spans = vec![Span::raw(indent.to_string())];
+            spans.extend(parse_inline(trimmed, Style::default()));
+            lines.push(Line::from(spans));
+        }
+    }
+
+    Text::from(lines)
+}
+
+fn code_style() -> Style {
+    Style::default().fg(Color::Yellow)
+}
+
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    line[level..].strip_prefix(' ').map(|heading| (level, heading.trim()))
+}
+
+fn parse_inline(text: &str, base: Style) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut i = 0;
+
+    while i < text.len() {
+        let rest = &text[i..];
+        let prev_is_word = text[..i].chars().last().is_some_and(|c| c.is_alphanumeric());
+
+        let parsed = if rest.starts_with('`') {
+            closed_by(rest, "`", "`").map(|(inner, len)| (vec![Span::styled(inner.to_string(), code_style())], len))
+        } else if rest.starts_with("**") {
+            closed_by(rest, "**", "**")
+                .map(|(inner, len)| (parse_inline(inner, base.add_modifier(Modifier::BOLD)), len))
+        } else if (rest.starts_with('*') || rest.starts_with('_')) && !prev_is_word {
+            // A lone `_` inside a word (snake_case) is not emphasis
+            let marker = &rest[..1];
+            closed_by(rest, marker, marker)
+                .map(|(inner, len)| (parse_inline(inner, base.add_modifier(Modifier::ITALIC)), len))
+        } else if rest.starts_with('[') {
+            parse_link(rest, base)
+        } else {
+            None
+        };
+
+        match parsed {
+            Some((styled, len)) => {
+                if !plain.is_empty() {
+                    spans.push(Span::styled(std::mem::take(&mut plain), base));
+                }
+                spans.extend(styled);
+                i += len;
+            }
+            None => {
+                let c = rest.chars().next().unwrap();
+                plain.push(c);
+                i += c.len_utf8();
+            }
+        }
+    }
+
+    if !plain.is_empty() {
+        spans.push(Span::styled(plain, base));
+    }
+    spans
+}
+
+/// The non-empty text between `open` and the next `close`, and the total length consumed
+fn closed_by<'a>(text: &'a str, open: &str, close: &str) -> Option<(&'a str, usize)> {
+    let body = text.strip_prefix(open)?;
+    let end = body.find(close)?;
+    (end > 0).then(|| (&body[..end], open.len() + end + close.len()))
+}
+
+fn parse_link(text: &str, base: Style) -> Option<(Vec<Span<'static>>, usize)> {
+    let (label, label_len) = closed_by(text, "[", "]")?;
+    let (url, url_len) = closed_by(&text[label_len..], "(", ")")?;
+    let spans = vec![
+        Span::styled(label.to_string(), base.fg(Color::Blue).add_modifier(Modifier::UNDERLINED)),
+        Span::styled(format!(" ({})", url), Style::default().fg(Color::DarkGray)),
+    ];
+    Some((spans, label_len + url_len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(text: &Text) -> Vec<String> {
+        text.lines.iter()
+            .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_block_elements() {
+        let text = markdown_to_text("# Plan\n- first\n  * nested\n> quoted\n```\nlet x = 1;\n```");
+        assert_eq!(plain(&text), ["Plan", "• first", "  • nested", "│ quoted", "  let x = 1;"]);
+        assert!(text.lines[0].spans[0].style.add_modifier.contains(Modifier::BOLD));
+    }
+
+    #[test]
+    fn test_inline_styles() {
+        let text = markdown_to_text("Use **bold**, *italic*, `code` and [docs](https://example.com)");
+        assert_eq!(plain(&text), ["Use bold, italic, code and docs (https://example.com)"]);
+
+        let bold = text.lines[0].spans.iter().find(|s| s.content == "bold").unwrap();
+        assert!(bold.style.add_modifier.contains(Modifier::BOLD));
+        let italic = text.lines[0].spans.iter().find(|s| s.content == "italic").unwrap();
+        assert!(italic.style.add_modifier.contains(Modifier::ITALIC));
+    }
+
+    #[test]
+    fn test_unbalanced_markup_is_left_as_typed() {
+        let text = markdown_to_text("**not closed, snake_case_name, [label] and #hashtag");
+        assert_eq!(plain(&text), ["**not closed, snake_case_name, [label] and #hashtag"]);
+    }
+}
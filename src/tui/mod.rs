@@ -6,39 +6,54 @@ use crossterm::{
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
-    backend::{Backend, CrosstermBackend},
+    backend::{Backend, CrosstermBackend, TestBackend},
+    buffer::Buffer,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
     Frame, Terminal,
 };
 
-use crate::db::Database;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use unicode_width::UnicodeWidthStr;
+
+use crate::config::CardField;
+use crate::db::{task_ref, AssigneeFilter, ColumnRole, Conflict, Database, Priority};
+use crate::github::{GitHubClient, PullRequestStatus, parse_pr_url, resolve_token};
+
+mod markdown;
+
+// Narrowest a column may get before the board scrolls horizontally instead
+const MIN_COLUMN_WIDTH: u16 = 24;
+
+pub async fn run_board_interface(focus_column: Option<String>) -> Result<()> {
+    // Create app state first so errors print on a normal terminal
+    let db = get_database().await?;
+    let mut app = load_app(&db, focus_column).await?;
 
-pub async fn run_board_interface() -> Result<()> {
     // Setup terminal
     enable_raw_mode()?;
+    let guard = TerminalGuard { restore: restore_terminal };
+    // Restore before the default hook prints, or the message lands on the alternate screen
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        default_hook(info);
+    }));
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
-
-    // Create app state
-    let db = get_database().await?;
-    let app = App::new(db).await?;
     
     // Run the app
-    let res = run_app(&mut terminal, app).await;
+    let res = run_app(&mut terminal, &db, &mut app).await;
 
     // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    drop(guard);
+
+    TuiState::from_app(&app).save();
 
     if let Err(err) = res {
         println!("{err:?}");
@@ -47,56 +62,548 @@ pub async fn run_board_interface() -> Result<()> {
     Ok(())
 }
 
+/// Draw the board once, as `pb board` would open it, and write it to `path`
+/// as plain text so it can be pasted without a screenshot
+pub async fn write_board_snapshot(focus_column: Option<String>, path: &Path) -> Result<()> {
+    let db = get_database().await?;
+    let mut app = load_app(&db, focus_column).await?;
+    std::fs::write(path, render_snapshot(&mut app)?)
+        .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", path.display(), e))?;
+    Ok(())
+}
+
+async fn load_app(db: &Database, focus_column: Option<String>) -> Result<App> {
+    let archived = crate::commands::auto_archive_done(db).await?;
+    let mut app = App::load(db).await?;
+    app.restore(&TuiState::load());
+    if archived > 0 {
+        app.status = Some(format!("Auto-archived {} task(s) that sat in Done past auto_archive_done_after", archived));
+    }
+    if let Some(name) = focus_column {
+        app.selected_column = app.columns.iter()
+            .position(|c| c.name == name)
+            .ok_or_else(|| anyhow::anyhow!("Column '{}' not found", name))?;
+        app.focused = true;
+    }
+    Ok(app)
+}
+
+/// `ui()` drawn into an offscreen buffer as wide as the terminal, or wider if
+/// that is what fits every column, and tall enough for the longest column
+fn render_snapshot(app: &mut App) -> Result<String> {
+    let visible = if app.focused { 1 } else { app.columns.len() as u16 };
+    let width = crossterm::terminal::size().map_or(120, |(w, _)| w).max(visible * MIN_COLUMN_WIDTH);
+    let tallest = app.columns.iter()
+        .map(|c| app.column_tasks(c.id).iter().map(|t| 1 + u16::from(!app.card_details(t).is_empty())).sum())
+        .max()
+        .unwrap_or(0);
+    // Header, footer, and the column borders around the cards
+    let height = tallest + 3 + 1 + 2;
+    
+    let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+    terminal.draw(|f| ui(f, app))?;
+    Ok(buffer_to_text(terminal.backend().buffer()))
+}
+
+/// The buffer's symbols line by line, without styling or trailing blanks
+fn buffer_to_text(buffer: &Buffer) -> String {
+    let mut text = String::new();
+    for row in buffer.content.chunks(buffer.area.width as usize) {
+        let mut line = String::new();
+        // A wide symbol covers the cells after it, which hold padding
+        let mut covered = 0;
+        for cell in row {
+            if covered == 0 {
+                line.push_str(&cell.symbol);
+            }
+            covered = covered.max(cell.symbol.width()).saturating_sub(1);
+        }
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+/// Runs `restore` when dropped, so an early return or a panic in the board
+/// still hands the shell back in a usable state
+struct TerminalGuard<F: FnMut()> {
+    restore: F,
+}
+
+impl<F: FnMut()> Drop for TerminalGuard<F> {
+    fn drop(&mut self) {
+        (self.restore)();
+    }
+}
+
+// Best-effort and safe to repeat: the panic hook and the guard may both run it
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, crossterm::cursor::Show);
+}
+
+/// Where the user left the board, restored on the next `pb board`
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TuiState {
+    selected_column_id: Option<i64>,
+    column_offset: usize,
+}
+
+impl TuiState {
+    fn path() -> Option<PathBuf> {
+        std::env::current_dir().ok().map(|dir| dir.join(".projectboard").join("tui_state.json"))
+    }
+    
+    // Best-effort: a missing or unreadable file just means starting fresh
+    fn load() -> Self {
+        Self::path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+    
+    fn save(&self) {
+        if let (Some(path), Ok(contents)) = (Self::path(), serde_json::to_string_pretty(self)) {
+            let _ = std::fs::write(path, contents);
+        }
+    }
+    
+    fn from_app(app: &App) -> Self {
+        TuiState {
+            selected_column_id: app.columns.get(app.selected_column).map(|c| c.id),
+            column_offset: app.column_offset,
+        }
+    }
+}
+
+/// A PR status request started with `s`, still in flight
+struct PrCheck {
+    task_id: i64,
+    pr_number: u32,
+    handle: tokio::task::JoinHandle<Result<PullRequestStatus>>,
+}
+
 struct App {
-    db: Database,
     columns: Vec<crate::db::Column>,
     tasks_by_column: std::collections::HashMap<i64, Vec<crate::db::Task>>,
+    wip_limits: std::collections::HashMap<i64, i64>,
     selected_column: usize,
+    selected_task: usize,
+    show_detail: bool,
+    // Show only the selected column, full width
+    focused: bool,
+    column_offset: usize,
+    sort_by_priority: bool,
+    oldest_first: bool,
+    // Tasks not updated for this long get marked; None when stale_days is 0
+    stale_after: Option<chrono::Duration>,
+    // Extra fields from `tui.card_fields`, shown on a line under each title
+    card_fields: Vec<CardField>,
+    assignee_filter: AssigneeFilter,
+    // Defined label names in order, and the tasks carrying each
+    labels: Vec<String>,
+    label_tasks: std::collections::HashMap<String, std::collections::HashSet<i64>>,
+    label_filter: Option<String>,
+    status: Option<String>,
+    pr_check: Option<PrCheck>,
 }
 
 impl App {
-    async fn new(db: Database) -> Result<Self> {
-        let columns = db.get_columns().await?;
-        let mut tasks_by_column = std::collections::HashMap::new();
-        
-        for column in &columns {
-            let tasks = db.get_tasks(Some(column.id)).await?;
-            tasks_by_column.insert(column.id, tasks);
-        }
-        
-        Ok(App {
-            db,
+    fn new(columns: Vec<crate::db::Column>, tasks_by_column: std::collections::HashMap<i64, Vec<crate::db::Task>>) -> Self {
+        App {
             columns,
             tasks_by_column,
+            wip_limits: std::collections::HashMap::new(),
             selected_column: 0,
-        })
+            selected_task: 0,
+            show_detail: false,
+            focused: false,
+            column_offset: 0,
+            sort_by_priority: false,
+            oldest_first: false,
+            stale_after: None,
+            card_fields: Vec::new(),
+            assignee_filter: AssigneeFilter::All,
+            labels: Vec::new(),
+            label_tasks: std::collections::HashMap::new(),
+            label_filter: None,
+            status: None,
+            pr_check: None,
+        }
+    }
+    
+    async fn load(db: &Database) -> Result<Self> {
+        let (columns, tasks_by_column) = load_board(db).await?;
+        let mut app = App::new(columns, tasks_by_column);
+        app.wip_limits = load_wip_limits(db).await?;
+        (app.labels, app.label_tasks) = load_labels(db).await?;
+        let config = crate::config::Config::load(&std::env::current_dir()?.join(".projectboard"))?;
+        app.stale_after = Some(chrono::Duration::days(config.stale_days.into())).filter(|_| config.stale_days > 0);
+        app.card_fields = config.tui.card_fields;
+        Ok(app)
+    }
+    
+    async fn reload(&mut self, db: &Database) -> Result<()> {
+        // Keep the view state, but the column count may have changed underneath us
+        let (columns, tasks_by_column) = load_board(db).await?;
+        self.columns = columns;
+        self.tasks_by_column = tasks_by_column;
+        self.wip_limits = load_wip_limits(db).await?;
+        (self.labels, self.label_tasks) = load_labels(db).await?;
+        // The filtered label may have been removed underneath us too
+        if self.label_filter.as_ref().is_some_and(|label| !self.labels.contains(label)) {
+            self.label_filter = None;
+        }
+        self.selected_column = self.selected_column.min(self.columns.len().saturating_sub(1));
+        self.column_offset = self.column_offset.min(self.selected_column);
+        Ok(())
+    }
+    
+    fn restore(&mut self, state: &TuiState) {
+        // Columns may have been removed or reordered since the state was saved
+        if let Some(index) = state.selected_column_id
+            .and_then(|id| self.columns.iter().position(|c| c.id == id))
+        {
+            self.selected_column = index;
+            self.column_offset = state.column_offset.min(index);
+        }
+    }
+    
+    fn scroll_to_selected(&mut self, visible: usize) {
+        // Slide the window only once the selection reaches its edge
+        if self.selected_column < self.column_offset {
+            self.column_offset = self.selected_column;
+        } else if self.selected_column >= self.column_offset + visible {
+            self.column_offset = self.selected_column + 1 - visible;
+        }
+        
+        // Don't leave empty space on the right after a resize
+        let max_offset = self.columns.len().saturating_sub(visible);
+        self.column_offset = self.column_offset.min(max_offset);
+    }
+    
+    fn toggle_priority_sort(&mut self) {
+        self.sort_by_priority = !self.sort_by_priority;
+    }
+    
+    fn toggle_oldest_first(&mut self) {
+        self.oldest_first = !self.oldest_first;
+    }
+    
+    fn is_stale(&self, task: &crate::db::Task, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.stale_after.is_some_and(|age| now - task.updated_at >= age)
+    }
+    
+    /// Filter by an assignee token (`@me`, `@unassigned`)
+    fn toggle_assignee_filter(&mut self, token: &str) {
+        let filter = match crate::commands::resolve_assignee_token(token) {
+            Ok(filter) => filter,
+            Err(e) => {
+                self.status = Some(e.to_string());
+                return;
+            }
+        };
+        // Pressing the active filter's key again clears it
+        self.assignee_filter = if self.assignee_filter == filter {
+            AssigneeFilter::All
+        } else {
+            filter
+        };
+    }
+    
+    fn matches_assignee_filter(&self, task: &crate::db::Task) -> bool {
+        self.assignee_filter.matches(task.assignee.as_deref())
+    }
+    
+    /// Step to the next defined label, and back to all tasks after the last one
+    fn cycle_label_filter(&mut self) {
+        let next = match &self.label_filter {
+            Some(current) => self.labels.iter().position(|l| l == current).map_or(0, |i| i + 1),
+            None => 0,
+        };
+        self.label_filter = self.labels.get(next).cloned();
+    }
+    
+    fn matches_label_filter(&self, task: &crate::db::Task) -> bool {
+        match &self.label_filter {
+            Some(label) => self.label_tasks.get(label).is_some_and(|ids| ids.contains(&task.id)),
+            None => true,
+        }
+    }
+    
+    /// The `card_fields` the task has values for, formatted for its card
+    fn card_details(&self, task: &crate::db::Task) -> Vec<String> {
+        self.card_fields.iter()
+            .filter_map(|field| match field {
+                CardField::Assignee => task.assignee.as_ref().map(|a| format!("@{}", a)),
+                CardField::Labels => {
+                    let labels: Vec<&str> = self.labels.iter()
+                        .filter(|l| self.label_tasks.get(*l).is_some_and(|tasks| tasks.contains(&task.id)))
+                        .map(String::as_str)
+                        .collect();
+                    (!labels.is_empty()).then(|| labels.join(", "))
+                }
+                CardField::Priority => task.priority.clone(),
+                CardField::Branch => task.branch_name.clone(),
+            })
+            .collect()
+    }
+    
+    fn column_tasks(&self, column_id: i64) -> Vec<&crate::db::Task> {
+        let mut tasks: Vec<_> = self.tasks_by_column
+            .get(&column_id)
+            .map(|tasks| {
+                tasks.iter()
+                    .filter(|t| self.matches_assignee_filter(t) && self.matches_label_filter(t))
+                    .collect()
+            })
+            .unwrap_or_default();
+        // Tasks are loaded newest first
+        if self.oldest_first {
+            tasks.reverse();
+        }
+        
+        // Stable sort keeps the stored order for tasks with equal priority
+        if self.sort_by_priority {
+            tasks.sort_by_key(|t| std::cmp::Reverse(t.priority()));
+        }
+        
+        tasks
+    }
+    
+    fn selected_column_tasks(&self) -> Vec<&crate::db::Task> {
+        self.columns.get(self.selected_column)
+            .map(|column| self.column_tasks(column.id))
+            .unwrap_or_default()
+    }
+    
+    fn selected_task(&self) -> Option<&crate::db::Task> {
+        let tasks = self.selected_column_tasks();
+        // Filters and reloads can shrink the column under the cursor
+        tasks.get(self.selected_task.min(tasks.len().saturating_sub(1))).copied()
     }
     
     fn next_column(&mut self) {
-        if self.selected_column < self.columns.len() - 1 {
+        if self.selected_column + 1 < self.columns.len() {
             self.selected_column += 1;
+            self.selected_task = 0;
         }
     }
     
     fn previous_column(&mut self) {
         if self.selected_column > 0 {
             self.selected_column -= 1;
+            self.selected_task = 0;
+        }
+    }
+    
+    /// Task count (ignoring filters) and WIP limit, if the column has one
+    fn column_load(&self, column_id: i64) -> Option<(usize, i64)> {
+        let limit = *self.wip_limits.get(&column_id)?;
+        let count = self.tasks_by_column.get(&column_id).map_or(0, Vec::len);
+        Some((count, limit))
+    }
+    
+    /// Column index the selected task would move to, unless it is blocked by a WIP limit
+    fn move_target(&self, forward: bool) -> Option<std::result::Result<usize, String>> {
+        self.selected_task()?;
+        let target = if forward {
+            Some(self.selected_column + 1).filter(|&i| i < self.columns.len())
+        } else {
+            self.selected_column.checked_sub(1)
+        }?;
+        
+        let column = &self.columns[target];
+        match self.column_load(column.id) {
+            Some((count, limit)) if count as i64 >= limit => Some(Err(format!(
+                "⚠️  {} is at its WIP limit ({}/{})", column.name, count, limit
+            ))),
+            _ => Some(Ok(target)),
+        }
+    }
+    
+    async fn move_selected_task(&mut self, db: &Database, forward: bool) -> Result<()> {
+        let target = match self.move_target(forward) {
+            Some(Ok(target)) => target,
+            Some(Err(warning)) => {
+                self.status = Some(warning);
+                return Ok(());
+            }
+            None => return Ok(()),
+        };
+        let Some(mut task) = self.selected_task().cloned() else {
+            return Ok(());
+        };
+        let from = self.columns[self.selected_column].name.clone();
+        let to = self.columns[target].clone();
+        
+        if let Err(e) = db.update_task_column(&mut task, to.id).await {
+            if e.downcast_ref::<Conflict>().is_none() {
+                return Err(e);
+            }
+            // Changed since the board loaded: retry on the fresh copy unless it moved too
+            let column_id = task.column_id;
+            db.refresh_task(&mut task).await?;
+            if task.column_id != column_id {
+                self.reload(db).await?;
+                self.status = Some(format!("Task {} was moved elsewhere; board reloaded", task_ref(task.id)));
+                return Ok(());
+            }
+            db.update_task_column(&mut task, to.id).await?;
+        }
+        db.log_task_activity(
+            "task_moved",
+            task.id,
+            Some((&from, &to)),
+            Some(format!("Task {}: {} → {}", task_ref(task.id), from, to.name))
+        ).await?;
+        let logged = match to.role() {
+            Some(ColumnRole::Done) => crate::commands::stop_timer(db, task.id).await?,
+            _ => None,
+        };
+        
+        // Follow the task to its new column
+        self.reload(db).await?;
+        self.selected_column = target;
+        self.selected_task = self.selected_column_tasks().iter().position(|t| t.id == task.id).unwrap_or(0);
+        self.status = Some(match logged {
+            Some(minutes) => format!("Moved {} to {}; timer stopped, {} logged", task_ref(task.id), to.name, crate::commands::format_minutes(minutes)),
+            None => format!("Moved {} to {}", task_ref(task.id), to.name),
+        });
+        Ok(())
+    }
+    
+    fn copy_selected_branch(&mut self) {
+        let Some(task) = self.selected_task() else {
+            return;
+        };
+        self.status = Some(match &task.branch_name {
+            Some(branch) => match crate::commands::copy_to_clipboard(branch) {
+                Ok(()) => format!("Copied {}", branch),
+                Err(e) => format!("{:#}", e),
+            },
+            None => format!("Task {} has no branch", task_ref(task.id)),
+        });
+    }
+    
+    /// Start fetching the selected task's PR status in the background
+    fn check_selected_pr(&mut self) {
+        let Some(task) = self.selected_task() else {
+            return;
+        };
+        let Some(pr_url) = &task.pr_url else {
+            self.status = Some(format!("Task {} has no PR", task_ref(task.id)));
+            return;
+        };
+        let Some((owner, repo, pr_number)) = parse_pr_url(pr_url) else {
+            self.status = Some(format!("Task {}'s PR is not a GitHub pull request", task_ref(task.id)));
+            return;
+        };
+        let task_id = task.id;
+        
+        // Only the latest request matters
+        if let Some(previous) = self.pr_check.take() {
+            previous.handle.abort();
+        }
+        let handle = tokio::spawn(async move {
+            let pb_dir = std::env::current_dir()?.join(".projectboard");
+            let token = resolve_token(&crate::config::Config::load(&pb_dir)?).map(|(token, _)| token);
+            GitHubClient::new(owner, repo, token).get_pull_request_status(pr_number).await
+        });
+        self.pr_check = Some(PrCheck { task_id, pr_number, handle });
+    }
+    
+    /// Turn a finished PR status request into a status message
+    async fn finish_pr_check(&mut self) {
+        if !self.pr_check.as_ref().is_some_and(|check| check.handle.is_finished()) {
+            return;
         }
+        let Some(check) = self.pr_check.take() else {
+            return;
+        };
+        self.status = Some(match check.handle.await {
+            Ok(Ok(status)) => format!("PR #{} for task {} is {}", check.pr_number, task_ref(check.task_id), status),
+            Ok(Err(e)) => format!("Could not check PR #{}: {:#}", check.pr_number, e),
+            Err(e) => format!("Could not check PR #{}: {}", check.pr_number, e),
+        });
+    }
+    
+    fn next_task(&mut self) {
+        if self.selected_task + 1 < self.selected_column_tasks().len() {
+            self.selected_task += 1;
+        }
+    }
+    
+    fn previous_task(&mut self) {
+        self.selected_task = self.selected_task.saturating_sub(1);
+    }
+}
+
+async fn load_wip_limits(db: &Database) -> Result<std::collections::HashMap<i64, i64>> {
+    Ok(db.get_columns_with_limits().await?
+        .into_iter()
+        .filter_map(|c| c.wip_limit.map(|limit| (c.column.id, limit)))
+        .collect())
+}
+
+async fn load_labels(db: &Database) -> Result<(Vec<String>, std::collections::HashMap<String, std::collections::HashSet<i64>>)> {
+    let labels = db.get_labels().await?.into_iter().map(|l| l.name).collect();
+    let mut label_tasks: std::collections::HashMap<_, std::collections::HashSet<_>> = std::collections::HashMap::new();
+    for task_label in db.get_all_task_labels().await? {
+        label_tasks.entry(task_label.name).or_default().insert(task_label.task_id);
+    }
+    Ok((labels, label_tasks))
+}
+
+async fn load_board(db: &Database) -> Result<(Vec<crate::db::Column>, std::collections::HashMap<i64, Vec<crate::db::Task>>)> {
+    let columns = db.get_columns().await?;
+    let mut tasks_by_column = std::collections::HashMap::new();
+    
+    for column in &columns {
+        let tasks = db.get_tasks(Some(column.id)).await?;
+        tasks_by_column.insert(column.id, tasks);
     }
+    
+    Ok((columns, tasks_by_column))
 }
 
-async fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result<()> {
+async fn run_app<B: Backend>(terminal: &mut Terminal<B>, db: &Database, app: &mut App) -> Result<()> {
     loop {
-        terminal.draw(|f| ui(f, &app))?;
+        app.finish_pr_check().await;
+        terminal.draw(|f| ui(f, app))?;
 
+        // Wake up now and then while a PR check is in flight so its result shows without a key press
+        if app.pr_check.is_some() && !event::poll(std::time::Duration::from_millis(100))? {
+            continue;
+        }
         if let Event::Key(key) = event::read()? {
+            // Status messages last until the next key press
+            app.status = None;
             match key.code {
                 KeyCode::Char('q') => return Ok(()),
                 KeyCode::Left => app.previous_column(),
                 KeyCode::Right => app.next_column(),
+                KeyCode::Down => app.next_task(),
+                KeyCode::Up => app.previous_task(),
+                KeyCode::Enter => app.show_detail = !app.show_detail,
+                KeyCode::Esc => app.show_detail = false,
+                KeyCode::Char('y') => app.copy_selected_branch(),
+                KeyCode::Char('s') => app.check_selected_pr(),
+                KeyCode::Char('f') => app.focused = !app.focused,
+                KeyCode::Char('>') => app.move_selected_task(db, true).await?,
+                KeyCode::Char('<') => app.move_selected_task(db, false).await?,
+                KeyCode::Char('p') => app.toggle_priority_sort(),
+                KeyCode::Char('o') => app.toggle_oldest_first(),
+                KeyCode::Char('u') => app.toggle_assignee_filter("@unassigned"),
+                KeyCode::Char('m') => app.toggle_assignee_filter("@me"),
+                KeyCode::Char('a') => {
+                    app.assignee_filter = AssigneeFilter::All;
+                    app.label_filter = None;
+                }
+                KeyCode::Char('l') => app.cycle_label_filter(),
                 KeyCode::Char('r') => {
                     // Refresh data
-                    app = App::new(app.db).await?;
+                    app.reload(db).await?;
                 }
                 _ => {}
             }
@@ -104,45 +611,133 @@ async fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result
     }
 }
 
-fn ui(f: &mut Frame, app: &App) {
+fn ui(f: &mut Frame, app: &mut App) {
     let size = f.size();
     
-    // Create layout with header and main content
+    // Create layout with header, main content, and footer
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .constraints([Constraint::Length(3), Constraint::Min(0), Constraint::Length(1)])
         .split(size);
     
     // Header
-    let header = Paragraph::new("ProjectBoard - Use ← → to navigate, 'r' to refresh, 'q' to quit")
+    let sort_label = match (app.sort_by_priority, app.oldest_first) {
+        (true, false) => "priority, newest first",
+        (true, true) => "priority, oldest first",
+        (false, false) => "newest first",
+        (false, true) => "oldest first",
+    };
+    let header = Paragraph::new(format!(
+        "ProjectBoard - Use ← → ↑ ↓ to navigate, Enter for details, 'p' to sort by priority, 'o' for oldest first (sorted by {}), 'r' to refresh, 'q' to quit",
+        sort_label
+    ))
         .style(Style::default().fg(Color::Cyan))
         .block(Block::default().borders(Borders::ALL).title("Help"));
     f.render_widget(header, chunks[0]);
     
-    // Main board
+    // Footer with the active filter
+    let filter_label = match &app.assignee_filter {
+        AssigneeFilter::All => "all tasks".to_string(),
+        AssigneeFilter::Unassigned => "unassigned".to_string(),
+        AssigneeFilter::User(user) => format!("assigned to {}", user),
+    };
+    let filter_label = match &app.label_filter {
+        Some(label) => {
+            let shown: usize = app.columns.iter().map(|c| app.column_tasks(c.id).len()).sum();
+            format!("{}, label '{}' ({} tasks)", filter_label, label, shown)
+        }
+        None => filter_label,
+    };
+    let footer = match (&app.status, &app.pr_check) {
+        (Some(status), _) => Paragraph::new(format!(" {}", crate::glyphs::render(status)))
+            .style(Style::default().fg(Color::Green)),
+        (None, Some(check)) => Paragraph::new(format!(" Checking PR #{} for task {}…", check.pr_number, task_ref(check.task_id)))
+            .style(Style::default().fg(Color::Yellow)),
+        (None, None) => Paragraph::new(format!(
+            " Filter: {} — 'u' unassigned, 'm' mine, 'l' label, 'a' all, '<' '>' move task, 'y' copy branch, 's' PR status, 'f' focus column",
+            filter_label
+        ))
+            .style(Style::default().fg(Color::DarkGray)),
+    };
+    f.render_widget(footer, chunks[2]);
+    
+    if app.columns.is_empty() {
+        let empty = Paragraph::new("This board has no columns. Run 'pb column add <name>' to create one, then press 'r'.")
+            .style(Style::default().fg(Color::Yellow))
+            .block(Block::default().borders(Borders::ALL).title("Board"));
+        f.render_widget(empty, chunks[1]);
+        return;
+    }
+    
+    // The detail panel takes the right side of the board area while open
+    let board_area = match app.selected_task().filter(|_| app.show_detail) {
+        Some(task) => {
+            let split = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .split(chunks[1]);
+            render_task_detail(f, task, split[1]);
+            split[0]
+        }
+        None => chunks[1],
+    };
+    
+    // Main board: show as many columns as fit, recomputed every draw so resizes are handled
+    let visible = if app.focused {
+        1
+    } else {
+        ((board_area.width / MIN_COLUMN_WIDTH) as usize).clamp(1, app.columns.len())
+    };
+    app.scroll_to_selected(visible);
+    let window = app.column_offset..app.column_offset + visible;
+    
     let board_layout = Layout::default()
         .direction(Direction::Horizontal)
         .constraints(
-            app.columns
-                .iter()
-                .map(|_| Constraint::Percentage((100 / app.columns.len()) as u16))
+            (0..visible)
+                .map(|_| Constraint::Ratio(1, visible as u32))
                 .collect::<Vec<_>>(),
         )
-        .split(chunks[1]);
+        .split(board_area);
     
-    for (i, column) in app.columns.iter().enumerate() {
-        let tasks = app.tasks_by_column.get(&column.id).unwrap_or(&vec![]);
+    let selected_task_id = app.selected_task().map(|t| t.id);
+    let now = chrono::Utc::now();
+    for (slot, (i, column)) in app.columns.iter().enumerate().skip(window.start).take(visible).enumerate() {
+        let tasks = app.column_tasks(column.id);
         
         let items: Vec<ListItem> = tasks
             .iter()
             .map(|task| {
-                let content = vec![Line::from(vec![
-                    Span::styled(
-                        format!("#{} ", task.id),
-                        Style::default().fg(Color::Yellow),
-                    ),
-                    Span::raw(&task.title),
-                ])];
+                // Urgent tasks get an accent so they stand out without reading every card
+                let mut title_style = match task.priority() {
+                    Some(Priority::Critical) => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                    Some(Priority::High) => Style::default().fg(Color::Rgb(255, 165, 0)),
+                    _ => Style::default(),
+                };
+                // Aging work fades and gets a cobweb
+                let stale = app.is_stale(task, now);
+                if stale {
+                    title_style = title_style.add_modifier(Modifier::DIM);
+                }
+                if i == app.selected_column && selected_task_id == Some(task.id) {
+                    title_style = title_style.add_modifier(Modifier::REVERSED);
+                }
+                let mut spans = vec![Span::styled(
+                    format!("{} ", task_ref(task.id)),
+                    Style::default().fg(Color::Yellow),
+                )];
+                if stale {
+                    spans.push(Span::raw(format!("{} ", crate::glyphs::icon("🕸"))));
+                }
+                spans.push(Span::styled(task.title.as_str(), title_style));
+                let mut content = vec![Line::from(spans)];
+                let details = app.card_details(task);
+                if !details.is_empty() {
+                    content.push(Line::from(Span::styled(
+                        format!("   {}", details.join(" · ")),
+                        Style::default().fg(Color::DarkGray),
+                    )));
+                }
                 ListItem::new(content)
             })
             .collect();
@@ -153,17 +748,68 @@ fn ui(f: &mut Frame, app: &App) {
             Style::default()
         };
         
+        // Arrows on the edge columns hint that more columns are off screen
+        let more_left = slot == 0 && window.start > 0;
+        let more_right = slot == visible - 1 && window.end < app.columns.len();
+        let (count, title_style) = match app.column_load(column.id) {
+            Some((count, limit)) => (
+                format!("{}/{}", count, limit),
+                if count as i64 >= limit { Style::default().fg(Color::Red) } else { Style::default() },
+            ),
+            None => (tasks.len().to_string(), Style::default()),
+        };
+        let title = Span::styled(
+            format!(
+                "{}{} ({}){}",
+                if more_left { format!("{} ", crate::glyphs::icon("◀")) } else { String::new() },
+                column.name,
+                count,
+                if more_right { format!(" {}", crate::glyphs::icon("▶")) } else { String::new() }
+            ),
+            title_style,
+        );
+        
         let list = List::new(items)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title(format!("{} ({})", column.name, tasks.len()))
+                    .title(title)
                     .border_style(style),
             )
             .style(Style::default().fg(Color::White));
         
-        f.render_widget(list, board_layout[i]);
+        f.render_widget(list, board_layout[slot]);
+    }
+}
+
+fn render_task_detail(f: &mut Frame, task: &crate::db::Task, area: Rect) {
+    let label = Style::default().fg(Color::DarkGray);
+    let mut lines = vec![
+        Line::from(Span::styled(task.title.clone(), Style::default().add_modifier(Modifier::BOLD))),
+        Line::from(vec![
+            Span::styled("Assignee: ", label),
+            Span::raw(task.assignee.clone().unwrap_or_else(|| "unassigned".to_string())),
+        ]),
+    ];
+    if let Some(priority) = task.priority() {
+        lines.push(Line::from(vec![Span::styled("Priority: ", label), Span::raw(priority.to_string())]));
+    }
+    if let Some(branch) = &task.branch_name {
+        lines.push(Line::from(vec![Span::styled("Branch:   ", label), Span::raw(branch.clone())]));
+    }
+    if let Some(pr) = &task.pr_url {
+        lines.push(Line::from(vec![Span::styled("PR:       ", label), Span::raw(pr.clone())]));
+    }
+    lines.push(Line::from(""));
+    match &task.description {
+        Some(description) => lines.extend(markdown::markdown_to_text(description).lines),
+        None => lines.push(Line::from(Span::styled("No description", label))),
     }
+    
+    let detail = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(Block::default().borders(Borders::ALL).title(format!("Task {} (Esc to close)", task_ref(task.id))));
+    f.render_widget(detail, area);
 }
 
 async fn get_database() -> Result<Database> {
@@ -176,3 +822,272 @@ async fn get_database() -> Result<Database> {
     
     Database::new(&db_path).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(id: i64, name: &str) -> crate::db::Column {
+        crate::db::Column { id, name: name.to_string(), order: id as i32, role: None }
+    }
+
+    fn task(id: i64, column_id: i64, title: &str, description: Option<&str>) -> crate::db::Task {
+        let now = chrono::Utc::now();
+        crate::db::Task {
+            id,
+            title: title.to_string(),
+            description: description.map(str::to_string),
+            column_id,
+            assignee: None,
+            created_at: now,
+            updated_at: now,
+            branch_name: None,
+            pr_url: None,
+            pr_number: None,
+            worktree_path: None,
+            recurrence: None,
+            priority: None,
+            archived_at: None,
+            deleted_at: None,
+            version: 1,
+        }
+    }
+
+    #[test]
+    fn test_ui_renders_without_columns() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 12)).unwrap();
+        let mut app = App::new(Vec::new(), std::collections::HashMap::new());
+
+        terminal.draw(|f| ui(f, &mut app)).unwrap();
+
+        let rendered: String = terminal.backend().buffer().content.iter().map(|c| c.symbol.as_str()).collect();
+        assert!(rendered.contains("no columns"));
+    }
+
+    #[test]
+    fn test_focus_mode_shows_only_the_selected_column() {
+        let mut terminal = Terminal::new(TestBackend::new(200, 12)).unwrap();
+        let mut app = App::new(
+            vec![column(1, "Backlog"), column(2, "Doing"), column(3, "Done")],
+            std::collections::HashMap::new(),
+        );
+        app.selected_column = 1;
+        app.focused = true;
+
+        terminal.draw(|f| ui(f, &mut app)).unwrap();
+        let rendered: String = terminal.backend().buffer().content.iter().map(|c| c.symbol.as_str()).collect();
+        assert!(rendered.contains("Doing"));
+        assert!(!rendered.contains("Backlog"));
+
+        app.focused = false;
+        terminal.draw(|f| ui(f, &mut app)).unwrap();
+        let rendered: String = terminal.backend().buffer().content.iter().map(|c| c.symbol.as_str()).collect();
+        assert!(rendered.contains("Backlog"));
+    }
+
+    #[test]
+    fn test_restore_ignores_missing_columns() {
+        let mut app = App::new(
+            vec![column(1, "Backlog"), column(2, "Doing"), column(3, "Done")],
+            std::collections::HashMap::new(),
+        );
+        app.restore(&TuiState { selected_column_id: Some(3), column_offset: 1 });
+        assert_eq!((app.selected_column, app.column_offset), (2, 1));
+
+        app.restore(&TuiState { selected_column_id: Some(42), column_offset: 0 });
+        assert_eq!(app.selected_column, 2);
+    }
+
+    #[test]
+    fn test_move_into_full_column_is_blocked() {
+        let tasks = std::collections::HashMap::from([
+            (1, vec![task(1, 1, "Ready", None)]),
+            (2, vec![task(2, 2, "Busy", None)]),
+        ]);
+        let mut app = App::new(vec![column(1, "To Do"), column(2, "Doing")], tasks);
+
+        assert_eq!(app.move_target(true), Some(Ok(1)));
+        assert_eq!(app.move_target(false), None);
+
+        app.wip_limits.insert(2, 1);
+        assert!(matches!(app.move_target(true), Some(Err(msg)) if msg.contains("(1/1)")));
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 10)).unwrap();
+        terminal.draw(|f| ui(f, &mut app)).unwrap();
+        let rendered: String = terminal.backend().buffer().content.iter().map(|c| c.symbol.as_str()).collect();
+        assert!(rendered.contains("Doing (1/1)"));
+    }
+
+    #[test]
+    fn test_card_fields_follow_config_order() {
+        let mut login = task(1, 1, "Login crash", None);
+        login.assignee = Some("ada".to_string());
+        login.priority = Some("high".to_string());
+        login.branch_name = Some("1-login-crash".to_string());
+        let tasks = std::collections::HashMap::from([(1, vec![login.clone(), task(2, 1, "Dark mode", None)])]);
+        let mut app = App::new(vec![column(1, "To Do")], tasks);
+        app.labels = vec!["bug".to_string(), "ui".to_string()];
+        app.label_tasks = std::collections::HashMap::from([
+            ("bug".to_string(), std::collections::HashSet::from([1])),
+            ("ui".to_string(), std::collections::HashSet::from([1])),
+        ]);
+
+        // Cards stay at ID and title unless fields are configured
+        assert!(app.card_details(&login).is_empty());
+
+        app.card_fields = vec![CardField::Priority, CardField::Labels, CardField::Assignee, CardField::Branch];
+        assert_eq!(app.card_details(&login), ["high", "bug, ui", "@ada", "1-login-crash"]);
+        assert!(app.card_details(&task(2, 1, "Dark mode", None)).is_empty());
+
+        let mut terminal = Terminal::new(TestBackend::new(200, 10)).unwrap();
+        terminal.draw(|f| ui(f, &mut app)).unwrap();
+        let rendered: String = terminal.backend().buffer().content.iter().map(|c| c.symbol.as_str()).collect();
+        assert!(rendered.contains("high · bug, ui · @ada · 1-login-crash"));
+    }
+
+    #[test]
+    fn test_label_filter_cycles_through_labels() {
+        let tasks = std::collections::HashMap::from([
+            (1, vec![task(1, 1, "Login crash", None), task(2, 1, "Dark mode", None)]),
+            (2, vec![task(3, 2, "Crash on save", None)]),
+        ]);
+        let mut app = App::new(vec![column(1, "To Do"), column(2, "Doing")], tasks);
+        app.labels = vec!["bug".to_string(), "ui".to_string()];
+        app.label_tasks = std::collections::HashMap::from([
+            ("bug".to_string(), std::collections::HashSet::from([1, 3])),
+            ("ui".to_string(), std::collections::HashSet::from([2])),
+        ]);
+        let titles = |app: &App, column_id| app.column_tasks(column_id).iter().map(|t| t.title.clone()).collect::<Vec<_>>();
+
+        app.cycle_label_filter();
+        assert_eq!(titles(&app, 1), ["Login crash"]);
+        assert_eq!(titles(&app, 2), ["Crash on save"]);
+
+        let mut terminal = Terminal::new(TestBackend::new(200, 10)).unwrap();
+        terminal.draw(|f| ui(f, &mut app)).unwrap();
+        let rendered: String = terminal.backend().buffer().content.iter().map(|c| c.symbol.as_str()).collect();
+        assert!(rendered.contains("label 'bug' (2 tasks)"));
+
+        app.cycle_label_filter();
+        assert_eq!(titles(&app, 1), ["Dark mode"]);
+        assert!(titles(&app, 2).is_empty());
+
+        app.cycle_label_filter();
+        assert_eq!(app.label_filter, None);
+        assert_eq!(titles(&app, 1).len(), 2);
+    }
+
+    #[test]
+    fn test_oldest_first_and_stale_marker() {
+        let mut old = task(1, 1, "Old task", None);
+        old.updated_at -= chrono::Duration::days(30);
+        // Loaded newest first, as load_board does
+        let tasks = std::collections::HashMap::from([
+            (1, vec![task(2, 1, "New task", None), old]),
+        ]);
+        let mut app = App::new(vec![column(1, "To Do")], tasks);
+        let titles = |app: &App| app.column_tasks(1).iter().map(|t| t.title.clone()).collect::<Vec<_>>();
+
+        assert_eq!(titles(&app), ["New task", "Old task"]);
+        app.toggle_oldest_first();
+        assert_eq!(titles(&app), ["Old task", "New task"]);
+
+        let mut terminal = Terminal::new(TestBackend::new(250, 10)).unwrap();
+        terminal.draw(|f| ui(f, &mut app)).unwrap();
+        let rendered: String = terminal.backend().buffer().content.iter().map(|c| c.symbol.as_str()).collect();
+        assert!(!rendered.contains('🕸'));
+
+        app.stale_after = Some(chrono::Duration::days(14));
+        terminal.draw(|f| ui(f, &mut app)).unwrap();
+        let rendered: String = terminal.backend().buffer().content.iter().map(|c| c.symbol.as_str()).collect();
+        assert_eq!(rendered.matches('🕸').count(), 1);
+        assert!(rendered.contains("oldest first"));
+    }
+
+    #[test]
+    fn test_terminal_guard_restores_on_panic() {
+        let restored = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let flag = restored.clone();
+
+        let result = std::panic::catch_unwind(move || {
+            let _guard = TerminalGuard { restore: || flag.store(true, std::sync::atomic::Ordering::SeqCst) };
+            panic!("simulated draw failure");
+        });
+
+        assert!(result.is_err());
+        assert!(restored.load(std::sync::atomic::Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_snapshot_fits_every_column_and_task() {
+        let columns: Vec<_> = (1..=8).map(|id| column(id, &format!("Column {}", id))).collect();
+        let tasks = (1..=5).map(|id| task(id, 8, &format!("Task {} 🚀", id), None)).collect();
+        let mut app = App::new(columns, std::collections::HashMap::from([(8, tasks)]));
+
+        let text = render_snapshot(&mut app).unwrap();
+        assert!(text.contains("Column 1") && text.contains("Column 8"));
+        assert!((1..=5).all(|id| text.contains(&format!("#{} Task {} 🚀", id, id))));
+        assert!(text.lines().all(|line| line == line.trim_end()));
+        // Padding behind the wide emoji is not copied out, so borders still line up
+        let line = text.lines().find(|line| line.contains("Task 1")).unwrap();
+        assert_eq!(line.width(), text.lines().next().unwrap().width());
+    }
+
+    #[tokio::test]
+    async fn test_pr_check_shows_checking_then_result() {
+        let mut app = App::new(vec![column(1, "Review")], std::collections::HashMap::new());
+        let (send, receive) = tokio::sync::oneshot::channel::<()>();
+        let handle = tokio::spawn(async move {
+            receive.await?;
+            Ok(PullRequestStatus::Merged)
+        });
+        app.pr_check = Some(PrCheck { task_id: 3, pr_number: 12, handle });
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 10)).unwrap();
+        app.finish_pr_check().await;
+        terminal.draw(|f| ui(f, &mut app)).unwrap();
+        let rendered: String = terminal.backend().buffer().content.iter().map(|c| c.symbol.as_str()).collect();
+        assert!(rendered.contains("Checking PR #12 for task #3"));
+
+        send.send(()).unwrap();
+        while !app.pr_check.as_ref().unwrap().handle.is_finished() {
+            tokio::task::yield_now().await;
+        }
+        app.finish_pr_check().await;
+        assert!(app.pr_check.is_none());
+        assert_eq!(app.status.as_deref(), Some("PR #12 for task #3 is merged"));
+    }
+
+    #[test]
+    fn test_navigation_is_clamped() {
+        let mut app = App::new(Vec::new(), std::collections::HashMap::new());
+        app.next_column();
+        app.previous_column();
+        assert_eq!(app.selected_column, 0);
+
+        app.columns = vec![column(1, "Backlog"), column(2, "Done")];
+        app.next_column();
+        app.next_column();
+        assert_eq!(app.selected_column, 1);
+    }
+
+    #[test]
+    fn test_detail_panel_renders_markdown_description() {
+        let mut terminal = Terminal::new(TestBackend::new(120, 16)).unwrap();
+        let tasks = std::collections::HashMap::from([(
+            1,
+            vec![task(1, 1, "First", None), task(2, 1, "Second", Some("## Notes\n- **ship** it"))],
+        )]);
+        let mut app = App::new(vec![column(1, "Backlog")], tasks);
+        app.next_task();
+        app.show_detail = true;
+
+        terminal.draw(|f| ui(f, &mut app)).unwrap();
+
+        let rendered: String = terminal.backend().buffer().content.iter().map(|c| c.symbol.as_str()).collect();
+        assert!(rendered.contains("Task #2"));
+        assert!(rendered.contains("Notes"));
+        assert!(!rendered.contains("## Notes"));
+        assert!(rendered.contains("• ship it"));
+    }
+}
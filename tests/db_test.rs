@@ -0,0 +1,442 @@
+use projectboard_cli::db::{check_task_prefix, ActivityFilter, AssigneeFilter, ColumnRole, Conflict, Database, TaskId, TaskQuery};
+use tempfile::TempDir;
+
+async fn setup_database(dir: &TempDir) -> Database {
+    let db_path = dir.path().join("board.sqlite");
+    std::fs::File::create(&db_path).expect("Failed to create database file");
+
+    let db = Database::new(&db_path).await.expect("Failed to open database");
+    db.migrate().await.expect("Failed to run migrations");
+    db.create_default_columns().await.expect("Failed to create columns");
+    db
+}
+
+fn column_names(columns: &[projectboard_cli::db::Column]) -> Vec<&str> {
+    columns.iter().map(|c| c.name.as_str()).collect()
+}
+
+#[tokio::test]
+async fn test_reorder_first_column_to_last() {
+    let dir = TempDir::new().unwrap();
+    let db = setup_database(&dir).await;
+
+    let backlog = db.get_column_by_name("Backlog").await.unwrap().unwrap();
+    db.reorder_column(backlog.id, 4).await.unwrap();
+
+    let columns = db.get_columns().await.unwrap();
+    assert_eq!(column_names(&columns), ["To Do", "Doing", "Review", "Done", "Backlog"]);
+    let orders: Vec<i32> = columns.iter().map(|c| c.order).collect();
+    assert_eq!(orders, [0, 1, 2, 3, 4]);
+}
+
+#[tokio::test]
+async fn test_reorder_last_column_to_first() {
+    let dir = TempDir::new().unwrap();
+    let db = setup_database(&dir).await;
+
+    let done = db.get_column_by_name("Done").await.unwrap().unwrap();
+    db.reorder_column(done.id, 0).await.unwrap();
+
+    let columns = db.get_columns().await.unwrap();
+    assert_eq!(column_names(&columns), ["Done", "Backlog", "To Do", "Doing", "Review"]);
+    let orders: Vec<i32> = columns.iter().map(|c| c.order).collect();
+    assert_eq!(orders, [0, 1, 2, 3, 4]);
+}
+
+#[tokio::test]
+async fn test_columns_with_limits_flags_overloaded_columns() {
+    let dir = TempDir::new().unwrap();
+    let db = setup_database(&dir).await;
+
+    let doing = db.get_column_by_name("Doing").await.unwrap().unwrap();
+    db.set_column_limit(doing.id, Some(1)).await.unwrap();
+    db.create_task("First", None, doing.id, None).await.unwrap();
+    db.create_task("Second", None, doing.id, None).await.unwrap();
+
+    let columns = db.get_columns_with_limits().await.unwrap();
+    let doing = columns.iter().find(|c| c.column.name == "Doing").unwrap();
+    assert_eq!(doing.wip_limit, Some(1));
+    assert_eq!(doing.task_count, 2);
+    assert!(doing.is_over_limit());
+    assert!(columns.iter().filter(|c| c.column.name != "Doing").all(|c| !c.is_over_limit()));
+
+    db.set_column_limit(doing.column.id, None).await.unwrap();
+    let columns = db.get_columns_with_limits().await.unwrap();
+    assert!(columns.iter().all(|c| c.wip_limit.is_none()));
+}
+
+#[tokio::test]
+async fn test_task_id_beyond_u32_is_a_clean_miss() {
+    let dir = TempDir::new().unwrap();
+    let db = setup_database(&dir).await;
+
+    let id: TaskId = "4294967296".parse().unwrap();
+    assert_eq!(id.get(), u32::MAX as i64 + 1);
+    assert!(db.get_task(id).await.unwrap().is_none());
+
+    assert!("0".parse::<TaskId>().is_err());
+    assert!("-3".parse::<TaskId>().is_err());
+    assert!("99999999999999999999".parse::<TaskId>().is_err());
+}
+
+#[test]
+fn test_task_id_parses_bare_hash_and_prefixed_forms() {
+    let ids: Vec<i64> = ["12", "#12", "PROJ-12", "proj-12", " PROJ-12 "].iter()
+        .map(|s| s.parse::<TaskId>().unwrap().get())
+        .collect();
+    assert_eq!(ids, [12; 5]);
+
+    assert!("PROJ-".parse::<TaskId>().is_err());
+    assert!("PROJ-0".parse::<TaskId>().is_err());
+    assert!("-PROJ-12".parse::<TaskId>().is_err());
+    assert!("1-12".parse::<TaskId>().is_err());
+    assert!("#PROJ-12".parse::<TaskId>().is_err());
+}
+
+#[test]
+fn test_task_prefix_must_parse_back() {
+    for prefix in ["PROJ", "web2", "MY_PROJ", ""] {
+        assert!(check_task_prefix(prefix).is_ok(), "{}", prefix);
+    }
+    for prefix in ["MY-PROJ", "1ABC", "_PROJ", "PRÖJ"] {
+        assert!(check_task_prefix(prefix).is_err(), "{}", prefix);
+    }
+}
+
+#[tokio::test]
+async fn test_compute_readiness_tracks_open_blockers() {
+    let dir = TempDir::new().unwrap();
+    let db = setup_database(&dir).await;
+
+    let backlog = db.get_column_by_name("Backlog").await.unwrap().unwrap();
+    let done = db.get_column_by_name("Done").await.unwrap().unwrap();
+    let mut schema = db.create_task("Schema", None, backlog.id, None).await.unwrap();
+    let api = db.create_task("API", None, backlog.id, None).await.unwrap();
+    let ui = db.create_task("UI", None, backlog.id, None).await.unwrap();
+    db.add_dependency(api.id, schema.id).await.unwrap();
+    db.add_dependency(ui.id, api.id).await.unwrap();
+    db.add_dependency(ui.id, schema.id).await.unwrap();
+    assert!(!db.add_dependency(ui.id, schema.id).await.unwrap());
+
+    let blockers = |readiness: &[projectboard_cli::db::TaskReadiness], id: i64| {
+        readiness.iter().find(|r| r.task.id == id).map(|r| r.open_blockers.clone())
+    };
+    let readiness = db.compute_readiness().await.unwrap();
+    assert_eq!(blockers(&readiness, schema.id), Some(vec![]));
+    assert_eq!(blockers(&readiness, api.id), Some(vec![schema.id]));
+    assert_eq!(blockers(&readiness, ui.id), Some(vec![schema.id, api.id]));
+
+    // Finishing a blocker unblocks its dependents, and Done tasks drop out
+    db.update_task_column(&mut schema, done.id).await.unwrap();
+    let readiness = db.compute_readiness().await.unwrap();
+    assert_eq!(blockers(&readiness, schema.id), None);
+    assert_eq!(blockers(&readiness, api.id), Some(vec![]));
+    assert_eq!(blockers(&readiness, ui.id), Some(vec![api.id]));
+}
+
+#[tokio::test]
+async fn test_archived_assigned_task_is_hidden_unless_requested() {
+    let dir = TempDir::new().unwrap();
+    let db = setup_database(&dir).await;
+
+    let doing = db.get_column_by_name("Doing").await.unwrap().unwrap();
+    let active = db.create_task("Active", None, doing.id, Some("alice")).await.unwrap();
+    let mut archived = db.create_task("Archived", None, doing.id, Some("alice")).await.unwrap();
+    let mut deleted = db.create_task("Deleted", None, doing.id, Some("alice")).await.unwrap();
+    db.create_task("Someone else's", None, doing.id, Some("bob")).await.unwrap();
+    db.archive_task(&mut archived).await.unwrap();
+    db.delete_task(&mut deleted).await.unwrap();
+
+    let mine = TaskQuery { assignee: AssigneeFilter::User("alice".to_string()), ..TaskQuery::default() };
+    let ids = |tasks: Vec<projectboard_cli::db::Task>| tasks.iter().map(|t| t.id).collect::<Vec<_>>();
+
+    assert_eq!(ids(db.query_tasks(&mine).await.unwrap()), [active.id]);
+    let with_archived = TaskQuery { include_archived: true, ..mine.clone() };
+    assert_eq!(ids(db.query_tasks(&with_archived).await.unwrap()), [archived.id, active.id]);
+
+    // The other views agree with query_tasks
+    assert_eq!(db.count_tasks(doing.id).await.unwrap(), 2);
+    assert!(db.search_tasks("Archived").await.unwrap().is_empty());
+
+    db.restore_task(&mut deleted).await.unwrap();
+    assert_eq!(ids(db.query_tasks(&mine).await.unwrap()), [deleted.id, active.id]);
+}
+
+#[tokio::test]
+async fn test_query_tasks_by_unassigned() {
+    let dir = TempDir::new().unwrap();
+    let db = setup_database(&dir).await;
+
+    let todo = db.get_column_by_name("To Do").await.unwrap().unwrap();
+    db.create_task("Taken", None, todo.id, Some("alice")).await.unwrap();
+    let open = db.create_task("Open", None, todo.id, None).await.unwrap();
+
+    let unassigned = TaskQuery { assignee: AssigneeFilter::Unassigned, ..TaskQuery::default() };
+    let tasks = db.query_tasks(&unassigned).await.unwrap();
+    assert_eq!(tasks.iter().map(|t| t.id).collect::<Vec<_>>(), [open.id]);
+    assert_eq!(db.query_tasks(&TaskQuery::default()).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_move_then_undo_from_task_activity() {
+    let dir = TempDir::new().unwrap();
+    let db = setup_database(&dir).await;
+
+    let todo = db.get_column_by_name("To Do").await.unwrap().unwrap();
+    let doing = db.get_column_by_name("Doing").await.unwrap().unwrap();
+    let mut task = db.create_task("Task", None, todo.id, None).await.unwrap();
+    let other = db.create_task("Other", None, todo.id, None).await.unwrap();
+
+    db.update_task_column(&mut task, doing.id).await.unwrap();
+    db.log_task_activity("task_moved", task.id, Some(("To Do", &doing)), None).await.unwrap();
+    db.log_task_activity("task_cloned", other.id, None, None).await.unwrap();
+
+    // The other task's later event doesn't hide this task's last move
+    let filter = ActivityFilter { limit: Some(1), task_id: Some(task.id), ..ActivityFilter::default() };
+    let last = db.query_activity(&filter).await.unwrap();
+    assert_eq!(last.len(), 1);
+    assert_eq!(last[0].event, "task_moved");
+    assert_eq!(last[0].column_to.as_deref(), Some("Doing"));
+    assert_eq!(last[0].column_to_id, Some(doing.id));
+
+    let previous = db.get_column_by_name(last[0].column_from.as_deref().unwrap()).await.unwrap().unwrap();
+    db.update_task_column(&mut task, previous.id).await.unwrap();
+    assert!(db.get_tasks(Some(todo.id)).await.unwrap().iter().any(|t| t.id == task.id));
+}
+
+#[tokio::test]
+async fn test_get_task_with_details() {
+    let dir = TempDir::new().unwrap();
+    let db = setup_database(&dir).await;
+
+    let todo = db.get_column_by_name("To Do").await.unwrap().unwrap();
+    let task = db.create_task("Task", None, todo.id, None).await.unwrap();
+    let blocker = db.create_task("Blocker", None, todo.id, None).await.unwrap();
+    db.add_dependency(task.id, blocker.id).await.unwrap();
+    let label = db.create_label("bug").await.unwrap();
+    db.add_task_label(task.id, label.id).await.unwrap();
+    db.create_comment(task.id, "alice", "Looks good").await.unwrap();
+
+    let detail = db.get_task_with_details(task.id).await.unwrap().unwrap();
+    assert_eq!(detail.task.title, "Task");
+    assert_eq!(detail.labels.len(), 1);
+    assert_eq!(detail.comments.len(), 1);
+    assert_eq!(detail.blocked_by.iter().map(|t| t.id).collect::<Vec<_>>(), [blocker.id]);
+    assert!(detail.links.is_empty());
+
+    assert!(db.get_task_with_details(9999).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_column_roles_survive_renames() {
+    let dir = TempDir::new().unwrap();
+    let db = setup_database(&dir).await;
+
+    let done = db.get_column_by_role(ColumnRole::Done).await.unwrap().unwrap();
+    db.rename_column(done.id, "Shipped").await.unwrap();
+    let shipped = db.get_column_by_role(ColumnRole::Done).await.unwrap().unwrap();
+    assert_eq!((shipped.id, shipped.name.as_str()), (done.id, "Shipped"));
+
+    // A role belongs to one column at a time
+    let review = db.get_column_by_name("Review").await.unwrap().unwrap();
+    db.set_column_role(review.id, ColumnRole::Done).await.unwrap();
+    assert_eq!(db.get_column_by_role(ColumnRole::Done).await.unwrap().unwrap().id, review.id);
+    assert_eq!(db.get_column_by_name("Shipped").await.unwrap().unwrap().role(), None);
+    assert!(db.get_column_by_role(ColumnRole::Review).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_touch_clears_staleness() {
+    let dir = TempDir::new().unwrap();
+    let db = setup_database(&dir).await;
+
+    let doing = db.get_column_by_name("Doing").await.unwrap().unwrap();
+    let mut task = db.create_task("Forgotten", None, doing.id, None).await.unwrap();
+    let stale = |cutoff| TaskQuery { updated_before: Some(cutoff), ..TaskQuery::default() };
+
+    let later = chrono::Utc::now() + chrono::Duration::seconds(1);
+    assert_eq!(db.query_tasks(&stale(later)).await.unwrap().len(), 1);
+    assert!(db.query_tasks(&stale(task.updated_at)).await.unwrap().is_empty());
+
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    db.touch_task(&mut task).await.unwrap();
+    assert!(db.query_tasks(&stale(later)).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_assignee_stats_skip_done_and_group_unassigned() {
+    let dir = TempDir::new().unwrap();
+    let db = setup_database(&dir).await;
+
+    let backlog = db.get_column_by_name("Backlog").await.unwrap().unwrap();
+    let doing = db.get_column_by_name("Doing").await.unwrap().unwrap();
+    let done = db.get_column_by_name("Done").await.unwrap().unwrap();
+    db.create_task("Login", None, doing.id, Some("bob")).await.unwrap();
+    db.create_task("Docs", None, backlog.id, Some("bob")).await.unwrap();
+    db.create_task("Shipped", None, done.id, Some("bob")).await.unwrap();
+    db.create_task("Search", None, doing.id, Some("alice")).await.unwrap();
+    db.create_task("Triage", None, backlog.id, None).await.unwrap();
+
+    let stats = db.get_assignee_stats().await.unwrap();
+    let rows: Vec<_> = stats.iter().map(|s| (s.assignee.as_deref(), s.open, s.in_progress)).collect();
+    assert_eq!(rows, [(Some("alice"), 1, 1), (Some("bob"), 2, 1), (None, 1, 0)]);
+}
+
+#[tokio::test]
+async fn test_stream_tasks_includes_hidden_tasks_on_request() {
+    use futures::TryStreamExt;
+
+    let dir = TempDir::new().unwrap();
+    let db = setup_database(&dir).await;
+
+    let backlog = db.get_column_by_name("Backlog").await.unwrap().unwrap();
+    db.create_task("Active", None, backlog.id, None).await.unwrap();
+    let mut archived = db.create_task("Archived", None, backlog.id, None).await.unwrap();
+    let mut deleted = db.create_task("Deleted", None, backlog.id, None).await.unwrap();
+    db.archive_task(&mut archived).await.unwrap();
+    db.delete_task(&mut deleted).await.unwrap();
+
+    let titles = |include_archived, include_deleted| {
+        let db = &db;
+        async move {
+            let tasks: Vec<_> = db.stream_tasks(include_archived, include_deleted).try_collect().await.unwrap();
+            let mut titles: Vec<_> = tasks.into_iter().map(|t| t.title).collect();
+            titles.sort();
+            titles
+        }
+    };
+    assert_eq!(titles(false, false).await, ["Active"]);
+    assert_eq!(titles(true, false).await, ["Active", "Archived"]);
+    assert_eq!(titles(true, true).await, ["Active", "Archived", "Deleted"]);
+}
+
+#[tokio::test]
+async fn test_logged_time_adds_up_per_task_and_column() {
+    let dir = TempDir::new().unwrap();
+    let db = setup_database(&dir).await;
+
+    let doing = db.get_column_by_name("Doing").await.unwrap().unwrap();
+    let login = db.create_task("Login", None, doing.id, Some("bob")).await.unwrap();
+    let search = db.create_task("Search", None, doing.id, None).await.unwrap();
+    let mut dropped = db.create_task("Dropped", None, doing.id, Some("bob")).await.unwrap();
+    db.log_time(login.id, 90).await.unwrap();
+    db.log_time(login.id, 30).await.unwrap();
+    db.log_time(search.id, 15).await.unwrap();
+    db.log_time(dropped.id, 60).await.unwrap();
+    db.delete_task(&mut dropped).await.unwrap();
+
+    assert_eq!(db.get_logged_minutes(login.id).await.unwrap(), 120);
+    let details = db.get_task_with_details(search.id).await.unwrap().unwrap();
+    assert_eq!(details.logged_minutes, 15);
+
+    let logged = db.get_logged_time().await.unwrap();
+    let rows: Vec<_> = logged.iter().map(|l| (l.column_id, l.assignee.as_deref(), l.minutes)).collect();
+    assert_eq!(rows, [(doing.id, Some("bob"), 120), (doing.id, None, 15)]);
+}
+
+#[tokio::test]
+async fn test_resolving_comments_reports_changes_only() {
+    let dir = TempDir::new().unwrap();
+    let db = setup_database(&dir).await;
+
+    let backlog = db.get_column_by_name("Backlog").await.unwrap().unwrap();
+    let task = db.create_task("Review login", None, backlog.id, None).await.unwrap();
+    let comment = db.create_comment(task.id, "bob", "Missing a test").await.unwrap();
+    assert!(!comment.resolved);
+
+    assert!(db.set_comment_resolved(comment.id, true).await.unwrap());
+    assert!(!db.set_comment_resolved(comment.id, true).await.unwrap());
+    assert!(db.get_comment(comment.id).await.unwrap().unwrap().resolved);
+
+    assert!(db.set_comment_resolved(comment.id, false).await.unwrap());
+    assert!(!db.get_comment(comment.id).await.unwrap().unwrap().resolved);
+}
+
+#[tokio::test]
+async fn test_comments_filtered_by_author_and_date() {
+    use projectboard_cli::db::CommentFilter;
+
+    let dir = TempDir::new().unwrap();
+    let db = setup_database(&dir).await;
+
+    let backlog = db.get_column_by_name("Backlog").await.unwrap().unwrap();
+    let task = db.create_task("Chatty", None, backlog.id, None).await.unwrap();
+    db.create_comment(task.id, "bob", "First").await.unwrap();
+    let cutoff = chrono::Utc::now();
+    db.create_comment(task.id, "alice", "Second").await.unwrap();
+    db.create_comment(task.id, "bob", "Third").await.unwrap();
+
+    let texts = |comments: Vec<projectboard_cli::db::Comment>| comments.into_iter().map(|c| c.text).collect::<Vec<_>>();
+    let all = db.get_comments_filtered(task.id, &CommentFilter::default()).await.unwrap();
+    assert_eq!(texts(all), ["First", "Second", "Third"]);
+
+    let by_bob = CommentFilter { author: Some("bob".to_string()), ..CommentFilter::default() };
+    assert_eq!(texts(db.get_comments_filtered(task.id, &by_bob).await.unwrap()), ["First", "Third"]);
+
+    let recent_bob = CommentFilter { since: Some(cutoff), ..by_bob };
+    assert_eq!(texts(db.get_comments_filtered(task.id, &recent_bob).await.unwrap()), ["Third"]);
+}
+
+#[tokio::test]
+async fn test_stale_update_is_a_conflict() {
+    let dir = TempDir::new().unwrap();
+    let db = setup_database(&dir).await;
+
+    let todo = db.get_column_by_name("To Do").await.unwrap().unwrap();
+    let doing = db.get_column_by_name("Doing").await.unwrap().unwrap();
+    let mut task = db.create_task("Task", None, todo.id, None).await.unwrap();
+    let mut stale = task.clone();
+
+    // Updates made through the same copy keep up with their own version bumps
+    db.update_task_assignee(&mut task, Some("alice")).await.unwrap();
+    db.update_task_column(&mut task, doing.id).await.unwrap();
+    assert_eq!(task.version, stale.version + 2);
+
+    let err = db.update_task_column(&mut stale, todo.id).await.unwrap_err();
+    assert_eq!(err.downcast_ref::<Conflict>(), Some(&Conflict { task_id: task.id }));
+
+    // The stale copy didn't overwrite the move; once refreshed it can update again
+    db.refresh_task(&mut stale).await.unwrap();
+    assert_eq!((stale.column_id, stale.version), (doing.id, task.version));
+    db.update_task_column(&mut stale, todo.id).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_create_task_with_labels_creates_missing_labels() {
+    let dir = TempDir::new().unwrap();
+    let db = setup_database(&dir).await;
+
+    let backlog = db.get_column_by_name("Backlog").await.unwrap().unwrap();
+    db.create_label("bug").await.unwrap();
+    let labels = ["bug".to_string(), "urgent".to_string()];
+    let (task, created) = db.create_task_with_labels("Task", None, backlog.id, None, &labels).await.unwrap();
+
+    let names = |labels: Vec<projectboard_cli::db::Label>| labels.into_iter().map(|l| l.name).collect::<Vec<_>>();
+    assert_eq!(names(created), ["urgent"]);
+    assert_eq!(names(db.get_task_labels(task.id).await.unwrap()), ["bug", "urgent"]);
+    assert_eq!(names(db.get_labels().await.unwrap()), ["bug", "urgent"]);
+}
+
+#[tokio::test]
+async fn test_count_tasks_by_column_skips_hidden_tasks() {
+    let dir = TempDir::new().unwrap();
+    let db = setup_database(&dir).await;
+
+    let todo = db.get_column_by_name("To Do").await.unwrap().unwrap();
+    let doing = db.get_column_by_name("Doing").await.unwrap().unwrap();
+    db.create_task("One", None, todo.id, Some("alice")).await.unwrap();
+    db.create_task("Two", None, todo.id, None).await.unwrap();
+    db.create_task("Three", None, doing.id, Some("alice")).await.unwrap();
+    let mut archived = db.create_task("Archived", None, doing.id, None).await.unwrap();
+    db.archive_task(&mut archived).await.unwrap();
+
+    let sorted = |counts: Vec<projectboard_cli::db::ColumnCount>| {
+        let mut counts: Vec<(i64, i64)> = counts.into_iter().map(|c| (c.column_id, c.count)).collect();
+        counts.sort();
+        counts
+    };
+    let alice = AssigneeFilter::User("alice".to_string());
+    assert_eq!(sorted(db.count_tasks_by_column(&AssigneeFilter::All).await.unwrap()), [(todo.id, 2), (doing.id, 1)]);
+    assert_eq!(sorted(db.count_tasks_by_column(&alice).await.unwrap()), [(todo.id, 1), (doing.id, 1)]);
+    assert_eq!(sorted(db.count_tasks_by_column(&AssigneeFilter::Unassigned).await.unwrap()), [(todo.id, 1)]);
+}
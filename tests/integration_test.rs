@@ -27,3 +27,112 @@ fn test_version_command() {
     let stdout = String::from_utf8(output.stdout).unwrap();
     assert!(stdout.contains("0.1.0"));
 }
+
+fn init_in(repo: &Path) -> std::process::Output {
+    git2::Repository::init(repo).expect("Failed to create git repository");
+
+    Command::new(env!("CARGO_BIN_EXE_pb"))
+        .arg("init")
+        .current_dir(repo)
+        .output()
+        .expect("Failed to execute command")
+}
+
+#[test]
+fn test_init_in_directory_with_unusual_characters() {
+    let dir = TempDir::new().unwrap();
+    let repo = dir.path().join("board #1 (café?) & more");
+
+    let output = init_in(&repo);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(repo.join(".projectboard").join("board.sqlite").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_init_in_non_utf8_directory_fails_cleanly() {
+    use std::os::unix::ffi::OsStrExt;
+
+    let dir = TempDir::new().unwrap();
+    let repo = dir.path().join(std::ffi::OsStr::from_bytes(b"caf\xe9"));
+
+    let output = init_in(&repo);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("needs a UTF-8 path"), "{}", stderr);
+    assert!(!repo.join(".projectboard").exists());
+}
+
+fn pb(repo: &Path, args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_pb"))
+        .args(args)
+        .current_dir(repo)
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "pb {:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_move_triggers_git_only_for_tasks_with_branches() {
+    let dir = TempDir::new().unwrap();
+    let repo_path = dir.path();
+    assert!(init_in(repo_path).status.success());
+
+    let repo = git2::Repository::open(repo_path).unwrap();
+    let mut config = repo.config().unwrap();
+    config.set_str("user.name", "Tester").unwrap();
+    config.set_str("user.email", "tester@example.com").unwrap();
+    repo.remote("origin", "https://github.com/owner/repo.git").unwrap();
+    let signature = repo.signature().unwrap();
+    let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+    repo.commit(Some("HEAD"), &signature, &signature, "Initial commit", &tree, &[]).unwrap();
+    std::fs::write(repo_path.join(".projectboard").join("config.toml"), "move_triggers_git = true\n").unwrap();
+
+    pb(repo_path, &["add", "Branch me"]);
+    pb(repo_path, &["add", "Paperwork"]);
+    let staged = |name: &str| {
+        std::fs::write(repo_path.join(name), "change").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(name)).unwrap();
+        index.write().unwrap();
+    };
+
+    // Entering Doing creates and checks out the branch, like pb start
+    pb(repo_path, &["move", "1", "Doing"]);
+    assert_eq!(repo.head().unwrap().shorthand(), Some("feature/1-branch-me"));
+
+    // A task started without a branch goes to Done without committing what's staged
+    pb(repo_path, &["start", "2", "--no-branch"]);
+    staged("notes.txt");
+    let head = repo.head().unwrap().target();
+    pb(repo_path, &["move", "2", "Done"]);
+    assert_eq!(repo.head().unwrap().target(), head);
+
+    // The branch's task commits and pushes, and the move's activity says so
+    pb(repo_path, &["move", "1", "Done"]);
+    assert_ne!(repo.head().unwrap().target(), head);
+    let log = pb(repo_path, &["log", "--event", "task_moved"]);
+    assert!(log.contains("committed \"Closes #1: Branch me\", pushed feature/1-branch-me to origin"), "{}", log);
+    assert!(log.contains("Task #2: Doing → Done\n"), "{}", log);
+}
+
+#[test]
+fn test_list_stale_rejects_non_positive_ages() {
+    let dir = TempDir::new().unwrap();
+    let repo_path = dir.path();
+    assert!(init_in(repo_path).status.success());
+    pb(repo_path, &["add", "Fresh"]);
+
+    // An age in the future would flag every task as stale
+    for age in ["--stale=-3d", "--stale=0h"] {
+        let output = Command::new(env!("CARGO_BIN_EXE_pb"))
+            .args(["list", age])
+            .current_dir(repo_path)
+            .output()
+            .expect("Failed to execute command");
+        assert!(!output.status.success(), "pb list {} succeeded", age);
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid"), "{}", String::from_utf8_lossy(&output.stderr));
+    }
+    assert!(!pb(repo_path, &["list", "--stale", "1d"]).contains("Fresh"));
+}